@@ -0,0 +1,46 @@
+//! Compares the pointer-based `RbTreeMap` with the arena-backed `ArenaRbTreeMap`.
+//!
+//! Run with `cargo bench --bench arena`.
+
+mod common;
+
+use rb_tree::{ArenaRbTreeMap, RbTreeMap};
+
+use common::measure;
+use std::hint::black_box;
+
+const LEN: u64 = 200_000;
+const ROUNDS: u32 = 20;
+
+/// Returns keys in a scrambled order, so that nodes are not allocated in key order.
+fn scrambled_keys() -> impl Iterator<Item = u64> {
+    // multiplying by an odd number is a permutation modulo a power of two.
+    let modulo = LEN.next_power_of_two();
+    (0..modulo)
+        .map(move |i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) % modulo)
+        .filter(|&k| k < LEN)
+}
+
+fn run(order: &str, keys: &[u64]) {
+    let map: RbTreeMap<u64, u64> = keys.iter().map(|&k| (k, k)).collect();
+    let arena: ArenaRbTreeMap<u64, u64> = keys.iter().map(|&k| (k, k)).collect();
+
+    println!("inserted in {order} order:");
+    measure("pointer iteration", LEN, ROUNDS, || {
+        black_box(map.iter().map(|(_, v)| v).sum::<u64>());
+    });
+    measure("arena iteration", LEN, ROUNDS, || {
+        black_box(arena.iter().map(|(_, v)| v).sum::<u64>());
+    });
+    measure("pointer lookup", LEN, ROUNDS, || {
+        black_box((0..LEN).filter_map(|k| map.get(&k)).sum::<u64>());
+    });
+    measure("arena lookup", LEN, ROUNDS, || {
+        black_box((0..LEN).filter_map(|k| arena.get(&k)).sum::<u64>());
+    });
+}
+
+fn main() {
+    run("ascending", &(0..LEN).collect::<Vec<_>>());
+    run("scrambled", &scrambled_keys().collect::<Vec<_>>());
+}
@@ -0,0 +1,19 @@
+//! The helpers shared by the benches.
+
+use std::time::{Duration, Instant};
+
+/// Runs `f` once to warm up, then `rounds` times, and reports the mean time of a round for `len` items.
+pub fn measure(name: &str, len: u64, rounds: u32, mut f: impl FnMut()) {
+    f();
+    let start = Instant::now();
+    for _ in 0..rounds {
+        f();
+    }
+    report(name, len, start.elapsed() / rounds);
+}
+
+/// Prints the time of a round and the time per item for `len` items.
+pub fn report(name: &str, len: u64, elapsed: Duration) {
+    let per_item = elapsed.as_secs_f64() * 1e9 / len as f64;
+    println!("{name:<24} {:>10.3?} ({per_item:.2} ns/item)", elapsed);
+}
@@ -0,0 +1,34 @@
+//! Compares `contains_key` with `get(..).is_some()` for the present and the absent keys. `contains_key` stops at the node found without reading the value, but the descent dominates the time, so the two are on par within noise.
+//!
+//! Run with `cargo bench --bench contains`.
+
+mod common;
+
+use rb_tree::RbTreeMap;
+
+use common::measure;
+use std::hint::black_box;
+
+const LEN: u64 = 1_000_000;
+const ROUNDS: u32 = 5;
+
+fn main() {
+    let mut map = RbTreeMap::new();
+    // even keys are present and odd keys are absent
+    map.extend_sorted((0..LEN).map(|k| (2 * k, [k; 4])));
+
+    for (name, offset) in [("present", 0), ("absent", 1)] {
+        measure(&format!("get is_some {name}"), LEN, ROUNDS, || {
+            let found = (0..LEN)
+                .filter(|&k| map.get(&black_box(2 * k + offset)).is_some())
+                .count();
+            black_box(found);
+        });
+        measure(&format!("contains_key {name}"), LEN, ROUNDS, || {
+            let found = (0..LEN)
+                .filter(|&k| map.contains_key(&black_box(2 * k + offset)))
+                .count();
+            black_box(found);
+        });
+    }
+}
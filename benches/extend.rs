@@ -0,0 +1,36 @@
+//! Compares `Extend::extend` with inserting one by one for appending an ascending batch to a map.
+//!
+//! Run with `cargo bench --bench extend`.
+
+mod common;
+
+use rb_tree::RbTreeMap;
+
+use common::measure;
+use std::hint::black_box;
+
+const LEN: u64 = 200_000;
+const ROUNDS: u32 = 20;
+
+fn main() {
+    // the batch is greater than all of the keys in the smaller map.
+    let base = || -> RbTreeMap<u64, u64> { (0..LEN / 10).map(|k| (k, k)).collect() };
+    let batch: Vec<_> = (LEN / 10..LEN).map(|k| (k, k)).collect();
+
+    // building the map is measured alone, because both of the others include it.
+    measure("build only", LEN, ROUNDS, || {
+        black_box(base());
+    });
+    measure("insert one by one", LEN, ROUNDS, || {
+        let mut map = base();
+        for &(k, v) in &batch {
+            map.insert(k, v);
+        }
+        black_box(map);
+    });
+    measure("extend", LEN, ROUNDS, || {
+        let mut map = base();
+        map.extend(batch.iter().copied());
+        black_box(map);
+    });
+}
@@ -0,0 +1,34 @@
+//! Compares `RbTreeMap::extend_sorted` with `Extend::extend` for merging a large sorted batch into a large map.
+//!
+//! Run with `cargo bench --bench extend_sorted`.
+
+mod common;
+
+use rb_tree::RbTreeMap;
+
+use common::measure;
+use std::hint::black_box;
+
+const LEN: u64 = 200_000;
+const ROUNDS: u32 = 20;
+
+fn main() {
+    // the even keys are in the map, and the batch overlaps a half of them.
+    let base = || -> RbTreeMap<u64, u64> { (0..LEN).map(|k| (k * 2, k)).collect() };
+    let batch: Vec<_> = (LEN / 2..LEN * 3 / 2).map(|k| (k, k)).collect();
+
+    // building the map is measured alone, because both of the others include it.
+    measure("build only", LEN, ROUNDS, || {
+        black_box(base());
+    });
+    measure("extend", LEN, ROUNDS, || {
+        let mut map = base();
+        map.extend(batch.iter().copied());
+        black_box(map);
+    });
+    measure("extend_sorted", LEN, ROUNDS, || {
+        let mut map = base();
+        map.extend_sorted(batch.iter().copied());
+        black_box(map);
+    });
+}
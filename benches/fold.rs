@@ -0,0 +1,42 @@
+//! Compares `Iterator::fold` with a loop of `next` for summing over the map, on `iter` and `range`. The fold walks the nodes without the per-item bookkeeping of `next`, and saves a few percent of the time.
+//!
+//! Run with `cargo bench --bench fold`.
+
+mod common;
+
+use rb_tree::RbTreeMap;
+
+use common::measure;
+use std::hint::black_box;
+
+const LEN: u64 = 1_000_000;
+const ROUNDS: u32 = 20;
+
+fn main() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..LEN).map(|k| (k, k)));
+
+    measure("iter next loop", LEN, ROUNDS, || {
+        let mut sum = 0u64;
+        // a `for` loop calls `next` for each item
+        for (_, &v) in map.iter() {
+            sum = sum.wrapping_add(v);
+        }
+        black_box(sum);
+    });
+    measure("iter fold", LEN, ROUNDS, || {
+        let sum = map.iter().fold(0u64, |sum, (_, &v)| sum.wrapping_add(v));
+        black_box(sum);
+    });
+    measure("range next loop", LEN, ROUNDS, || {
+        let mut sum = 0u64;
+        for (_, &v) in map.range(..) {
+            sum = sum.wrapping_add(v);
+        }
+        black_box(sum);
+    });
+    measure("range fold", LEN, ROUNDS, || {
+        let sum = map.range(..).fold(0u64, |sum, (_, &v)| sum.wrapping_add(v));
+        black_box(sum);
+    });
+}
@@ -0,0 +1,42 @@
+//! Compares `into_entries_sorted` with `into_iter().collect::<Vec<_>>()` for moving the entries out into a sorted `Vec`. The two are on par within noise: `collect` already reserves exactly by the size hint, and deallocating the nodes dominates the time. They are measured by turns on fresh clones to cancel the drift of the allocator.
+//!
+//! Run with `cargo bench --bench into_entries`.
+
+// the collectors are measured by turns, so `measure` is not used.
+#[allow(dead_code)]
+mod common;
+
+use rb_tree::RbTreeMap;
+
+use common::report;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const LEN: u64 = 1_000_000;
+const ROUNDS: u32 = 10;
+
+type Collector = fn(RbTreeMap<u64, u64>) -> Vec<(u64, u64)>;
+
+fn main() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..LEN).map(|k| (k, k)));
+
+    let collectors: [(&str, Collector); 2] = [
+        ("into_iter().collect", |map| map.into_iter().collect()),
+        ("into_entries_sorted", |map| map.into_entries_sorted()),
+    ];
+    let mut elapsed = [Duration::ZERO; 2];
+    for _ in 0..ROUNDS {
+        for ((_, f), elapsed) in collectors.iter().zip(&mut elapsed) {
+            let map = map.clone();
+            let start = Instant::now();
+            black_box(f(map));
+            *elapsed += start.elapsed();
+        }
+    }
+    for ((name, _), elapsed) in collectors.iter().zip(elapsed) {
+        report(name, LEN, elapsed / ROUNDS);
+    }
+}
@@ -0,0 +1,245 @@
+//! The implementations of [`rkyv`] traits. [`RbTreeMap`] is archived as a slice of the entries in ascending order of key, so it can be searched by binary search without deserializing.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{node::Root, RbTreeMap};
+
+use std::{borrow::Borrow, fmt, ops};
+
+use rkyv::{
+    bytecheck::CheckBytes,
+    collections::util::{Entry, EntryAdapter},
+    rancor::Fallible,
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Deserialize, Place, Portable, Serialize,
+};
+
+/// An archived [`RbTreeMap`]. The entries are stored in a slice in ascending order of key.
+#[repr(transparent)]
+pub struct ArchivedRbTreeMap<K, V> {
+    entries: ArchivedVec<Entry<K, V>>,
+}
+
+// Safety: `ArchivedRbTreeMap` is `repr(transparent)` over `ArchivedVec`, which is `Portable`.
+unsafe impl<K: Portable, V: Portable> Portable for ArchivedRbTreeMap<K, V> {}
+
+// Safety: `ArchivedRbTreeMap` is `repr(transparent)` over `ArchivedVec`, so it is valid if the inner one is valid.
+unsafe impl<C, K, V> CheckBytes<C> for ArchivedRbTreeMap<K, V>
+where
+    C: Fallible + ?Sized,
+    ArchivedVec<Entry<K, V>>: CheckBytes<C>,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        ArchivedVec::<Entry<K, V>>::check_bytes(value.cast(), context)
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedRbTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> ArchivedRbTreeMap<K, V> {
+    /// Returns the number of elements in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{archive::ArchivedRbTreeMap, RbTreeMap};
+    /// use rkyv::{rancor::Error, rend::u32_le};
+    ///
+    /// let map: RbTreeMap<u32, u32> = [(1, 10), (2, 20)].into_iter().collect();
+    /// let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+    /// let archived = rkyv::access::<ArchivedRbTreeMap<u32_le, u32_le>, Error>(&bytes).unwrap();
+    /// assert_eq!(archived.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{archive::ArchivedRbTreeMap, RbTreeMap};
+    /// use rkyv::{rancor::Error, rend::u32_le};
+    ///
+    /// let map: RbTreeMap<u32, u32> = [(1, 10), (2, 20)].into_iter().collect();
+    /// let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+    /// let archived = rkyv::access::<ArchivedRbTreeMap<u32_le, u32_le>, Error>(&bytes).unwrap();
+    /// assert!(!archived.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{archive::ArchivedRbTreeMap, RbTreeMap};
+    /// use rkyv::{rancor::Error, rend::u32_le};
+    ///
+    /// let map: RbTreeMap<u32, u32> = [(1, 10), (2, 20)].into_iter().collect();
+    /// let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+    /// let archived = rkyv::access::<ArchivedRbTreeMap<u32_le, u32_le>, Error>(&bytes).unwrap();
+    /// assert!(archived.iter().map(|(k, v)| (k.to_native(), v.to_native())).eq([(1, 10), (2, 20)]));
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator + '_ {
+        self.entries.iter().map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl<K: Ord, V> ArchivedRbTreeMap<K, V> {
+    /// Returns a reference to the value corresponding to the key, found by binary search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<String, u8> = [("a".to_string(), 1), ("b".to_string(), 2)]
+    ///     .into_iter()
+    ///     .collect();
+    /// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&map).unwrap();
+    /// let archived =
+    ///     rkyv::access::<rb_tree::archive::ArchivedRbTreeMap<rkyv::string::ArchivedString, u8>, rkyv::rancor::Error>(&bytes)
+    ///         .unwrap();
+    /// assert_eq!(archived.get("b"), Some(&2));
+    /// assert_eq!(archived.get("c"), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self
+            .entries
+            .binary_search_by(|entry| entry.key.borrow().cmp(key))
+            .ok()?;
+        Some(&self.entries[index].value)
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{archive::ArchivedRbTreeMap, RbTreeMap};
+    /// use rkyv::{rancor::Error, rend::u32_le};
+    ///
+    /// let map: RbTreeMap<u32, u32> = [(1, 10), (2, 20)].into_iter().collect();
+    /// let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+    /// let archived = rkyv::access::<ArchivedRbTreeMap<u32_le, u32_le>, Error>(&bytes).unwrap();
+    /// assert!(archived.contains_key(&u32_le::from_native(1)));
+    /// assert!(!archived.contains_key(&u32_le::from_native(3)));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the map. The bounds are found by binary search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let map: RbTreeMap<String, u8> = ["a", "b", "c", "d"]
+    ///     .into_iter()
+    ///     .enumerate()
+    ///     .map(|(i, s)| (s.to_string(), i as u8))
+    ///     .collect();
+    /// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&map).unwrap();
+    /// let archived =
+    ///     rkyv::access::<rb_tree::archive::ArchivedRbTreeMap<rkyv::string::ArchivedString, u8>, rkyv::rancor::Error>(&bytes)
+    ///         .unwrap();
+    /// let range = (Bound::Included("b"), Bound::Excluded("d"));
+    /// assert!(archived.range::<str, _>(range).map(|(_, &v)| v).eq([1, 2]));
+    /// ```
+    pub fn range<Q, R>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator + '_
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: ops::RangeBounds<Q>,
+    {
+        let start = match range.start_bound() {
+            ops::Bound::Included(b) => self.entries.partition_point(|e| e.key.borrow() < b),
+            ops::Bound::Excluded(b) => self.entries.partition_point(|e| e.key.borrow() <= b),
+            ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            ops::Bound::Included(b) => self.entries.partition_point(|e| e.key.borrow() <= b),
+            ops::Bound::Excluded(b) => self.entries.partition_point(|e| e.key.borrow() < b),
+            ops::Bound::Unbounded => self.entries.len(),
+        };
+        self.entries[start..end.max(start)]
+            .iter()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl<K: Archive, V: Archive> Archive for RbTreeMap<K, V> {
+    type Archived = ArchivedRbTreeMap<K::Archived, V::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // Safety: `ArchivedRbTreeMap` is `repr(transparent)` over `ArchivedVec`.
+        let out = unsafe { out.cast_unchecked::<ArchivedVec<Entry<K::Archived, V::Archived>>>() };
+        ArchivedVec::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<K, V, S> Serialize<S> for RbTreeMap<K, V>
+where
+    K: Serialize<S>,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_iter(
+            self.iter()
+                .map(|(key, value)| EntryAdapter::<_, _, K, V>::new(key, value)),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, D> Deserialize<RbTreeMap<K, V>, D> for ArchivedRbTreeMap<K::Archived, V::Archived>
+where
+    K: Archive + Ord,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<RbTreeMap<K, V>, D::Error> {
+        let mut entries = Vec::with_capacity(self.len());
+        for entry in self.entries.iter() {
+            entries.push((
+                entry.key.deserialize(deserializer)?,
+                entry.value.deserialize(deserializer)?,
+            ));
+        }
+        if entries.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+            return Ok(RbTreeMap {
+                root: Root::from_sorted_iter(entries.into_iter()),
+            });
+        }
+        // the archive is not made by `RbTreeMap`, so the entries are inserted one by one.
+        Ok(entries.into_iter().collect())
+    }
+}
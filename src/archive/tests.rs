@@ -0,0 +1,37 @@
+use super::ArchivedRbTreeMap;
+use crate::{tests::rng, RbTreeMap};
+
+use rkyv::{rancor::Error, rend::u32_le};
+
+#[test]
+fn archived_lookup() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..5000u32).map(|k| (k * 3, k)));
+    let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+    let archived = rkyv::access::<ArchivedRbTreeMap<u32_le, u32_le>, Error>(&bytes).unwrap();
+    assert_eq!(archived.len(), map.len());
+
+    let mut rand = rng(0x2545_f491);
+    for _ in 0..1000 {
+        let key = rand() % 16000;
+        assert_eq!(
+            archived
+                .get(&u32_le::from_native(key))
+                .map(|v| v.to_native()),
+            map.get(&key).copied()
+        );
+    }
+
+    let from = u32_le::from_native(100);
+    let to = u32_le::from_native(200);
+    assert!(archived
+        .range(from..to)
+        .map(|(k, v)| (k.to_native(), v.to_native()))
+        .eq(map.range(100..200).map(|(&k, &v)| (k, v))));
+    assert_eq!(archived.range(to..from).count(), 0);
+
+    let restored = rkyv::deserialize::<RbTreeMap<u32, u32>, Error>(archived).unwrap();
+    assert_eq!(restored, map);
+    let root = restored.root.inner().unwrap();
+    root.assert_tree(&Some(root));
+}
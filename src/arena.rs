@@ -0,0 +1,717 @@
+#[cfg(test)]
+mod tests;
+
+use crate::node::{ChildIndex, Color};
+
+use std::{borrow::Borrow, fmt, iter::FusedIterator, num::NonZeroU32};
+
+/// A map based on a red-black tree, whose nodes are stored in a growable arena and linked by indices.
+///
+/// The nodes are packed in a contiguous buffer instead of being allocated one by one, so traversals touch less scattered memory. The slots of removed nodes are reused by later insertions.
+pub struct ArenaRbTreeMap<K, V> {
+    slots: Vec<Slot<K, V>>,
+    root: Option<Index>,
+    free: Option<Index>,
+    len: usize,
+}
+
+enum Slot<K, V> {
+    Occupied(ArenaNode<K, V>),
+    /// A removed slot, linked to the next free slot.
+    Vacant(Option<Index>),
+}
+
+/// An index of a slot in the arena. It is stored as the index plus one, so `Option<Index>` is as small as `u32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Index(NonZeroU32);
+
+impl Index {
+    fn new(idx: usize) -> Self {
+        u32::try_from(idx + 1)
+            .ok()
+            .and_then(NonZeroU32::new)
+            .map(Index)
+            .expect("the arena must not exceed `u32::MAX` nodes")
+    }
+
+    fn get(self) -> usize {
+        self.0.get() as usize - 1
+    }
+}
+
+struct ArenaNode<K, V> {
+    parent: Option<Index>,
+    children: (Option<Index>, Option<Index>),
+    color: Color,
+    key: K,
+    value: V,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArenaRbTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> Default for ArenaRbTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for ArenaRbTreeMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for ArenaRbTreeMap<K, V> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a ArenaRbTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V> ArenaRbTreeMap<K, V> {
+    /// Creates a new, empty `ArenaRbTreeMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::new();
+    ///
+    /// map.insert(1, "a");
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            root: None,
+            free: None,
+            len: 0,
+        }
+    }
+
+    /// Creates a new, empty `ArenaRbTreeMap` with the arena which can hold at least `capacity` nodes without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::with_capacity(10);
+    ///
+    /// map.insert(1, "a");
+    /// assert!(map.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the number of nodes the arena can hold without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let map: ArenaRbTreeMap<i32, i32> = ArenaRbTreeMap::with_capacity(10);
+    /// assert!(map.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Returns an estimate of the bytes used by the map, that is the size of the map itself and its whole arena including the unused capacity. It takes `O(1)` time.
+    ///
+    /// The heap owned by the keys and values themselves, such as the buffer of `String`, is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::<u64, u64>::with_capacity(10);
+    /// let reserved = map.memory_footprint();
+    ///
+    /// map.insert(1, 10);
+    /// assert_eq!(map.memory_footprint(), reserved);
+    /// ```
+    pub fn memory_footprint(&self) -> usize {
+        std::mem::size_of::<Self>() + self.slots.capacity() * std::mem::size_of::<Slot<K, V>>()
+    }
+
+    /// Returns the number of elements in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut a = ArenaRbTreeMap::new();
+    /// assert_eq!(a.len(), 0);
+    /// a.insert(1, "a");
+    /// assert_eq!(a.len(), 1);
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the map contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut a = ArenaRbTreeMap::new();
+    /// assert!(a.is_empty());
+    /// a.insert(1, "a");
+    /// assert!(!a.is_empty());
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Clears the map, removing all elements. The arena keeps its capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut a = ArenaRbTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.clear();
+    /// assert!(a.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.root = None;
+        self.free = None;
+        self.len = 0;
+    }
+
+    /// Clears the map as [`clear`](Self::clear) does, and then returns the memory of the arena to the allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut a = ArenaRbTreeMap::with_capacity(100);
+    /// a.insert(1, "a");
+    /// a.clear_and_shrink();
+    /// assert!(a.is_empty());
+    /// assert_eq!(a.capacity(), 0);
+    /// ```
+    pub fn clear_and_shrink(&mut self) {
+        self.clear();
+        self.slots.shrink_to_fit();
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::new();
+    /// map.insert(3, "c");
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    ///
+    /// for (key, value) in map.iter() {
+    ///     println!("{key}: {value}");
+    /// }
+    ///
+    /// let (first_key, first_value) = map.iter().next().unwrap();
+    /// assert_eq!((*first_key, *first_value), (1, "a"));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            map: self,
+            front: self.root.map(|root| self.min_child(root)),
+            back: self.root.map(|root| self.max_child(root)),
+            len: self.len,
+        }
+    }
+
+    fn node(&self, idx: Index) -> &ArenaNode<K, V> {
+        match &self.slots[idx.get()] {
+            Slot::Occupied(node) => node,
+            Slot::Vacant(_) => unreachable!("the slot must be occupied"),
+        }
+    }
+
+    fn node_mut(&mut self, idx: Index) -> &mut ArenaNode<K, V> {
+        match &mut self.slots[idx.get()] {
+            Slot::Occupied(node) => node,
+            Slot::Vacant(_) => unreachable!("the slot must be occupied"),
+        }
+    }
+
+    fn parent(&self, idx: Index) -> Option<Index> {
+        self.node(idx).parent
+    }
+
+    fn child(&self, idx: Index, child_idx: ChildIndex) -> Option<Index> {
+        let children = self.node(idx).children;
+        match child_idx {
+            ChildIndex::Left => children.0,
+            ChildIndex::Right => children.1,
+        }
+    }
+
+    fn is_red(&self, idx: Option<Index>) -> bool {
+        idx.is_some_and(|idx| self.node(idx).color == Color::Red)
+    }
+
+    fn set_color(&mut self, idx: Index, color: Color) {
+        self.node_mut(idx).color = color;
+    }
+
+    fn index_on_parent(&self, idx: Index) -> Option<ChildIndex> {
+        let parent = self.parent(idx)?;
+        Some(if self.child(parent, ChildIndex::Left) == Some(idx) {
+            ChildIndex::Left
+        } else {
+            ChildIndex::Right
+        })
+    }
+
+    fn min_child(&self, mut idx: Index) -> Index {
+        while let Some(left) = self.child(idx, ChildIndex::Left) {
+            idx = left;
+        }
+        idx
+    }
+
+    fn max_child(&self, mut idx: Index) -> Index {
+        while let Some(right) = self.child(idx, ChildIndex::Right) {
+            idx = right;
+        }
+        idx
+    }
+
+    /// Returns the next node of `idx` in ascending (`Right`) or descending (`Left`) order.
+    fn step(&self, idx: Index, dir: ChildIndex) -> Option<Index> {
+        if let Some(child) = self.child(idx, dir) {
+            return Some(match dir {
+                ChildIndex::Left => self.max_child(child),
+                ChildIndex::Right => self.min_child(child),
+            });
+        }
+        let mut current = idx;
+        loop {
+            let from = self.index_on_parent(current)?;
+            current = self.parent(current)?;
+            if from != dir {
+                return Some(current);
+            }
+        }
+    }
+
+    /// Sets `child` into the `child_idx` side of `idx`, and links the parent of `child` to `idx`.
+    fn set_child(&mut self, idx: Index, child_idx: ChildIndex, child: Option<Index>) {
+        let children = &mut self.node_mut(idx).children;
+        match child_idx {
+            ChildIndex::Left => children.0 = child,
+            ChildIndex::Right => children.1 = child,
+        }
+        if let Some(child) = child {
+            self.node_mut(child).parent = Some(idx);
+        }
+    }
+
+    /// Replaces the link from the parent of `old` (or the root) with `new`.
+    fn replace_in_parent(&mut self, old: Index, new: Option<Index>) {
+        if let Some(parent) = self.parent(old) {
+            let idx = self.index_on_parent(old).unwrap();
+            self.set_child(parent, idx, new);
+        } else {
+            self.root = new;
+            if let Some(new) = new {
+                self.node_mut(new).parent = None;
+            }
+        }
+    }
+
+    fn rotate(&mut self, target: Index, pivot_idx: ChildIndex) {
+        //           [target]
+        //            /   \
+        //        [pivot] [be_fallen]
+        //         /   \
+        // [be_risen] [be_moved]
+        //            ↓
+        //        [pivot]
+        //         /   \
+        // [be_risen] [target]
+        //             /   \
+        //     [be_moved] [be_fallen]
+        let pivot = self.child(target, pivot_idx).expect("pivot must be found");
+        let be_moved = self.child(pivot, !pivot_idx);
+        self.set_child(target, pivot_idx, be_moved);
+        self.replace_in_parent(target, Some(pivot));
+        self.set_child(pivot, !pivot_idx, Some(target));
+    }
+
+    fn allocate(&mut self, key: K, value: V) -> Index {
+        let node = ArenaNode {
+            parent: None,
+            children: (None, None),
+            color: Color::Red,
+            key,
+            value,
+        };
+        if let Some(idx) = self.free {
+            if let Slot::Vacant(next) = self.slots[idx.get()] {
+                self.free = next;
+            } else {
+                unreachable!("the free slot must be vacant")
+            }
+            self.slots[idx.get()] = Slot::Occupied(node);
+            idx
+        } else {
+            self.slots.push(Slot::Occupied(node));
+            Index::new(self.slots.len() - 1)
+        }
+    }
+
+    fn deallocate(&mut self, idx: Index) -> (K, V) {
+        let slot = std::mem::replace(&mut self.slots[idx.get()], Slot::Vacant(self.free));
+        self.free = Some(idx);
+        match slot {
+            Slot::Occupied(node) => (node.key, node.value),
+            Slot::Vacant(_) => unreachable!("the slot must be occupied"),
+        }
+    }
+
+    fn balance_after_insert(&mut self, mut target: Index) {
+        while let Some(parent) = self.parent(target).filter(|&p| self.is_red(Some(p))) {
+            let grandparent = if let Some(grandparent) = self.parent(parent) {
+                grandparent
+            } else {
+                // the red root will be black.
+                break;
+            };
+            let parent_idx = self.index_on_parent(parent).unwrap();
+            let uncle = self.child(grandparent, !parent_idx);
+            if self.is_red(uncle) {
+                // if the parent and the uncle is red, they will be black and the grandparent will be red.
+                self.set_color(parent, Color::Black);
+                self.set_color(uncle.unwrap(), Color::Black);
+                self.set_color(grandparent, Color::Red);
+                target = grandparent;
+                continue;
+            }
+            let mut parent = parent;
+            if self.index_on_parent(target) != Some(parent_idx) {
+                // make the target and the parent aligned to the same side.
+                self.rotate(parent, !parent_idx);
+                parent = target;
+            }
+            self.rotate(grandparent, parent_idx);
+            self.set_color(parent, Color::Black);
+            self.set_color(grandparent, Color::Red);
+            break;
+        }
+        if let Some(root) = self.root {
+            self.set_color(root, Color::Black);
+        }
+    }
+
+    /// Balances the tree for removing `target`, which is black and has no children.
+    fn balance_after_remove(&mut self, target: Index) {
+        let mut current = target;
+        while Some(current) != self.root && !self.is_red(Some(current)) {
+            let idx = self.index_on_parent(current).unwrap();
+            let parent = self.parent(current).unwrap();
+            let mut sibling = self.child(parent, !idx).unwrap();
+            if self.is_red(Some(sibling)) {
+                self.set_color(sibling, Color::Black);
+                self.set_color(parent, Color::Red);
+                self.rotate(parent, !idx);
+                sibling = self.child(parent, !idx).unwrap();
+            }
+            let close_nephew = self.child(sibling, idx);
+            let distant_nephew = self.child(sibling, !idx);
+            if !self.is_red(close_nephew) && !self.is_red(distant_nephew) {
+                // the parent node needs to re-balance.
+                self.set_color(sibling, Color::Red);
+                current = parent;
+                continue;
+            }
+            if !self.is_red(distant_nephew) {
+                self.set_color(close_nephew.unwrap(), Color::Black);
+                self.set_color(sibling, Color::Red);
+                self.rotate(sibling, idx);
+                sibling = self.child(parent, !idx).unwrap();
+            }
+            let parent_color = self.node(parent).color;
+            self.set_color(sibling, parent_color);
+            self.set_color(parent, Color::Black);
+            let distant_nephew = self.child(sibling, !idx).unwrap();
+            self.set_color(distant_nephew, Color::Black);
+            self.rotate(parent, !idx);
+            return;
+        }
+        self.set_color(current, Color::Black);
+    }
+
+    fn delete(&mut self, mut target: Index) -> (K, V) {
+        self.len -= 1;
+        if let (Some(left), Some(_)) = self.node(target).children {
+            // move the entry of `target` into the maximum node in the left, then remove that node instead.
+            let max_in_left = self.max_child(left);
+            let (low, high) = (
+                target.get().min(max_in_left.get()),
+                target.get().max(max_in_left.get()),
+            );
+            let (head, tail) = self.slots.split_at_mut(high);
+            if let (Slot::Occupied(a), Slot::Occupied(b)) = (&mut head[low], &mut tail[0]) {
+                std::mem::swap(&mut a.key, &mut b.key);
+                std::mem::swap(&mut a.value, &mut b.value);
+            }
+            target = max_in_left;
+        }
+        let (left, right) = self.node(target).children;
+        if let Some(child) = left.or(right) {
+            // `target` is black and `child` is red.
+            self.replace_in_parent(target, Some(child));
+            self.set_color(child, Color::Black);
+        } else {
+            if !self.is_red(Some(target)) {
+                self.balance_after_remove(target);
+            }
+            self.replace_in_parent(target, None);
+        }
+        self.deallocate(target)
+    }
+}
+
+impl<K: Ord, V> ArenaRbTreeMap<K, V> {
+    fn search<Q>(&self, key: &Q) -> Result<Index, Option<(Index, ChildIndex)>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut current = self.root.ok_or(None)?;
+        loop {
+            let idx = match key.cmp(self.node(current).key.borrow()) {
+                std::cmp::Ordering::Less => ChildIndex::Left,
+                std::cmp::Ordering::Equal => return Ok(current),
+                std::cmp::Ordering::Greater => ChildIndex::Right,
+            };
+            current = self.child(current, idx).ok_or(Some((current, idx)))?;
+        }
+    }
+
+    /// Inserts a key-value pair into the map. If the map had an equal key, both of the stored key and value are replaced with the new ones, and the old pair is returned, as [`RbTreeMap::insert`](crate::RbTreeMap::insert) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::new();
+    /// assert_eq!(map.insert(37, "a"), None);
+    /// assert!(!map.is_empty());
+    ///
+    /// map.insert(37, "b");
+    /// assert_eq!(map.insert(37, "c"), Some((37, "b")));
+    /// assert_eq!(map.get(&37), Some(&"c"));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        match self.search(&key) {
+            Ok(found) => {
+                let node = self.node_mut(found);
+                let old_key = std::mem::replace(&mut node.key, key);
+                let old_value = std::mem::replace(&mut node.value, value);
+                Some((old_key, old_value))
+            }
+            Err(position) => {
+                let new_node = self.allocate(key, value);
+                if let Some((parent, idx)) = position {
+                    self.set_child(parent, idx, Some(new_node));
+                } else {
+                    self.root = Some(new_node);
+                }
+                self.balance_after_insert(new_node);
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get(&1), Some(&"a"));
+    /// assert_eq!(map.get(&2), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let found = self.search(key).ok()?;
+        Some(&self.node(found).value)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::new();
+    /// map.insert(1, "a");
+    /// if let Some(x) = map.get_mut(&1) {
+    ///     *x = "b";
+    /// }
+    /// assert_eq!(map.get(&1), Some(&"b"));
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let found = self.search(key).ok()?;
+        Some(&mut self.node_mut(found).value)
+    }
+
+    /// Returns whether the map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert!(map.contains_key(&1));
+    /// assert!(!map.contains_key(&2));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(key).is_ok()
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in the map. The slot of the removed node is reused by later insertions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ArenaRbTreeMap;
+    ///
+    /// let mut map = ArenaRbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.remove(&1), Some("a"));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let found = self.search(key).ok()?;
+        Some(self.delete(found).1)
+    }
+}
+
+/// An iterator over the entries of an `ArenaRbTreeMap`.
+///
+/// This `struct` is created by the [`ArenaRbTreeMap::iter`] method.
+pub struct Iter<'a, K, V> {
+    map: &'a ArenaRbTreeMap<K, V>,
+    front: Option<Index>,
+    back: Option<Index>,
+    len: usize,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self { ..*self }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let curr = self.front?;
+        self.len -= 1;
+        self.front = self.map.step(curr, ChildIndex::Right);
+        let node = self.map.node(curr);
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let curr = self.back?;
+        self.len -= 1;
+        self.back = self.map.step(curr, ChildIndex::Left);
+        let node = self.map.node(curr);
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
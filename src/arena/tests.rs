@@ -0,0 +1,156 @@
+use super::{ArenaRbTreeMap, Slot};
+use crate::{
+    node::{ChildIndex, Color},
+    tests::{rng, Tagged},
+};
+
+fn assert_tree<K: Ord, V>(map: &ArenaRbTreeMap<K, V>) {
+    let root = if let Some(root) = map.root {
+        root
+    } else {
+        assert_eq!(map.len(), 0);
+        return;
+    };
+    assert!(map.parent(root).is_none());
+    let mut black_height = None;
+    let mut count = 0;
+    let mut stack = vec![(0usize, root)];
+    while let Some((black_count, node)) = stack.pop() {
+        count += 1;
+        let is_red = map.is_red(Some(node));
+        let black_count = black_count + !is_red as usize;
+        for idx in [ChildIndex::Left, ChildIndex::Right] {
+            if let Some(child) = map.child(node, idx) {
+                assert_eq!(map.parent(child), Some(node));
+                assert!(!(is_red && map.is_red(Some(child))));
+                stack.push((black_count, child));
+            } else {
+                assert_eq!(*black_height.get_or_insert(black_count), black_count);
+            }
+        }
+    }
+    assert_eq!(count, map.len());
+    assert!(map.iter().map(|(k, _)| k).is_sorted());
+}
+
+#[test]
+fn simple() {
+    let mut map = ArenaRbTreeMap::new();
+    for i in [5, 3, 8, 1, 4, 7, 9, 2, 6] {
+        assert_eq!(map.insert(i, i * 10), None);
+        assert_tree(&map);
+    }
+    assert_eq!(map.insert(4, 0), Some((4, 40)));
+    assert_eq!(map.len(), 9);
+    assert_eq!(map.node(map.root.unwrap()).color, Color::Black);
+    assert!(map.iter().map(|(&k, _)| k).eq(1..=9));
+    assert!(map.iter().rev().map(|(&k, _)| k).eq((1..=9).rev()));
+
+    assert_eq!(map.remove(&5), Some(50));
+    assert_eq!(map.remove(&5), None);
+    assert_tree(&map);
+    assert_eq!(map.get(&4), Some(&0));
+    assert!(!map.contains_key(&5));
+}
+
+#[test]
+fn reuse_removed_slots() {
+    let mut map: ArenaRbTreeMap<_, _> = (0..16).map(|i| (i, ())).collect();
+    assert_eq!(map.slots.len(), 16);
+    for i in 0..8 {
+        map.remove(&i);
+    }
+    assert_eq!(
+        map.slots
+            .iter()
+            .filter(|slot| matches!(slot, Slot::Vacant(_)))
+            .count(),
+        8
+    );
+    for i in 16..24 {
+        map.insert(i, ());
+    }
+    assert_eq!(map.slots.len(), 16);
+    assert_tree(&map);
+    assert!(map.iter().map(|(&k, _)| k).eq(8..24));
+}
+
+#[test]
+fn random_against_btree_map() {
+    let mut rand = rng(0x1234_5678);
+    let mut map = ArenaRbTreeMap::new();
+    let mut expected = std::collections::BTreeMap::new();
+    for i in 0..5000 {
+        let key = rand() % 256;
+        if rand().is_multiple_of(3) {
+            assert_eq!(map.remove(&key), expected.remove(&key));
+        } else {
+            assert_eq!(
+                map.insert(key, i).map(|(_, old)| old),
+                expected.insert(key, i)
+            );
+        }
+        assert_tree(&map);
+    }
+    assert!(map.iter().eq(expected.iter()));
+    assert!(map.iter().rev().eq(expected.iter().rev()));
+}
+
+#[test]
+fn insert_replaces_key() {
+    let mut map = ArenaRbTreeMap::new();
+    map.insert(Tagged(1, "old"), 10);
+    map.insert(Tagged(2, "other"), 20);
+    let (old_key, old_value) = map.insert(Tagged(1, "new"), 11).unwrap();
+    assert_eq!((old_key.1, old_value), ("old", 10));
+    assert!(map
+        .iter()
+        .map(|(k, &v)| (k.1, v))
+        .eq([("new", 11), ("other", 20)]));
+}
+
+#[test]
+fn memory_footprint_follows_capacity() {
+    let mut map = ArenaRbTreeMap::<u64, u64>::new();
+    assert_eq!(
+        map.memory_footprint(),
+        std::mem::size_of::<ArenaRbTreeMap<u64, u64>>()
+    );
+    for i in 0..100 {
+        map.insert(i, i);
+    }
+    let slot_size = std::mem::size_of::<Slot<u64, u64>>();
+    assert_eq!(
+        map.memory_footprint(),
+        std::mem::size_of::<ArenaRbTreeMap<u64, u64>>() + map.capacity() * slot_size
+    );
+    // removed slots stay in the arena
+    let before = map.memory_footprint();
+    for i in 0..50 {
+        map.remove(&i);
+    }
+    assert_eq!(map.memory_footprint(), before);
+}
+
+#[test]
+fn clear_and_shrink_releases_arena() {
+    let mut map = ArenaRbTreeMap::new();
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    map.clear();
+    assert!(map.capacity() >= 1000);
+
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    map.clear_and_shrink();
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(), 0);
+    assert_eq!(map.iter().next(), None);
+
+    map.insert(2, 20);
+    map.insert(1, 10);
+    assert_tree(&map);
+    assert!(map.iter().eq([(&1, &10), (&2, &20)]));
+}
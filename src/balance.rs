@@ -39,7 +39,7 @@ impl<K, V> Node<K, V> {
 
     pub(crate) fn balance_after_insert(mut self, root: &mut Option<Self>) {
         loop {
-            if self.parent().map_or(true, |p| p.is_black()) {
+            if self.parent().is_none_or(|p| p.is_black()) {
                 // if the parent is black or none, the tree is well balanced.
                 break;
             }
@@ -50,7 +50,7 @@ impl<K, V> Node<K, V> {
                 break;
             }
             // the parent is red and the grandparent exists
-            if self.uncle().map_or(false, |uncle| uncle.is_red()) {
+            if self.uncle().is_some_and(|uncle| uncle.is_red()) {
                 // if the parent and the uncle is red, they will be black and the grandparent will be red.
                 self.parent().unwrap().set_color(Color::Black);
                 self.uncle().unwrap().set_color(Color::Black);
@@ -116,7 +116,7 @@ impl<K, V> Node<K, V> {
         debug_assert!(self.left().is_none());
         debug_assert!(self.right().is_none());
 
-        let (idx, mut parent) = self.index_and_parent().unwrap();
+        let (mut idx, mut parent) = self.index_and_parent().unwrap();
         let mut sibling = parent.child(!idx).unwrap();
         let mut close_nephew = sibling.child(idx);
         let mut distant_nephew = sibling.child(!idx);
@@ -134,8 +134,8 @@ impl<K, V> Node<K, V> {
                 //            /    \
                 // [close_nephew] [distant_nephew]
                 debug_assert!(parent.is_black());
-                debug_assert!(close_nephew.map_or(true, |n| n.is_black()));
-                debug_assert!(distant_nephew.map_or(true, |n| n.is_black()));
+                debug_assert!(close_nephew.is_none_or(|n| n.is_black()));
+                debug_assert!(distant_nephew.is_none_or(|n| n.is_black()));
                 parent.rotate(!idx, root);
                 parent.set_color(Color::Red);
                 sibling.set_color(Color::Black);
@@ -149,7 +149,7 @@ impl<K, V> Node<K, V> {
                 //    /    \
                 // target [close_nephew]
             }
-            if distant_nephew.map_or(false, |n| n.is_red()) {
+            if distant_nephew.is_some_and(|n| n.is_red()) {
                 // if the sibling is black and the distant nephew is red:
                 //     parent
                 //      /  \
@@ -168,7 +168,7 @@ impl<K, V> Node<K, V> {
                 // target
                 break;
             }
-            if close_nephew.map_or(false, |n| n.is_red()) {
+            if close_nephew.is_some_and(|n| n.is_red()) {
                 // if the sibling is black and the close nephew is red:
                 //        parent
                 //         /  \
@@ -202,8 +202,12 @@ impl<K, V> Node<K, V> {
             // if the parent and sibling and nephews are all black:
             sibling.set_color(Color::Red);
             // the parent node needs to re-balance.
-            if let Some(grandparent) = parent.parent() {
+            if let Some((parent_idx, grandparent)) = parent.index_and_parent() {
+                idx = parent_idx;
                 parent = grandparent;
+                sibling = parent.child(!idx).unwrap();
+                close_nephew = sibling.child(idx);
+                distant_nephew = sibling.child(!idx);
             } else {
                 // one black nodes are removed from all paths.
                 break;
@@ -217,27 +221,32 @@ impl<K, V> Node<K, V> {
     fn assert_tree(self, _: &Option<Self>) {}
 
     #[cfg(test)]
-    fn assert_tree(self, root: &Option<Self>) {
+    pub(crate) fn assert_tree(self, root: &Option<Self>) {
         if root.is_none() {
             return;
         }
+        let mut black_height = None;
         let mut stack = vec![(0usize, root.unwrap())];
         while let Some((black_count, node)) = stack.pop() {
             if node.is_red() {
-                assert!(node.left().map_or(true, |n| n.is_black()));
-                assert!(node.right().map_or(true, |n| n.is_black()));
+                assert!(node.left().is_none_or(|n| n.is_black()));
+                assert!(node.right().is_none_or(|n| n.is_black()));
             }
-            let is_black = node.is_black() as usize;
+            let black_count = black_count + node.is_black() as usize;
             let children = node.children();
+            if children.0.is_none() || children.1.is_none() {
+                // every path to a nil leaf must have the same number of black nodes.
+                assert_eq!(*black_height.get_or_insert(black_count), black_count);
+            }
             if let Some(c) = children.0 {
                 let back_ptr = c.parent().unwrap();
                 assert_eq!(back_ptr, node);
-                stack.push((black_count + is_black, c));
+                stack.push((black_count, c));
             }
             if let Some(c) = children.1 {
                 let back_ptr = c.parent().unwrap();
                 assert_eq!(back_ptr, node);
-                stack.push((black_count + is_black, c));
+                stack.push((black_count, c));
             }
         }
     }
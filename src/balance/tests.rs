@@ -1,4 +1,4 @@
-use crate::RbTreeMap;
+use crate::{tests::rng, RbTreeMap};
 
 #[test]
 fn simple_insert() {
@@ -182,3 +182,45 @@ fn simple_remove() {
     tree.remove(&5);
     assert!(tree.is_empty());
 }
+
+#[test]
+fn removing_root_detaches_new_root() {
+    let mut tree = RbTreeMap::new();
+    for key in [2, 1, 3] {
+        tree.insert(key, ());
+    }
+    //   [2]
+    //   / \
+    // (1) (3)
+    assert_eq!(tree.remove(&2), Some(()));
+    //   [1]
+    //     \
+    //     (3)
+    let root = tree.root.inner().unwrap();
+    assert_eq!(root.key(), &1);
+    assert!(root.parent().is_none());
+    root.assert_tree(&Some(root));
+}
+
+#[test]
+fn random_insert_remove() {
+    let mut rand = rng(0x9e37_79b9);
+    let mut tree = RbTreeMap::new();
+    let mut expected = std::collections::BTreeMap::new();
+    for _ in 0..5000 {
+        let key = rand() % 256;
+        if rand().is_multiple_of(3) {
+            assert_eq!(tree.remove(&key), expected.remove(&key));
+        } else {
+            assert_eq!(
+                tree.insert(key, ()).is_some(),
+                expected.insert(key, ()).is_some()
+            );
+        }
+        if let Some(root) = tree.root.inner() {
+            root.assert_tree(&Some(root));
+        }
+        assert_eq!(tree.len(), expected.len());
+    }
+    assert!(tree.keys().eq(expected.keys()));
+}
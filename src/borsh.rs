@@ -0,0 +1,84 @@
+//! The implementations of [`BorshSerialize`] and [`BorshDeserialize`]. The collections are encoded as the `u32` length followed by the elements in ascending order, so the bytes are canonical regardless of the insertion order.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{node::Root, RbTreeMap, RbTreeSet};
+
+use ::borsh::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    BorshDeserialize, BorshSerialize,
+};
+
+fn write_len<W: Write>(len: usize, writer: &mut W) -> Result<()> {
+    u32::try_from(len)
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "the length exceeds u32::MAX"))?
+        .serialize(writer)
+}
+
+/// Reads the elements in strictly ascending order, and builds the tree from them at once.
+fn read_sorted<R, K, V>(
+    reader: &mut R,
+    mut read: impl FnMut(&mut R) -> Result<(K, V)>,
+) -> Result<Root<K, V>>
+where
+    R: Read,
+    K: Ord,
+{
+    let len = u32::deserialize_reader(reader)? as usize;
+    // the capacity is limited, not to trust the length of broken data too much.
+    let mut entries: Vec<(K, V)> = Vec::with_capacity(len.min(4096));
+    for _ in 0..len {
+        let (key, value) = read(reader)?;
+        if entries.last().is_some_and(|(last, _)| last >= &key) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "the keys are not in strictly ascending order",
+            ));
+        }
+        entries.push((key, value));
+    }
+    Ok(Root::from_sorted_iter(entries.into_iter()))
+}
+
+impl<K: BorshSerialize, V: BorshSerialize> BorshSerialize for RbTreeMap<K, V> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_len(self.len(), writer)?;
+        for (key, value) in self.iter() {
+            key.serialize(writer)?;
+            value.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K: BorshDeserialize + Ord, V: BorshDeserialize> BorshDeserialize for RbTreeMap<K, V> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let root = read_sorted(reader, |reader| {
+            Ok((
+                K::deserialize_reader(reader)?,
+                V::deserialize_reader(reader)?,
+            ))
+        })?;
+        Ok(Self { root })
+    }
+}
+
+impl<T: BorshSerialize> BorshSerialize for RbTreeSet<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_len(self.len(), writer)?;
+        for value in self.iter() {
+            value.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BorshDeserialize + Ord> BorshDeserialize for RbTreeSet<T> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let root = read_sorted(reader, |reader| Ok((T::deserialize_reader(reader)?, ())))?;
+        Ok(Self {
+            map: RbTreeMap { root },
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{RbTreeMap, RbTreeSet};
+
+use ::borsh::BorshDeserialize;
+
+#[test]
+fn canonical_bytes() {
+    let ascending: RbTreeMap<u32, String> = (0..100).map(|k| (k, k.to_string())).collect();
+    let scrambled: RbTreeMap<u32, String> = (0..100)
+        .map(|k| k * 37 % 100)
+        .map(|k| (k, k.to_string()))
+        .collect();
+    let bytes = borsh::to_vec(&ascending).unwrap();
+    assert_eq!(bytes, borsh::to_vec(&scrambled).unwrap());
+    assert_eq!(&bytes[..4], &100u32.to_le_bytes());
+
+    let expected: std::collections::BTreeMap<u32, String> =
+        ascending.iter().map(|(&k, v)| (k, v.clone())).collect();
+    assert_eq!(bytes, borsh::to_vec(&expected).unwrap());
+
+    let restored = RbTreeMap::<u32, String>::try_from_slice(&bytes).unwrap();
+    assert_eq!(restored, ascending);
+    let root = restored.root.inner().unwrap();
+    root.assert_tree(&Some(root));
+
+    let set: RbTreeSet<i64> = [5, -3, 9, 0].into_iter().collect();
+    let other: RbTreeSet<i64> = [9, 0, 5, -3].into_iter().collect();
+    let bytes = borsh::to_vec(&set).unwrap();
+    assert_eq!(bytes, borsh::to_vec(&other).unwrap());
+    assert_eq!(RbTreeSet::<i64>::try_from_slice(&bytes).unwrap(), set);
+}
+
+#[test]
+fn rejects_unordered() {
+    let bytes = borsh::to_vec(&(2u32, 1u8, 0u8)).unwrap();
+    assert!(RbTreeSet::<u8>::try_from_slice(&bytes).is_err());
+    let bytes = borsh::to_vec(&(2u32, 1u8, 1u8)).unwrap();
+    assert!(RbTreeSet::<u8>::try_from_slice(&bytes).is_err());
+    let bytes = borsh::to_vec(&(2u32, 0u8, 1u8)).unwrap();
+    assert!(RbTreeSet::<u8>::try_from_slice(&bytes).is_ok());
+}
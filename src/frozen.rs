@@ -0,0 +1,269 @@
+//! A read-only map to be shared across threads.
+//!
+//! [`RbTreeMap`] is `Sync` because none of its `&self` methods mutates the tree. [`FrozenRbTreeMap`] makes the contract explicit at the type level: it has no mutating method at all, so it can be put behind an [`Arc`](std::sync::Arc) without any chance of `&mut` access until it is thawed.
+
+#[cfg(test)]
+mod tests;
+
+use std::{borrow::Borrow, fmt, ops::RangeBounds};
+
+use crate::{
+    map::iter::{Iter, Keys, Range, Values},
+    RbTreeMap,
+};
+
+impl<K, V> RbTreeMap<K, V> {
+    /// Freezes the map into a read-only map, which is sound to be shared across threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::{sync::Arc, thread};
+    ///
+    /// let map: RbTreeMap<_, _> = (0..10).map(|i| (i, i * i)).collect();
+    /// let frozen = Arc::new(map.freeze());
+    ///
+    /// let handle = {
+    ///     let frozen = Arc::clone(&frozen);
+    ///     thread::spawn(move || frozen.get(&3).copied())
+    /// };
+    /// assert_eq!(handle.join().unwrap(), Some(9));
+    /// ```
+    #[inline]
+    pub fn freeze(self) -> FrozenRbTreeMap<K, V> {
+        FrozenRbTreeMap(self)
+    }
+}
+
+/// A read-only map created by [`RbTreeMap::freeze`]. It provides only the read operations, and [`thaw`](Self::thaw) gives back the mutable map.
+pub struct FrozenRbTreeMap<K, V>(RbTreeMap<K, V>);
+
+impl<K, V> FrozenRbTreeMap<K, V> {
+    /// Thaws the map into the mutable map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(1, "a")]).freeze();
+    ///
+    /// let mut map = frozen.thaw();
+    /// map.insert(2, "b");
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    #[inline]
+    pub fn thaw(self) -> RbTreeMap<K, V> {
+        self.0
+    }
+
+    /// Returns the number of elements in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(1, "a"), (2, "b")]).freeze();
+    /// assert_eq!(frozen.len(), 2);
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::<i32, ()>::new().freeze();
+    /// assert!(frozen.is_empty());
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(2, "b"), (1, "a")]).freeze();
+    /// assert!(frozen.iter().eq([(&1, &"a"), (&2, &"b")]));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.0.iter()
+    }
+
+    /// Gets an iterator over the keys of the map, in sorted order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(2, "b"), (1, "a")]).freeze();
+    /// assert!(frozen.keys().eq(&[1, 2]));
+    /// ```
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        self.0.keys()
+    }
+
+    /// Gets an iterator over the values of the map, in order by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(2, "b"), (1, "a")]).freeze();
+    /// assert!(frozen.values().eq(&["a", "b"]));
+    /// ```
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        self.0.values()
+    }
+}
+
+impl<K: Ord, V> FrozenRbTreeMap<K, V> {
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(1, "a")]).freeze();
+    /// assert_eq!(frozen.get(&1), Some(&"a"));
+    /// assert_eq!(frozen.get(&2), None);
+    /// ```
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.0.get(key)
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(1, "a")]).freeze();
+    /// assert_eq!(frozen.get_key_value(&1), Some((&1, &"a")));
+    /// ```
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.0.get_key_value(key)
+    }
+
+    /// Returns whether the map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(1, "a")]).freeze();
+    /// assert!(frozen.contains_key(&1));
+    /// assert!(!frozen.contains_key(&2));
+    /// ```
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.0.contains_key(key)
+    }
+
+    /// Returns the first key-value pair in the map. The key in this pair is the minimum key in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(2, "b"), (1, "a")]).freeze();
+    /// assert_eq!(frozen.first(), Some((&1, &"a")));
+    /// ```
+    #[inline]
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.0.first()
+    }
+
+    /// Returns the last key-value pair in the map. The key in this pair is the maximum key in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen = RbTreeMap::from_iter([(2, "b"), (1, "a")]).freeze();
+    /// assert_eq!(frozen.last(), Some((&2, &"b")));
+    /// ```
+    #[inline]
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.0.last()
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let frozen: RbTreeMap<_, _> = (0..10).map(|i| (i, i)).collect();
+    /// let frozen = frozen.freeze();
+    /// assert!(frozen.range(3..6).map(|(&k, _)| k).eq(3..6));
+    /// ```
+    #[inline]
+    pub fn range<I, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        I: Ord + ?Sized,
+        K: Borrow<I>,
+        R: RangeBounds<I>,
+    {
+        self.0.range(range)
+    }
+}
+
+impl<K: fmt::Debug + Ord, V: fmt::Debug> fmt::Debug for FrozenRbTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<K, V> From<RbTreeMap<K, V>> for FrozenRbTreeMap<K, V> {
+    fn from(map: RbTreeMap<K, V>) -> Self {
+        map.freeze()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a FrozenRbTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
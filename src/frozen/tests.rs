@@ -0,0 +1,36 @@
+use crate::RbTreeMap;
+
+use std::{sync::Arc, thread};
+
+#[test]
+fn share_across_threads() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..10_000u32).map(|k| (k, k.to_string())));
+    let frozen = Arc::new(map.freeze());
+
+    let handles: Vec<_> = (0..8u32)
+        .map(|t| {
+            let frozen = Arc::clone(&frozen);
+            thread::spawn(move || {
+                let mut hits = 0;
+                for k in (t..10_000).step_by(8) {
+                    if frozen.get(&k) == Some(&k.to_string()) {
+                        hits += 1;
+                    }
+                }
+                let lo = t * 1000;
+                let sum: u32 = frozen.range(lo..lo + 100).map(|(&k, _)| k).sum();
+                (hits, sum)
+            })
+        })
+        .collect();
+    for (t, handle) in (0..8u32).zip(handles) {
+        let (hits, sum) = handle.join().unwrap();
+        assert_eq!(hits, (t..10_000).step_by(8).count());
+        assert_eq!(sum, (t * 1000..t * 1000 + 100).sum::<u32>());
+    }
+
+    let mut map = Arc::into_inner(frozen).unwrap().thaw();
+    map.insert(10_000, "new".to_string());
+    assert_eq!(map.len(), 10_001);
+}
@@ -0,0 +1,221 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    map::iter::{Iter, Values},
+    RbTreeMap,
+};
+
+use std::{borrow::Borrow, fmt};
+
+/// A map based on a red-black tree, which derives the key of each value by a projection function. It is useful for indexing structs by a field without duplicating the field as the key.
+///
+/// The values are never accessed mutably, because modifying a value may change its key and break the order of the tree. Remove the value and insert it again to modify it.
+pub struct RbTreeIndex<K, V, P> {
+    map: RbTreeMap<K, V>,
+    projection: P,
+}
+
+impl<K: fmt::Debug + Ord, V: fmt::Debug, P> fmt::Debug for RbTreeIndex<K, V, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.map.iter()).finish()
+    }
+}
+
+impl<K: Ord, V, P: Fn(&V) -> K> Extend<V> for RbTreeIndex<K, V, P> {
+    fn extend<T: IntoIterator<Item = V>>(&mut self, iter: T) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<K, V, P> RbTreeIndex<K, V, P> {
+    /// Creates an empty `RbTreeIndex` with the projection from a value to its key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|s: &String| s.len());
+    ///
+    /// index.insert("foo".to_owned());
+    /// ```
+    #[inline]
+    pub const fn new(projection: P) -> Self {
+        Self {
+            map: RbTreeMap::new(),
+            projection,
+        }
+    }
+
+    /// Returns the number of values in the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// assert_eq!(index.len(), 0);
+    /// index.insert((1, "a"));
+    /// assert_eq!(index.len(), 1);
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the index contains no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// assert!(index.is_empty());
+    /// index.insert((1, "a"));
+    /// assert!(!index.is_empty());
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Gets an iterator over the keys and the values of the index, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// index.insert((2, "b"));
+    /// index.insert((1, "a"));
+    ///
+    /// assert!(index.iter().eq([(&1, &(1, "a")), (&2, &(2, "b"))]));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.map.iter()
+    }
+
+    /// Gets an iterator over the values of the index, in order by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// index.insert((2, "b"));
+    /// index.insert((1, "a"));
+    ///
+    /// assert!(index.values().eq(&[(1, "a"), (2, "b")]));
+    /// ```
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        self.map.values()
+    }
+
+    /// Consumes the index and returns the map from the derived keys to the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// index.insert((1, "a"));
+    ///
+    /// let map = index.into_map();
+    /// assert_eq!(map[&1], (1, "a"));
+    /// ```
+    #[inline]
+    pub fn into_map(self) -> RbTreeMap<K, V> {
+        self.map
+    }
+}
+
+impl<K: Ord, V, P: Fn(&V) -> K> RbTreeIndex<K, V, P> {
+    /// Inserts a value with the key derived by the projection. If the index had a value of the key, it is replaced and returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// assert_eq!(index.insert((1, "a")), None);
+    /// assert_eq!(index.insert((1, "b")), Some((1, "a")));
+    /// assert_eq!(index.get(&1), Some(&(1, "b")));
+    /// ```
+    pub fn insert(&mut self, value: V) -> Option<V> {
+        let key = (self.projection)(&value);
+        self.map.insert(key, value).map(|(_, old)| old)
+    }
+
+    /// Returns a reference to the value whose derived key equals to `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// index.insert((1, "a"));
+    /// assert_eq!(index.get(&1), Some(&(1, "a")));
+    /// assert_eq!(index.get(&2), None);
+    /// ```
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get(key)
+    }
+
+    /// Returns whether the index contains a value whose derived key equals to `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// index.insert((1, "a"));
+    /// assert!(index.contains_key(&1));
+    /// assert!(!index.contains_key(&2));
+    /// ```
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Removes the value whose derived key equals to `key` from the index, and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeIndex;
+    ///
+    /// let mut index = RbTreeIndex::new(|&(id, _): &(u32, &str)| id);
+    /// index.insert((1, "a"));
+    /// assert_eq!(index.remove(&1), Some((1, "a")));
+    /// assert_eq!(index.remove(&1), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(key)
+    }
+}
@@ -0,0 +1,36 @@
+use super::RbTreeIndex;
+
+#[derive(Debug, Clone, PartialEq)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[test]
+fn look_up_structs_by_id() {
+    let mut users = RbTreeIndex::new(|user: &User| user.id);
+    users.extend([7, 3, 9, 1].map(|id| User {
+        id,
+        name: format!("user{id}"),
+    }));
+    assert_eq!(users.len(), 4);
+    assert_eq!(users.get(&3).map(|u| u.name.as_str()), Some("user3"));
+    assert!(users.get(&4).is_none());
+    assert!(users.values().map(|u| u.id).eq([1, 3, 7, 9]));
+
+    // the key is derived again when the value is replaced
+    let old = users.insert(User {
+        id: 7,
+        name: "renamed".to_owned(),
+    });
+    assert_eq!(old.map(|u| u.name), Some("user7".to_owned()));
+    assert_eq!(users.get(&7).map(|u| u.name.as_str()), Some("renamed"));
+    assert_eq!(users.len(), 4);
+
+    assert_eq!(users.remove(&1).map(|u| u.id), Some(1));
+    assert!(!users.contains_key(&1));
+    assert!(users.iter().all(|(&id, user)| id == user.id));
+
+    let map = users.into_map();
+    assert!(map.keys().eq(&[3, 7, 9]));
+}
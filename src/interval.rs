@@ -0,0 +1,278 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{node::Node, RbTreeMap};
+
+use std::{fmt, iter::FusedIterator, marker::PhantomData, ops::Range};
+
+/// A map from half-open intervals `[start, end)` to values, based on a red-black tree.
+///
+/// Each node is augmented with the maximum `end` in its subtree, so queries for intervals overlapping a range can skip subtrees that end before it.
+pub struct RbIntervalMap<K, V> {
+    map: RbTreeMap<(K, K), Augmented<K, V>>,
+}
+
+struct Augmented<K, V> {
+    value: V,
+    max_end: K,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RbIntervalMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.map.iter().map(|(k, aug)| (k, &aug.value)))
+            .finish()
+    }
+}
+
+impl<K, V> Default for RbIntervalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> RbIntervalMap<K, V> {
+    /// Creates an empty `RbIntervalMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbIntervalMap;
+    ///
+    /// let mut map = RbIntervalMap::new();
+    ///
+    /// map.insert((1, 3), "a");
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            map: RbTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of intervals in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbIntervalMap;
+    ///
+    /// let mut map = RbIntervalMap::new();
+    /// assert_eq!(map.len(), 0);
+    /// map.insert((1, 3), "a");
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns whether the map contains no intervals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbIntervalMap;
+    ///
+    /// let mut map = RbIntervalMap::new();
+    /// assert!(map.is_empty());
+    /// map.insert((1, 3), "a");
+    /// assert!(!map.is_empty());
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<K: Ord + Clone, V> RbIntervalMap<K, V> {
+    /// Inserts an interval `[start, end)` with its value into the map. Then the old value of the equal interval is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start > end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbIntervalMap;
+    ///
+    /// let mut map = RbIntervalMap::new();
+    /// assert_eq!(map.insert((1, 3), "a"), None);
+    /// assert_eq!(map.insert((1, 3), "b"), Some("a"));
+    /// assert_eq!(map.get(&(1, 3)), Some(&"b"));
+    /// ```
+    pub fn insert(&mut self, interval: (K, K), value: V) -> Option<V> {
+        assert!(
+            interval.0 <= interval.1,
+            "interval start must not exceed end"
+        );
+        let max_end = interval.1.clone();
+        let aug = Augmented { value, max_end };
+        match self.map.root.insert_node(interval, aug) {
+            Ok(node) => {
+                fix_max_end(node);
+                None
+            }
+            Err((interval, old)) => {
+                // the replaced entry is also stamped with its own end, so it needs recalculation.
+                let node = self.map.root.search(&interval).unwrap().ok().unwrap();
+                fix_max_end(node);
+                Some(old.value)
+            }
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the interval.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbIntervalMap;
+    ///
+    /// let mut map = RbIntervalMap::new();
+    /// map.insert((1, 3), "a");
+    /// assert_eq!(map.get(&(1, 3)), Some(&"a"));
+    /// assert_eq!(map.get(&(1, 4)), None);
+    /// ```
+    pub fn get(&self, interval: &(K, K)) -> Option<&V> {
+        self.map.get(interval).map(|aug| &aug.value)
+    }
+
+    /// Removes an interval from the map, returning the old value if the interval was in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbIntervalMap;
+    ///
+    /// let mut map = RbIntervalMap::new();
+    /// map.insert((1, 3), "a");
+    /// assert_eq!(map.remove(&(1, 3)), Some("a"));
+    /// assert_eq!(map.remove(&(1, 3)), None);
+    /// ```
+    pub fn remove(&mut self, interval: &(K, K)) -> Option<V> {
+        let to_remove = self.map.root.search(interval)?.ok()?;
+        // The nodes whose subtree changes by the removal are on the path from the parent of the spliced position to the root, or children of them. `Root::remove_node` swaps `to_remove` with the maximum node in the left if it has two children, so the spliced position is there.
+        let fix_from = if let (Some(left), Some(_)) = to_remove.children() {
+            let max_in_left = left.max_child();
+            if left == max_in_left {
+                Some(max_in_left)
+            } else {
+                max_in_left.parent()
+            }
+        } else {
+            to_remove
+                .parent()
+                .or_else(|| to_remove.left().or_else(|| to_remove.right()))
+        };
+        let (_, aug) = self.map.root.remove_node(interval)?;
+        if let Some(fix_from) = fix_from {
+            fix_max_end(fix_from);
+        }
+        Some(aug.value)
+    }
+
+    /// Constructs an iterator over the entries whose interval overlaps `query`, in ascending order of the intervals.
+    ///
+    /// Subtrees whose maximum end does not exceed the start of `query` are skipped, so it takes `O(log n + k)` time for `k` overlapping entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbIntervalMap;
+    ///
+    /// let mut map = RbIntervalMap::new();
+    /// map.insert((1, 3), "a");
+    /// map.insert((2, 6), "b");
+    /// map.insert((7, 9), "c");
+    ///
+    /// let found: Vec<_> = map.overlapping(4..8).collect();
+    /// assert_eq!(found, [(&(2, 6), &"b"), (&(7, 9), &"c")]);
+    /// ```
+    pub fn overlapping(&self, query: Range<K>) -> Overlapping<'_, K, V> {
+        let mut iter = Overlapping {
+            stack: vec![],
+            query,
+            _phantom: PhantomData,
+        };
+        iter.push_left_edge(self.map.root.inner());
+        iter
+    }
+}
+
+/// Recalculates the maximum ends from `start` to the root. The children of the nodes on the path are recalculated too, because the rotations may move nodes into there.
+fn fix_max_end<K: Ord + Clone, V>(start: Node<(K, K), Augmented<K, V>>) {
+    let mut current = Some(start);
+    while let Some(node) = current {
+        if let Some(left) = node.left() {
+            update_max_end(left);
+        }
+        if let Some(right) = node.right() {
+            update_max_end(right);
+        }
+        update_max_end(node);
+        current = node.parent();
+    }
+}
+
+fn update_max_end<K: Ord + Clone, V>(node: Node<(K, K), Augmented<K, V>>) {
+    // Safety: The references are dropped before returning.
+    unsafe {
+        let mut max_end = &node.key::<(K, K)>().1;
+        for child in [node.left(), node.right()].into_iter().flatten() {
+            let child_max = &child.value().max_end;
+            if max_end < child_max {
+                max_end = child_max;
+            }
+        }
+        let max_end = max_end.clone();
+        node.value_mut().max_end = max_end;
+    }
+}
+
+/// An iterator over the entries of a `RbIntervalMap` which overlap a query range.
+///
+/// This `struct` is created by the [`RbIntervalMap::overlapping`] method.
+pub struct Overlapping<'a, K, V> {
+    stack: Vec<Node<(K, K), Augmented<K, V>>>,
+    query: Range<K>,
+    _phantom: PhantomData<&'a (K, V)>,
+}
+
+impl<K: Ord, V> Overlapping<'_, K, V> {
+    fn push_left_edge(&mut self, mut node: Option<Node<(K, K), Augmented<K, V>>>) {
+        while let Some(curr) = node {
+            // Safety: The reference is dropped soon.
+            if unsafe { curr.value() }.max_end <= self.query.start {
+                // all of the subtree ends before the query.
+                break;
+            }
+            self.stack.push(curr);
+            node = curr.left();
+        }
+    }
+}
+
+impl<'a, K: Ord + 'a, V: 'a> Iterator for Overlapping<'a, K, V> {
+    type Item = (&'a (K, K), &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(curr) = self.stack.pop() {
+            // Safety: The reference will not live longer than the map.
+            let (interval, aug) = unsafe { curr.key_value() };
+            if self.query.end <= interval.0 {
+                // the rest of nodes start after the query.
+                self.stack.clear();
+                return None;
+            }
+            self.push_left_edge(curr.right());
+            if self.query.start < interval.1 {
+                return Some((interval, &aug.value));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, K: Ord + 'a, V: 'a> FusedIterator for Overlapping<'a, K, V> {}
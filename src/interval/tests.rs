@@ -0,0 +1,84 @@
+use super::{Augmented, RbIntervalMap};
+use crate::{node::Node, tests::rng};
+
+fn assert_max_end(map: &RbIntervalMap<u32, u32>) {
+    fn check(node: Option<Node<(u32, u32), Augmented<u32, u32>>>) -> u32 {
+        let node = if let Some(node) = node {
+            node
+        } else {
+            return 0;
+        };
+        let expected = node
+            .key::<(u32, u32)>()
+            .1
+            .max(check(node.left()))
+            .max(check(node.right()));
+        assert_eq!(unsafe { node.value() }.max_end, expected);
+        expected
+    }
+    check(map.map.root.inner());
+}
+
+#[test]
+fn overlapping() {
+    let mut map = RbIntervalMap::new();
+    map.insert((0, 2), 'a');
+    map.insert((1, 5), 'b');
+    map.insert((3, 4), 'c');
+    map.insert((6, 8), 'd');
+    map.insert((9, 12), 'e');
+    map.insert((10, 11), 'f');
+
+    let found = |map: &RbIntervalMap<_, _>, query| {
+        map.overlapping(query).map(|(_, v)| *v).collect::<Vec<_>>()
+    };
+    assert_eq!(found(&map, 2..3), vec!['b']);
+    assert_eq!(found(&map, 3..7), vec!['b', 'c', 'd']);
+    assert_eq!(found(&map, 5..6), Vec::<char>::new());
+    assert_eq!(found(&map, 8..9), Vec::<char>::new());
+    assert_eq!(found(&map, 10..11), vec!['e', 'f']);
+    assert_eq!(found(&map, 0..100), vec!['a', 'b', 'c', 'd', 'e', 'f']);
+    assert_eq!(found(&map, 12..13), Vec::<char>::new());
+
+    assert_eq!(map.remove(&(1, 5)), Some('b'));
+    assert_eq!(found(&map, 2..3), Vec::<char>::new());
+    assert_eq!(found(&map, 3..7), vec!['c', 'd']);
+}
+
+#[test]
+fn random_against_brute_force() {
+    let mut rand = rng(0x2545_f491);
+    let mut map = RbIntervalMap::new();
+    let mut expected = vec![];
+    for i in 0..1000 {
+        let start = rand() % 500;
+        let interval = (start, start + rand() % 50);
+        if rand().is_multiple_of(3) {
+            if let Some(pos) = expected.iter().position(|&(k, _)| k == interval) {
+                let (_, v) = expected.remove(pos);
+                assert_eq!(map.remove(&interval), Some(v));
+            } else {
+                assert_eq!(map.remove(&interval), None);
+            }
+        } else {
+            expected.retain(|&(k, _)| k != interval);
+            expected.push((interval, i));
+            map.insert(interval, i);
+        }
+        assert_max_end(&map);
+        expected.sort_unstable();
+
+        let query_start = rand() % 550;
+        let query = query_start..query_start + rand() % 30;
+        let found: Vec<_> = map
+            .overlapping(query.clone())
+            .map(|(k, v)| (*k, *v))
+            .collect();
+        let brute: Vec<_> = expected
+            .iter()
+            .copied()
+            .filter(|&((s, e), _)| s < query.end && query.start < e)
+            .collect();
+        assert_eq!(found, brute);
+    }
+}
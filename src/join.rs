@@ -0,0 +1,185 @@
+//! Joins over the maps sorted by the same key.
+
+use std::{cmp::Reverse, collections::BinaryHeap, iter::FusedIterator};
+
+use crate::{
+    map::iter::{Iter, ZipKeys},
+    RbTreeMap,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// A value which is present in the left, the right or the both sides of a join.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EitherOrBoth<A, B> {
+    /// The key is only in the left map.
+    Left(A),
+    /// The key is only in the right map.
+    Right(B),
+    /// The key is in the both maps.
+    Both(A, B),
+}
+
+/// Joins `a` and `b` by key, as the full outer merge join of SQL. The returned iterator yields each distinct key with the values of the key in `a` and `b`, in ascending order of key.
+///
+/// It walks the both maps only once, so it takes `O(m + n)` time in total and allocates nothing.
+///
+/// # Examples
+///
+/// ```
+/// use rb_tree::{
+///     join::{merge_join, EitherOrBoth},
+///     RbTreeMap,
+/// };
+///
+/// let names: RbTreeMap<_, _> = [(1, "alice"), (2, "bob")].into_iter().collect();
+/// let scores: RbTreeMap<_, _> = [(2, 80), (3, 95)].into_iter().collect();
+///
+/// let mut join = merge_join(&names, &scores);
+/// assert_eq!(join.next(), Some((&1, EitherOrBoth::Left(&"alice"))));
+/// assert_eq!(join.next(), Some((&2, EitherOrBoth::Both(&"bob", &80))));
+/// assert_eq!(join.next(), Some((&3, EitherOrBoth::Right(&95))));
+/// assert_eq!(join.next(), None);
+/// ```
+pub fn merge_join<'a, K: Ord, V, W>(
+    a: &'a RbTreeMap<K, V>,
+    b: &'a RbTreeMap<K, W>,
+) -> MergeJoin<'a, K, V, W> {
+    MergeJoin(a.zip_keys(b))
+}
+
+/// An iterator over the rows of the merge join of two maps, created by [`merge_join`].
+pub struct MergeJoin<'a, K, V, W>(ZipKeys<'a, K, V, W>);
+
+impl<K, V, W> Clone for MergeJoin<'_, K, V, W> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'a, K: Ord, V, W> Iterator for MergeJoin<'a, K, V, W> {
+    type Item = (&'a K, EitherOrBoth<&'a V, &'a W>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.0.next()? {
+            (key, Some(left), None) => (key, EitherOrBoth::Left(left)),
+            (key, None, Some(right)) => (key, EitherOrBoth::Right(right)),
+            (key, Some(left), Some(right)) => (key, EitherOrBoth::Both(left, right)),
+            (_, None, None) => unreachable!("one of the values must be present"),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<K: Ord, V, W> FusedIterator for MergeJoin<'_, K, V, W> {}
+
+/// Merges the entries of `maps` into one stream in ascending order of key, as the multi-shard scan does. The entries of an equal key in different maps are yielded separately, in the order of the maps in `maps`.
+///
+/// It keeps the next entry of each map in a binary heap, so it takes `O(n log k)` time in total for `n` entries over `k` maps.
+///
+/// # Examples
+///
+/// ```
+/// use rb_tree::{join::k_way_merge, RbTreeMap};
+///
+/// let shards: [RbTreeMap<_, _>; 3] = [
+///     [(1, "a"), (4, "d")].into_iter().collect(),
+///     [(2, "b"), (4, "D")].into_iter().collect(),
+///     [(3, "c")].into_iter().collect(),
+/// ];
+///
+/// let keys: Vec<_> = k_way_merge(&shards).map(|(&k, &v)| (k, v)).collect();
+/// assert_eq!(keys, [(1, "a"), (2, "b"), (3, "c"), (4, "d"), (4, "D")]);
+/// ```
+pub fn k_way_merge<K: Ord, V>(maps: &[RbTreeMap<K, V>]) -> KWayMerge<'_, K, V> {
+    let mut iters: Vec<_> = maps.iter().map(RbTreeMap::iter).collect();
+    let heap = iters
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(source, iter)| Some(Reverse(Head::new(iter.next()?, source))))
+        .collect();
+    KWayMerge { iters, heap }
+}
+
+/// The next entry of a map in [`KWayMerge`], ordered by the key and then the index of the map.
+struct Head<'a, K, V> {
+    key: &'a K,
+    value: &'a V,
+    source: usize,
+}
+
+impl<'a, K, V> Head<'a, K, V> {
+    fn new((key, value): (&'a K, &'a V), source: usize) -> Self {
+        Self { key, value, source }
+    }
+}
+
+impl<K, V> Clone for Head<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self { ..*self }
+    }
+}
+
+impl<K: Ord, V> PartialEq for Head<'_, K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<K: Ord, V> Eq for Head<'_, K, V> {}
+
+impl<K: Ord, V> PartialOrd for Head<'_, K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, V> Ord for Head<'_, K, V> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.key, self.source).cmp(&(other.key, other.source))
+    }
+}
+
+/// An iterator over the entries of several maps in ascending order of key, created by [`k_way_merge`].
+pub struct KWayMerge<'a, K, V> {
+    iters: Vec<Iter<'a, K, V>>,
+    heap: BinaryHeap<Reverse<Head<'a, K, V>>>,
+}
+
+impl<K: Ord, V> Clone for KWayMerge<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            iters: self.iters.clone(),
+            heap: self.heap.clone(),
+        }
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for KWayMerge<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(head) = self.heap.pop()?;
+        if let Some(next) = self.iters[head.source].next() {
+            self.heap.push(Reverse(Head::new(next, head.source)));
+        }
+        Some((head.key, head.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<K: Ord, V> ExactSizeIterator for KWayMerge<'_, K, V> {
+    fn len(&self) -> usize {
+        self.heap.len() + self.iters.iter().map(ExactSizeIterator::len).sum::<usize>()
+    }
+}
+
+impl<K: Ord, V> FusedIterator for KWayMerge<'_, K, V> {}
@@ -0,0 +1,74 @@
+use super::{k_way_merge, merge_join, EitherOrBoth};
+use crate::RbTreeMap;
+
+#[test]
+fn merge_join_rows() {
+    let a: RbTreeMap<i32, char> = [(1, 'a'), (3, 'c'), (5, 'e'), (6, 'f')]
+        .into_iter()
+        .collect();
+    let b: RbTreeMap<i32, u8> = [(2, 2), (3, 3), (6, 6), (9, 9)].into_iter().collect();
+
+    let rows: Vec<_> = merge_join(&a, &b).collect();
+    assert_eq!(
+        rows,
+        [
+            (&1, EitherOrBoth::Left(&'a')),
+            (&2, EitherOrBoth::Right(&2)),
+            (&3, EitherOrBoth::Both(&'c', &3)),
+            (&5, EitherOrBoth::Left(&'e')),
+            (&6, EitherOrBoth::Both(&'f', &6)),
+            (&9, EitherOrBoth::Right(&9)),
+        ]
+    );
+    assert!(rows.windows(2).all(|pair| pair[0].0 < pair[1].0));
+}
+
+#[test]
+fn merge_join_one_sided() {
+    let a: RbTreeMap<i32, i32> = (0..5).map(|k| (k, k)).collect();
+    let empty = RbTreeMap::<i32, ()>::new();
+
+    assert!(merge_join(&a, &empty).eq(a.iter().map(|(k, v)| (k, EitherOrBoth::Left(v)))));
+    assert!(merge_join(&empty, &a).all(|(_, row)| matches!(row, EitherOrBoth::Right(_))));
+    assert_eq!(merge_join(&empty, &empty).next(), None);
+}
+
+#[test]
+fn merge_join_is_resumable() {
+    let a: RbTreeMap<i32, ()> = (0..10).step_by(2).map(|k| (k, ())).collect();
+    let b: RbTreeMap<i32, ()> = (0..10).step_by(3).map(|k| (k, ())).collect();
+
+    let mut join = merge_join(&a, &b);
+    assert_eq!(join.size_hint(), (5, Some(9)));
+    join.next();
+    let rest: Vec<_> = join.clone().map(|(&k, _)| k).collect();
+    assert_eq!(rest, [2, 3, 4, 6, 8, 9]);
+    assert!(join.map(|(&k, _)| k).eq(rest));
+}
+
+#[test]
+fn k_way_merge_keeps_multiplicity() {
+    let shards: Vec<RbTreeMap<u32, usize>> =
+        [(0..60).step_by(2), (0..60).step_by(3), (30..90).step_by(5)]
+            .into_iter()
+            .enumerate()
+            .map(|(shard, keys)| keys.map(|k| (k, shard)).collect())
+            .collect();
+
+    let mut expected: Vec<(u32, usize)> = shards
+        .iter()
+        .flat_map(|map| map.iter().map(|(&k, &v)| (k, v)))
+        .collect();
+    // the stable sort keeps the entries of an equal key in the order of the shards
+    expected.sort_by_key(|&(k, _)| k);
+
+    let merge = k_way_merge(&shards);
+    assert_eq!(merge.len(), expected.len());
+    let merged: Vec<_> = merge.map(|(&k, &v)| (k, v)).collect();
+    assert_eq!(merged, expected);
+    // 30 and 48 are in all of the shards except the third one for 48
+    assert_eq!(merged.iter().filter(|&&(k, _)| k == 30).count(), 3);
+    assert_eq!(merged.iter().filter(|&&(k, _)| k == 48).count(), 2);
+
+    assert_eq!(k_way_merge::<u32, ()>(&[]).next(), None);
+}
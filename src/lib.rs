@@ -1,10 +1,35 @@
+#[cfg(feature = "rkyv")]
+pub mod archive;
+pub mod arena;
 mod balance;
+#[cfg(feature = "borsh")]
+mod borsh;
+pub mod frozen;
+pub mod index;
+pub mod interval;
+pub mod join;
 pub mod map;
-mod mem;
+pub mod multimap;
+pub mod multiset;
 mod node;
+pub mod ord;
+#[cfg(feature = "serde")]
+pub mod persist;
+#[cfg(feature = "persistent")]
+pub mod persistent;
+pub mod pqueue;
+#[cfg(feature = "rayon")]
+mod rayon;
 pub mod set;
+pub mod sync;
 #[cfg(test)]
 mod tests;
 
-pub use map::RbTreeMap;
+pub use arena::ArenaRbTreeMap;
+pub use index::RbTreeIndex;
+pub use interval::RbIntervalMap;
+pub use map::{AllocError, DuplicateKey, OrderError, RbTreeMap};
+pub use multimap::RbTreeMultiMap;
+pub use multiset::RbTreeMultiSet;
+pub use pqueue::RbPriorityQueue;
 pub use set::RbTreeSet;
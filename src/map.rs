@@ -1,15 +1,56 @@
+pub mod cursor;
 pub mod entry;
 pub mod iter;
+pub mod raw_entry;
+pub mod rev;
+pub mod subtree;
 
-use crate::node::Root;
+use self::entry::Entry;
+use crate::node::{Node, Root};
 
-use std::{borrow::Borrow, fmt, hash, ops};
+use std::{borrow::Borrow, collections::BTreeMap, fmt, hash, ops};
 
 /// A map based on a red-black tree.
 pub struct RbTreeMap<K, V> {
     pub(crate) root: Root<K, V>,
 }
 
+/// The error returned by the fallible insertions such as [`RbTreeMap::try_insert_alloc`] when the allocator fails to allocate a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+impl std::error::Error for AllocError {}
+
+/// The error returned by [`RbTreeMap::insert_checked`] when the `Ord` implementation of the key is found to be inconsistent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderError;
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("inconsistent Ord implementation")
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+/// The error returned by [`RbTreeMap::try_from_iter`] when a key appears twice, which holds the duplicate key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateKey<K>(pub K);
+
+impl<K: fmt::Debug> fmt::Display for DuplicateKey<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "duplicate key: {:?}", self.0)
+    }
+}
+
+impl<K: fmt::Debug> std::error::Error for DuplicateKey<K> {}
+
 impl<K, V> Drop for RbTreeMap<K, V> {
     fn drop(&mut self) {
         // Safety: `self` will not be used after.
@@ -17,9 +58,29 @@ impl<K, V> Drop for RbTreeMap<K, V> {
     }
 }
 
+/// The maximum number of entries that `Debug` of [`RbTreeMap`] prints all of.
+const DEBUG_FULL_LIMIT: usize = 100;
+/// The number of entries that `Debug` of [`RbTreeMap`] prints on each end of a larger map.
+const DEBUG_EDGE_LEN: usize = 5;
+/// [`FromIterator`] for [`RbTreeMap`] sorts the items into a tree at once if the size hint is at least this, or inserts them one by one otherwise. [`Extend`] buffers the items if the size hint is at least this or the length of the map.
+const BULK_THRESHOLD: usize = 1024;
+/// [`RbTreeMap::remove_all`] searches each key if the map is larger than the keys by this ratio, or walks the whole map otherwise.
+const REMOVE_ALL_TIPPING_RATIO: usize = 16;
+
+/// A map of more than 100 entries is printed only with the first and last 5 entries, with a marker `…: (n total)` between them. It prevents a huge map from flooding logs by accident. The `full-debug` feature prints all of the entries always.
 impl<K: fmt::Debug + Ord, V: fmt::Debug> fmt::Debug for RbTreeMap<K, V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_map().entries(self.iter()).finish()
+        let len = self.len();
+        if cfg!(feature = "full-debug") || len <= DEBUG_FULL_LIMIT {
+            return f.debug_map().entries(self.iter()).finish();
+        }
+        // the first key of the last entries is found from the back, so the printing does not walk the whole map.
+        let (tail, _) = self.iter().nth_back(DEBUG_EDGE_LEN - 1).unwrap();
+        f.debug_map()
+            .entries(self.iter().take(DEBUG_EDGE_LEN))
+            .entry(&format_args!("…"), &format_args!("({len} total)"))
+            .entries(self.range((ops::Bound::Included(tail), ops::Bound::Unbounded)))
+            .finish()
     }
 }
 
@@ -29,19 +90,59 @@ impl<K, V> Default for RbTreeMap<K, V> {
     }
 }
 
+/// The later duplicates of a key overwrite the earlier ones, as [`RbTreeMap::insert`] does. Use [`RbTreeMap::from_iter_first_wins`] to keep the first ones instead.
+///
+/// If the lower bound of the size hint is at least 1024, the items are buffered, sorted and built into a tree at once, which is about 8× faster than inserting 1M unsorted items one by one as `benches/collect.rs` shows. Otherwise they are inserted one by one.
 impl<K: Ord, V> FromIterator<(K, V)> for RbTreeMap<K, V> {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        let mut tree = Self::new();
-        for (k, v) in iter {
-            tree.insert(k, v);
+        let iter = iter.into_iter();
+        if iter.size_hint().0 < BULK_THRESHOLD {
+            let mut tree = Self::new();
+            for (k, v) in iter {
+                tree.insert(k, v);
+            }
+            return tree;
+        }
+        let mut batch: Vec<(K, V)> = iter.collect();
+        // the stable sort keeps the duplicates in order, so the last one is moved into the kept slot.
+        batch.sort_by(|a, b| a.0.cmp(&b.0));
+        batch.dedup_by(|later, kept| {
+            let duplicate = later.0 == kept.0;
+            if duplicate {
+                std::mem::swap(later, kept);
+            }
+            duplicate
+        });
+        Self {
+            root: Root::from_sorted_iter(batch.into_iter()),
         }
-        tree
     }
 }
 
+/// If the lower bound of the size hint is at least 1024 or the length of the map, the items are buffered. If the buffered items are strictly ascending and greater than all of the keys in the map, they are built into a tree at once and joined to the map. Otherwise they are inserted one by one.
 impl<K: Ord, V> Extend<(K, V)> for RbTreeMap<K, V> {
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
-        for (k, v) in iter {
+        let iter = iter.into_iter();
+        let hint = iter.size_hint().0;
+        if hint == 0 || hint < BULK_THRESHOLD.min(self.len()) {
+            for (k, v) in iter {
+                self.insert(k, v);
+            }
+            return;
+        }
+        let batch: Vec<(K, V)> = iter.collect();
+        let ascending = batch.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        let after_last = match (self.last(), batch.first()) {
+            (Some((last, _)), Some((first, _))) => last < first,
+            _ => true,
+        };
+        if ascending && after_last {
+            self.join(Self {
+                root: Root::from_sorted_iter(batch.into_iter()),
+            });
+            return;
+        }
+        for (k, v) in batch {
             self.insert(k, v);
         }
     }
@@ -49,12 +150,22 @@ impl<K: Ord, V> Extend<(K, V)> for RbTreeMap<K, V> {
 
 impl<'a, K: Ord + Copy + 'a, V: Copy + 'a> Extend<(&'a K, &'a V)> for RbTreeMap<K, V> {
     fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
-        for (k, v) in iter {
-            self.insert(*k, *v);
-        }
+        self.extend(iter.into_iter().map(|(&k, &v)| (k, v)));
+    }
+}
+
+/// `clone` deep-clones the entries as [`RbTreeMap::snapshot`] does. `clone_from` reuses the nodes of `self` by cloning the entries into them, so refreshing a map repeatedly from another one does not churn the allocator.
+impl<K: Clone, V: Clone> Clone for RbTreeMap<K, V> {
+    fn clone(&self) -> Self {
+        self.snapshot()
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.root.clone_from_root(&source.root);
     }
 }
 
+/// Hashes the length and then all of the entries in ascending order of key, so it takes `O(n)` time for every call. Cache the hash on the caller side if the map is hashed repeatedly without modification.
 impl<K: hash::Hash, V: hash::Hash> hash::Hash for RbTreeMap<K, V> {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.root.len().hash(state);
@@ -92,6 +203,18 @@ impl<K: PartialEq, V: PartialEq> PartialEq for RbTreeMap<K, V> {
 
 impl<K: Eq, V: Eq> Eq for RbTreeMap<K, V> {}
 
+impl<K: PartialEq, V: PartialEq> PartialEq<BTreeMap<K, V>> for RbTreeMap<K, V> {
+    fn eq(&self, other: &BTreeMap<K, V>) -> bool {
+        self.root.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<RbTreeMap<K, V>> for BTreeMap<K, V> {
+    fn eq(&self, other: &RbTreeMap<K, V>) -> bool {
+        other == self
+    }
+}
+
 impl<K: PartialOrd, V: PartialOrd> PartialOrd for RbTreeMap<K, V> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         self.iter().partial_cmp(other.iter())
@@ -141,6 +264,26 @@ impl<K, V> RbTreeMap<K, V> {
         *self = Self::new();
     }
 
+    /// Removes all elements from the map and releases the memory held by the map, as [`ArenaRbTreeMap::clear_and_shrink`](crate::ArenaRbTreeMap::clear_and_shrink) does for the arena backend.
+    ///
+    /// Each node is allocated separately and the map caches nothing else, so [`clear`](Self::clear) already releases all of the memory. This is the same as `clear`, which frees the nodes by walking the tree without recursion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut a = RbTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.clear_and_shrink();
+    /// assert!(a.is_empty());
+    /// assert_eq!(a.memory_footprint(), std::mem::size_of_val(&a));
+    /// ```
+    #[inline]
+    pub fn clear_and_shrink(&mut self) {
+        self.clear();
+    }
+
     /// Returns whether the map contains no elements.
     ///
     /// # Examples
@@ -174,161 +317,170 @@ impl<K, V> RbTreeMap<K, V> {
     pub const fn len(&self) -> usize {
         self.root.len()
     }
-}
 
-impl<K: Ord, V> RbTreeMap<K, V> {
-    /// Moves all elements from `other` into `Self`, leaving `other` empty.
+    /// Returns an estimate of the bytes used by the map, that is the size of the map itself and its nodes. It takes `O(1)` time.
+    ///
+    /// The heap owned by the keys and values themselves, such as the buffer of `String`, is not included, and neither is the bookkeeping of the allocator.
     ///
     /// # Examples
     ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
-    /// let mut a = RbTreeMap::new();
-    /// a.insert(1, "a");
-    /// a.insert(2, "b");
-    /// a.insert(3, "c");
+    /// let mut map = RbTreeMap::<u64, u64>::new();
+    /// let empty = map.memory_footprint();
     ///
-    /// let mut b = RbTreeMap::new();
-    /// b.insert(3, "d");
-    /// b.insert(4, "e");
-    /// b.insert(5, "f");
+    /// map.insert(1, 10);
+    /// map.insert(2, 20);
+    /// assert!(map.memory_footprint() >= empty + 2 * 16);
+    /// ```
+    pub const fn memory_footprint(&self) -> usize {
+        std::mem::size_of::<Self>() + self.root.node_bytes()
+    }
+
+    /// Feeds the map into `state` as [`Hash`](hash::Hash) does. The order of entries fed is always ascending by key, so the maps with the same entries hash equally regardless of the order of their insertions.
     ///
-    /// a.append(&mut b);
+    /// It walks all of the entries, taking `O(n)` time.
     ///
-    /// assert_eq!(a.len(), 5);
-    /// assert_eq!(b.len(), 0);
+    /// # Examples
     ///
-    /// let drained: Vec<_> = a.into_iter().collect();
-    /// assert_eq!(drained, vec![
-    ///     (1, "a"),
-    ///     (2, "b"),
-    ///     (3, "d"),
-    ///     (4, "e"),
-    ///     (5, "f"),
-    /// ]);
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+    ///
+    /// let a: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    /// let b: RbTreeMap<_, _> = [(2, "b"), (1, "a")].into_iter().collect();
+    ///
+    /// let (mut ha, mut hb) = (DefaultHasher::new(), DefaultHasher::new());
+    /// a.stable_hash(&mut ha);
+    /// b.stable_hash(&mut hb);
+    /// assert_eq!(ha.finish(), hb.finish());
     /// ```
     #[inline]
-    pub fn append(&mut self, other: &mut Self) {
-        if other.is_empty() {
-            return;
-        }
-        if self.is_empty() {
-            std::mem::swap(self, other);
-            return;
-        }
-
-        for (k, v) in other.drain_filter(|_, _| true) {
-            self.insert(k, v);
-        }
+    pub fn stable_hash<H: hash::Hasher>(&self, state: &mut H)
+    where
+        K: hash::Hash,
+        V: hash::Hash,
+    {
+        hash::Hash::hash(self, state);
     }
 
-    /// Inserts a key-value pair into the map. Then the old value is returned.
+    /// Returns whether the entries of `self` are the leading entries of `other` in ascending order of key. An empty map is a prefix of any map, and a map is a prefix of itself.
+    ///
+    /// It compares the entries from the minimum and stops at the first mismatch, so it takes `O(min(m, n))` time at most.
     ///
     /// # Examples
     ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
-    /// let mut map = RbTreeMap::<i32, &str>::new();
-    /// assert_eq!(map.insert(37, "a"), None);
-    /// assert_eq!(map.is_empty(), false);
+    /// let path: RbTreeMap<_, _> = [(0, "usr"), (1, "lib")].into_iter().collect();
+    /// let file: RbTreeMap<_, _> = [(0, "usr"), (1, "lib"), (2, "libc.so")].into_iter().collect();
+    /// let other: RbTreeMap<_, _> = [(0, "usr"), (1, "bin")].into_iter().collect();
     ///
-    /// map.insert(37, "b");
-    /// assert_eq!(map.insert(37, "c"), Some((37, "b")));
-    /// assert_eq!(map[&37], "c");
+    /// assert!(path.is_prefix_of(&file));
+    /// assert!(!file.is_prefix_of(&path));
+    /// assert!(!other.is_prefix_of(&file));
     /// ```
-    #[inline]
-    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
-        self.root.insert_node(key, value).err()
+    pub fn is_prefix_of(&self, other: &Self) -> bool
+    where
+        K: PartialEq,
+        V: PartialEq,
+    {
+        self.len() <= other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
     }
 
-    /// Removes a key from the map, returning the old value if the key was in.
+    /// Returns whether the entries of the map equal to `slice` in ascending order of key, without building another map. It returns `false` immediately if the lengths differ, and takes `O(n)` time otherwise.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
     /// let mut map = RbTreeMap::new();
+    /// map.insert(2, "b");
     /// map.insert(1, "a");
-    /// assert_eq!(map.remove(&1), Some("a"));
-    /// assert_eq!(map.remove(&1), None);
+    ///
+    /// assert!(map.eq_sorted_slice(&[(1, "a"), (2, "b")]));
+    /// assert!(!map.eq_sorted_slice(&[(2, "b"), (1, "a")]));
+    /// assert!(!map.eq_sorted_slice(&[(1, "a")]));
     /// ```
-    #[inline]
-    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    pub fn eq_sorted_slice(&self, slice: &[(K, V)]) -> bool
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: PartialEq,
+        V: PartialEq,
     {
-        self.remove_entry(key).map(|(_, v)| v)
+        self.len() == slice.len()
+            && self
+                .iter()
+                .zip(slice)
+                .all(|((k, v), (sk, sv))| k == sk && v == sv)
     }
 
-    /// Removes a key from the map, returning the old key-value pair if the key was in.
+    /// Consumes the map and creates a new map with the same keys, whose values are mapped by `f` in ascending key order.
+    ///
+    /// The tree structure is reused as is, so this never compares the keys nor rebalances the tree.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
     /// let mut map = RbTreeMap::new();
     /// map.insert(1, "a");
-    /// assert_eq!(map.remove_entry(&1), Some((1, "a")));
-    /// assert_eq!(map.remove_entry(&1), None);
+    /// map.insert(2, "bb");
+    ///
+    /// let lengths = map.map_values(|v| v.len());
+    /// assert_eq!(lengths.into_iter().collect::<Vec<_>>(), vec![(1, 1), (2, 2)]);
     /// ```
-    #[inline]
-    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
-    where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
-    {
-        self.root.remove_node(key)
+    pub fn map_values<W, F: FnMut(V) -> W>(mut self, f: F) -> RbTreeMap<K, W> {
+        RbTreeMap {
+            root: std::mem::take(&mut self.root).map_values(f),
+        }
     }
 
-    /// Returns a reference to the value corresponding to the key.
+    /// Rebalances the map into a tree of the minimum height. The nodes are re-linked in place, so this never compares the keys nor reallocates the entries.
+    ///
+    /// Inserting and removing keep the tree balanced enough, so this is only a maintenance operation to call occasionally after heavy churn. It takes `O(n)` time.
     ///
     /// # Examples
     ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
-    /// let mut map = RbTreeMap::new();
-    /// map.insert(1, "a");
-    /// assert_eq!(map.get(&1), Some(&"a"));
-    /// assert_eq!(map.get(&2), None);
+    /// let mut map: RbTreeMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    /// map.retain(|&k, _| k % 7 == 0);
+    ///
+    /// map.rebuild();
+    /// assert_eq!(map.len(), 15);
+    /// assert!(map.keys().copied().eq((0..100).step_by(7)));
     /// ```
-    #[inline]
-    pub fn get<Q>(&self, key: &Q) -> Option<&V>
-    where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
-    {
-        self.get_key_value(key).map(|(_, v)| v)
+    pub fn rebuild(&mut self) {
+        self.root.rebuild();
     }
 
-    /// Returns a mutable reference ti the value corresponding to the key.
+    /// Does nothing and returns `false` always, because the tree is always balanced.
+    ///
+    /// Every insertion and removal of `RbTreeMap` restores the red-black invariants before returning, and the bulk constructions build a tree of the minimum height, so no rotation is ever pending and `budget` is unused. A latency-sensitive application may still call it once per frame as a maintenance hook, which returns immediately.
     ///
     /// # Examples
     ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
-    /// let mut map = RbTreeMap::new();
-    /// map.insert(1, "a");
-    /// if let Some(x) = map.get_mut(&1) {
-    ///     *x = "b";
-    /// }
-    /// assert_eq!(map[&1], "b");
+    /// let mut map: RbTreeMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    ///
+    /// assert!(!map.rebalance_incremental(8));
+    /// assert_eq!(map.len(), 100);
     /// ```
-    #[inline]
-    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
-    where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
-    {
-        self.root
-            .search(key)?
-            .ok()
-            .map(|n| unsafe { n.value_mut() })
+    pub fn rebalance_incremental(&mut self, budget: usize) -> bool {
+        let _ = budget;
+        false
     }
 
-    /// Returns the key-value pair corresponding to the supplied key.
+    /// Returns the numbers of the red nodes and the black nodes in the tree, in this order, for diagnosing the balance of the tree. The sum of them equals to the length of the map.
+    ///
+    /// It walks all of the nodes, taking `O(n)` time.
     ///
     /// # Examples
     ///
@@ -336,23 +488,19 @@ impl<K: Ord, V> RbTreeMap<K, V> {
     /// use rb_tree::RbTreeMap;
     ///
     /// let mut map = RbTreeMap::new();
-    /// map.insert(1, "a");
-    /// assert_eq!(map.get_key_value(&1), Some((&1, &"a")));
-    /// assert_eq!(map.get_key_value(&2), None);
+    /// assert_eq!(map.color_histogram(), (0, 0));
+    ///
+    /// map.extend((0..100).map(|i| (i, i)));
+    /// let (red, black) = map.color_histogram();
+    /// assert_eq!(red + black, 100);
     /// ```
-    #[inline]
-    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
-    where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
-    {
-        self.root
-            .search(key)?
-            .ok()
-            .map(|n| unsafe { n.key_value() })
+    pub fn color_histogram(&self) -> (usize, usize) {
+        self.root.color_histogram()
     }
 
-    /// Returns whether the map contains a value for the specified key.
+    /// Renders the structure of the tree into lines of the keys, for filing a bug report or understanding the rebalancing. Each node is drawn as `[k]` if black or `(k)` if red, followed by its left and right children indented one more level. A missing child of a node with one child is drawn as `-`.
+    ///
+    /// It walks the nodes with a stack instead of recursion, so a deep tree does not overflow the call stack. It is available with the `debug-asserts` feature.
     ///
     /// # Examples
     ///
@@ -360,95 +508,1084 @@ impl<K: Ord, V> RbTreeMap<K, V> {
     /// use rb_tree::RbTreeMap;
     ///
     /// let mut map = RbTreeMap::new();
-    /// map.insert(1, "a");
-    /// assert_eq!(map.contains_key(&1), true);
-    /// assert_eq!(map.contains_key(&2), false);
+    /// map.insert(1, ());
+    /// map.insert(2, ());
+    ///
+    /// assert_eq!(map.debug_tree(), "[1]\n  -\n  (2)\n");
     /// ```
-    #[inline]
-    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    #[cfg(feature = "debug-asserts")]
+    pub fn debug_tree(&self) -> String
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: fmt::Debug,
     {
-        self.get(key).is_some()
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        let mut stack: Vec<_> = self
+            .root
+            .inner()
+            .map(|root| (Some(root), 0))
+            .into_iter()
+            .collect();
+        while let Some((node, depth)) = stack.pop() {
+            let indent = "  ".repeat(depth);
+            let node = if let Some(node) = node {
+                node
+            } else {
+                // Writing into `String` never fails.
+                let _ = writeln!(out, "{indent}-");
+                continue;
+            };
+            let _ = if node.is_red() {
+                writeln!(out, "{indent}({:?})", node.key())
+            } else {
+                writeln!(out, "{indent}[{:?}]", node.key())
+            };
+            if let (None, None) = node.children() {
+                continue;
+            }
+            // the left child is drawn first, so it is pushed last.
+            stack.push((node.right(), depth + 1));
+            stack.push((node.left(), depth + 1));
+        }
+        out
     }
 
-    /// Retains only the elements specified by the predicate. In other words, remove all pairs `(k, v)` such that the predicate `f(&k, &mut v)` returns `false`.
+    /// Takes a snapshot of the map, which is not affected by the later mutations of the map. It deep-clones the entries into a new tree built at once, without comparing the keys.
+    ///
+    /// To take a snapshot in `O(1)` time, see `PersistentRbTreeMap` behind the `persistent` feature.
     ///
     /// # Examples
     ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
-    /// let mut map: RbTreeMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
-    /// map.retain(|&k, _| k % 2 == 0);
-    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![(0, 0), (2, 20), (4, 40), (6, 60)]);
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    ///
+    /// let snapshot = map.snapshot();
+    /// map.insert(1, "c");
+    /// map.remove(&2);
+    ///
+    /// assert!(snapshot.into_iter().eq([(1, "a"), (2, "b")]));
     /// ```
-    #[inline]
-    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
-        self.drain_filter(move |k, v| !f(k, v));
+    pub fn snapshot(&self) -> Self
+    where
+        K: Clone,
+        V: Clone,
+    {
+        Self {
+            root: Root::from_sorted_iter(self.iter().map(|(k, v)| (k.clone(), v.clone()))),
+        }
     }
+}
 
-    /// Returns the first key-value pair in the map. The key in this pair is the minimum key in the map.
+impl<K: Ord, V> RbTreeMap<K, V> {
+    /// Creates a map from the key-value pairs of `iter`, keeping the first occurrence of each key. It is the opposite of `collect` through [`FromIterator`], where the later duplicates overwrite the earlier ones.
     ///
     /// # Examples
     ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
-    /// let mut map = RbTreeMap::new();
-    /// assert_eq!(map.first(), None);
-    /// map.insert(1, "b");
-    /// map.insert(2, "a");
-    /// assert_eq!(map.first(), Some((&1, &"b")));
+    /// let map = RbTreeMap::from_iter_first_wins([(1, "a"), (2, "b"), (1, "c")]);
+    /// assert_eq!(map[&1], "a");
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (1, "c")].into_iter().collect();
+    /// assert_eq!(map[&1], "c");
     /// ```
-    pub fn first(&self) -> Option<(&K, &V)> {
-        Some(unsafe { self.root.inner()?.min_child().key_value() })
+    pub fn from_iter_first_wins<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.entry(key).or_insert(value);
+        }
+        map
     }
 
-    /// Returns the last key-value pair in the map. The key in this pair is the maximum key in the map.
+    /// Creates a map from the key-value pairs of `iter`, failing with the second occurrence of a key if any key appears twice. The pairs before the duplicate are dropped with the map.
     ///
     /// # Examples
     ///
     /// ```
-    /// use rb_tree::RbTreeMap;
+    /// use rb_tree::{map::DuplicateKey, RbTreeMap};
     ///
-    /// let mut map = RbTreeMap::new();
-    /// map.insert(1, "b");
-    /// map.insert(2, "a");
-    /// assert_eq!(map.last(), Some((&2, &"a")));
+    /// let map = RbTreeMap::try_from_iter([(1, "a"), (2, "b")]).unwrap();
+    /// assert_eq!(map.len(), 2);
+    ///
+    /// assert_eq!(RbTreeMap::try_from_iter([(1, "a"), (1, "b")]), Err(DuplicateKey(1)));
     /// ```
-    pub fn last(&self) -> Option<(&K, &V)> {
-        Some(unsafe { self.root.inner()?.max_child().key_value() })
-    }
-
-    pub fn first_mut(&mut self) -> Option<(&K, &mut V)> {
-        Some(unsafe { self.root.inner()?.min_child().key_value_mut() })
-    }
-
-    pub fn last_mut(&mut self) -> Option<(&K, &mut V)> {
-        Some(unsafe { self.root.inner()?.max_child().key_value_mut() })
+    pub fn try_from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Result<Self, DuplicateKey<K>> {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            match map.root.search(&key) {
+                Some(Ok(_)) => return Err(DuplicateKey(key)),
+                Some(Err(position)) => {
+                    map.root.insert_at(Some(position), key, value);
+                }
+                None => {
+                    map.root.insert_at(None, key, value);
+                }
+            }
+        }
+        Ok(map)
     }
 
-    /// Removes and returns the first element in the map. The key of this element is the minimum key that was in the map.
+    /// Moves all elements from `other` into `Self`, leaving `other` empty.
     ///
     /// # Examples
     ///
-    /// Draining elements in ascending order, while keeping a usable map each iteration.
-    ///
     /// ```
     /// use rb_tree::RbTreeMap;
     ///
-    /// let mut map = RbTreeMap::new();
-    /// map.insert(1, "a");
-    /// map.insert(2, "b");
-    /// while let Some((key, _val)) = map.pop_first() {
-    ///     assert!(map.iter().all(|(k, _v)| *k > key));
-    /// }
-    /// assert!(map.is_empty());
-    /// ```
-    pub fn pop_first(&mut self) -> Option<(K, V)> {
-        self.root.remove_min()
+    /// let mut a = RbTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    ///
+    /// let mut b = RbTreeMap::new();
+    /// b.insert(3, "d");
+    /// b.insert(4, "e");
+    /// b.insert(5, "f");
+    ///
+    /// a.append(&mut b);
+    ///
+    /// assert_eq!(a.len(), 5);
+    /// assert_eq!(b.len(), 0);
+    ///
+    /// let drained: Vec<_> = a.into_iter().collect();
+    /// assert_eq!(drained, vec![
+    ///     (1, "a"),
+    ///     (2, "b"),
+    ///     (3, "d"),
+    ///     (4, "e"),
+    ///     (5, "f"),
+    /// ]);
+    /// ```
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            std::mem::swap(self, other);
+            return;
+        }
+
+        for (k, v) in other.drain_filter(|_, _| true) {
+            self.insert(k, v);
+        }
+    }
+
+    /// Moves all elements from `other` into `self` as [`append`](Self::append) does, only if the keys of the both maps are disjoint. Otherwise it returns the keys in both maps in ascending order, and leaves the both maps unchanged.
+    ///
+    /// The conflicts are collected by walking the both maps together before moving anything, so it takes `O(m + n)` time in addition to the append.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut a: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    /// let mut b: RbTreeMap<_, _> = [(3, "c")].into_iter().collect();
+    /// assert_eq!(a.try_append(&mut b), Ok(()));
+    /// assert_eq!(a.len(), 3);
+    /// assert!(b.is_empty());
+    ///
+    /// let mut c: RbTreeMap<_, _> = [(2, "x"), (4, "d")].into_iter().collect();
+    /// assert_eq!(a.try_append(&mut c), Err(vec![2]));
+    /// assert_eq!(a[&2], "b");
+    /// assert_eq!(c.len(), 2);
+    /// ```
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), Vec<K>>
+    where
+        K: Clone,
+    {
+        let conflicts: Vec<K> = self
+            .zip_keys(other)
+            .filter_map(|(key, a, b)| a.and(b).map(|_| key.clone()))
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        self.append(other);
+        Ok(())
+    }
+
+    /// Moves all elements from `other` into `Self`, where all of the keys in `other` must be greater than the keys in `self`. It takes `O(log n)` time, unlike [`RbTreeMap::append`] which inserts the elements one by one.
+    ///
+    /// The order of the keys is checked only in debug builds. If it is violated in release builds, the map will be broken for searching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut a: RbTreeMap<_, _> = (0..3).map(|i| (i, i * 10)).collect();
+    /// let b: RbTreeMap<_, _> = (3..6).map(|i| (i, i * 10)).collect();
+    ///
+    /// a.join(b);
+    ///
+    /// assert_eq!(a.len(), 6);
+    /// assert!(a.keys().copied().eq(0..6));
+    /// ```
+    pub fn join(&mut self, mut other: Self) {
+        debug_assert!(
+            match (self.last(), other.first()) {
+                (Some((left, _)), Some((right, _))) => left < right,
+                _ => true,
+            },
+            "all keys of `other` must be greater than the keys of `self`"
+        );
+        let (key, value) = if let Some(entry) = other.root.remove_min() {
+            entry
+        } else {
+            return;
+        };
+        let left = std::mem::take(&mut self.root);
+        let right = std::mem::take(&mut other.root);
+        self.root = left.join(key, value, right);
+    }
+
+    /// Splits the map into the entries whose keys are less than `key`, the value at `key` if exists, and the entries whose keys are greater than `key`. The nodes are re-linked without moving the entries, and they can be recombined with [`RbTreeMap::join`].
+    ///
+    /// Re-linking the nodes takes `O(log n)` time, and counting the lengths of the new maps takes `O(min(k, n - k))` time for `k` entries less than `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+    ///
+    /// let (less, found, greater) = map.split(&4);
+    ///
+    /// assert!(less.keys().copied().eq(0..4));
+    /// assert_eq!(found, Some(40));
+    /// assert!(greater.keys().copied().eq(5..10));
+    /// ```
+    pub fn split<Q>(mut self, key: &Q) -> (Self, Option<V>, Self)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (less, found, greater) = std::mem::take(&mut self.root).split(key);
+        (
+            Self { root: less },
+            found.map(|(_, v)| v),
+            Self { root: greater },
+        )
+    }
+
+    /// Splits the map into two at the given key. Returns everything after the given key, including the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut a = RbTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(17, "d");
+    /// a.insert(41, "e");
+    ///
+    /// let b = a.split_off(&3);
+    ///
+    /// assert_eq!(a.len(), 2);
+    /// assert_eq!(b.len(), 3);
+    ///
+    /// assert_eq!(a[&1], "a");
+    /// assert_eq!(a[&2], "b");
+    ///
+    /// assert_eq!(b[&3], "c");
+    /// assert_eq!(b[&17], "d");
+    /// assert_eq!(b[&41], "e");
+    /// ```
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (less, found, greater) = std::mem::take(&mut self.root).split(key);
+        self.root = less;
+        let greater = if let Some((k, v)) = found {
+            Root::new().join(k, v, greater)
+        } else {
+            greater
+        };
+        Self { root: greater }
+    }
+
+    /// Merges the key-value pairs sorted by key in ascending order into the map. If the map had an equal key, both of the stored key and value are replaced with the new ones like [`RbTreeMap::insert`].
+    ///
+    /// The entries of the map and `iter` are merged in one in-order walk, and then the tree is rebuilt by re-linking the nodes. It takes `O(n + m)` time for `m` new entries, so it suits merging a large sorted batch rather than a few entries.
+    ///
+    /// The order of `iter` is checked only in debug builds. If it is violated in release builds, the map will be broken for searching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<_, _> = (0..10).step_by(2).map(|i| (i, "old")).collect();
+    ///
+    /// map.extend_sorted((5..10).map(|i| (i, "new")));
+    ///
+    /// assert_eq!(map.len(), 8);
+    /// assert_eq!(map[&4], "old");
+    /// assert_eq!(map[&6], "new");
+    /// assert!(map.keys().copied().eq([0, 2, 4, 5, 6, 7, 8, 9]));
+    /// ```
+    pub fn extend_sorted<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.root.extend_sorted(iter);
+    }
+
+    /// Extends the map with the key-value pairs of `iter`, but merges the value into the existing one by `combine` for the key already in the map, instead of replacing it. The existing key and value stay in place, and each key is searched only once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut count = RbTreeMap::new();
+    /// count.extend_or_modify(["a", "b", "a", "c", "a"].map(|w| (w, 1)), |n, m| *n += m);
+    ///
+    /// assert!(count.into_iter().eq([("a", 3), ("b", 1), ("c", 1)]));
+    /// ```
+    pub fn extend_or_modify<I, F>(&mut self, iter: I, mut combine: F)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        F: FnMut(&mut V, V),
+    {
+        for (key, value) in iter {
+            match self.entry(key) {
+                Entry::Occupied(mut entry) => combine(entry.get_mut(), value),
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+            }
+        }
+    }
+
+    /// Builds a map by cloning the key-value pairs in `slice`, which must be strictly ascending by key. The tree is built at once without comparing the keys, so it takes `O(n)` time rather than `O(n log n)` of [`FromIterator`].
+    ///
+    /// The order of `slice` is checked only in debug builds. If it is violated in release builds, the map will be broken for searching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let column = [(1, "a"), (2, "b"), (4, "d")];
+    /// let map = RbTreeMap::from_sorted_slice(&column);
+    ///
+    /// assert_eq!(map.len(), 3);
+    /// assert_eq!(map[&4], "d");
+    /// ```
+    pub fn from_sorted_slice(slice: &[(K, V)]) -> Self
+    where
+        K: Clone,
+        V: Clone,
+    {
+        debug_assert!(
+            slice.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "the slice must be strictly ascending by key"
+        );
+        Self {
+            root: Root::from_sorted_iter(slice.iter().cloned()),
+        }
+    }
+
+    /// Builds a map by moving the key-value pairs out of `vec`, which must be strictly ascending by key. It is the consuming variant of [`from_sorted_slice`](Self::from_sorted_slice), taking `O(n)` time.
+    ///
+    /// The order of `vec` is checked only in debug builds. If it is violated in release builds, the map will be broken for searching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let names = vec![("alice".to_string(), 1), ("bob".to_string(), 2)];
+    /// let map = RbTreeMap::from_sorted_vec(names);
+    ///
+    /// assert_eq!(map["bob"], 2);
+    /// ```
+    pub fn from_sorted_vec(vec: Vec<(K, V)>) -> Self {
+        debug_assert!(
+            vec.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "the vec must be strictly ascending by key"
+        );
+        Self {
+            root: Root::from_sorted_iter(vec.into_iter()),
+        }
+    }
+
+    /// Inserts a key-value pair into the map. If the map had an equal key, both of the stored key and value are replaced with the new ones, and the old pair is returned.
+    ///
+    /// Unlike [`std::collections::BTreeMap::insert`], the stored key is also refreshed. It matters when the equality of the keys is coarser than their contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::<i32, &str>::new();
+    /// assert_eq!(map.insert(37, "a"), None);
+    /// assert_eq!(map.is_empty(), false);
+    ///
+    /// map.insert(37, "b");
+    /// assert_eq!(map.insert(37, "c"), Some((37, "b")));
+    /// assert_eq!(map[&37], "c");
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.root.insert_node(key, value).err()
+    }
+
+    /// Inserts a key-value pair into the map as [`insert`](Self::insert) does, but verifies the `Ord` implementation of the key around the insertion point. The new key must be strictly greater than its predecessor and less than its successor, answered consistently by comparing in both directions. Otherwise `Err(OrderError)` is returned and the map is left unchanged.
+    ///
+    /// It is a safety net for hand-written comparators, which may break the map silently with [`insert`](Self::insert). The check costs a few more comparisons and a walk to the neighbors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// assert_eq!(map.insert_checked(1, "a"), Ok(None));
+    /// assert_eq!(map.insert_checked(2, "b"), Ok(None));
+    /// assert_eq!(map.insert_checked(1, "c"), Ok(Some((1, "a"))));
+    /// ```
+    pub fn insert_checked(&mut self, key: K, value: V) -> Result<Option<(K, V)>, OrderError> {
+        Ok(self.root.insert_node_checked(key, value)?.err())
+    }
+
+    /// Inserts a key-value pair into the map as [`insert`](Self::insert) does, but returns `Err(AllocError)` instead of aborting if the new node cannot be allocated. The map is left unchanged on the error, and the pair is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// assert_eq!(map.try_insert_alloc(37, "a"), Ok(None));
+    /// assert_eq!(map.try_insert_alloc(37, "b"), Ok(Some((37, "a"))));
+    /// assert_eq!(map[&37], "b");
+    /// ```
+    pub fn try_insert_alloc(&mut self, key: K, value: V) -> Result<Option<(K, V)>, AllocError> {
+        Ok(self.root.try_insert_node(key, value)?.err())
+    }
+
+    /// Inserts the key-value pairs of `iter` one by one as [`try_insert_alloc`](Self::try_insert_alloc) does. It stops at the first allocation failure and returns the error, keeping the pairs inserted before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// assert_eq!(map.try_extend([(2, "b"), (1, "a")]), Ok(()));
+    /// assert!(map.into_iter().eq([(1, "a"), (2, "b")]));
+    /// ```
+    pub fn try_extend<I: IntoIterator<Item = (K, V)>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), AllocError> {
+        for (key, value) in iter {
+            self.try_insert_alloc(key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Removes a key from the map, returning the old value if the key was in.
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.remove(&1), Some("a"));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    #[inline]
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the old key-value pair if the key was in.
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.remove_entry(&1), Some((1, "a")));
+    /// assert_eq!(map.remove_entry(&1), None);
+    /// ```
+    #[inline]
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.root.remove_node(key)
+    }
+
+    /// Removes all of the keys in `keys` from the map, and returns the number of the entries removed. The keys absent from the map and the duplicated keys are ignored.
+    ///
+    /// If there are few keys compared with the length of the map, each key is searched and removed. Otherwise the keys are sorted if not yet, and the map and the keys are walked together once, so it takes `O(n + m log m)` time at most rather than `O(m log n)` of repeated [`remove`](Self::remove).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<_, _> = (0..10).map(|k| (k, k * 10)).collect();
+    ///
+    /// assert_eq!(map.remove_all([8, 2, 4, 4, 42]), 3);
+    /// assert!(map.keys().copied().eq([0, 1, 3, 5, 6, 7, 9]));
+    /// ```
+    pub fn remove_all<Q, I>(&mut self, keys: I) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+        I: IntoIterator<Item = Q>,
+    {
+        let mut keys: Vec<Q> = keys.into_iter().collect();
+        if keys.len() <= self.len() / REMOVE_ALL_TIPPING_RATIO {
+            return keys
+                .iter()
+                .filter(|key| self.remove_entry(*key).is_some())
+                .count();
+        }
+        if !keys.windows(2).all(|pair| pair[0] <= pair[1]) {
+            keys.sort_unstable();
+        }
+        self.root.remove_sorted(&keys)
+    }
+
+    /// Removes a key from the map as [`remove`](Self::remove) does, returning the old value together with the clones of the keys which were just before and after the removed key.
+    ///
+    /// The neighbors are found from the node before it is unlinked, so the map is searched only once. It helps to keep an external structure of adjacent keys in sync with the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (3, "c"), (5, "e")].into_iter().collect();
+    ///
+    /// assert_eq!(map.remove_with_neighbors(&3), Some(("c", Some(1), Some(5))));
+    /// assert_eq!(map.remove_with_neighbors(&1), Some(("a", None, Some(5))));
+    /// assert_eq!(map.remove_with_neighbors(&1), None);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn remove_with_neighbors<Q>(&mut self, key: &Q) -> Option<(V, Option<K>, Option<K>)>
+    where
+        K: Borrow<Q> + Clone,
+        Q: Ord + ?Sized,
+    {
+        let to_remove = self.root.search(key)?.ok()?;
+        let prev = to_remove.predecessor().map(|node| node.key::<K>().clone());
+        let next = to_remove.successor().map(|node| node.key::<K>().clone());
+        let (_, value) = self.root.delete_node(to_remove)?;
+        Some((value, prev, next))
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get(&1), Some(&"a"));
+    /// assert_eq!(map.get(&2), None);
+    /// ```
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// Returns a mutable reference ti the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    /// if let Some(x) = map.get_mut(&1) {
+    ///     *x = "b";
+    /// }
+    /// assert_eq!(map[&1], "b");
+    /// ```
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.root
+            .search(key)?
+            .ok()
+            .map(|n| unsafe { n.value_mut() })
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get_key_value(&1), Some((&1, &"a")));
+    /// assert_eq!(map.get_key_value(&2), None);
+    /// ```
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.root
+            .search(key)?
+            .ok()
+            .map(|n| unsafe { n.key_value() })
+    }
+
+    /// Returns the canonical key stored in the map and the value corresponding to the supplied key. It is an alias of [`get_key_value`](Self::get_key_value), for the call sites which need the stored key rather than the supplied one, equal via [`Borrow`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("Alice".to_string(), 1);
+    ///
+    /// let (key, value) = map.get_entry("Alice").unwrap();
+    /// assert_eq!((key.as_str(), *value), ("Alice", 1));
+    /// assert_eq!(map.get_entry("Bob"), None);
+    /// ```
+    #[inline]
+    pub fn get_entry<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key)
+    }
+
+    /// Returns the stored key and a mutable reference to the value corresponding to the supplied key. The stored key may differ from the supplied one which is equal via [`Borrow`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland".to_string(), 0);
+    /// if let Some((key, value)) = map.get_key_value_mut("poneyland") {
+    ///     *value += key.len();
+    /// }
+    /// assert_eq!(map["poneyland"], 9);
+    /// assert_eq!(map.get_key_value_mut("unknown"), None);
+    /// ```
+    #[inline]
+    pub fn get_key_value_mut<Q>(&mut self, key: &Q) -> Option<(&K, &mut V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.root
+            .search(key)?
+            .ok()
+            .map(|n| unsafe { n.key_value_mut() })
+    }
+
+    /// Returns whether the map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.contains_key(&1), true);
+    /// assert_eq!(map.contains_key(&2), false);
+    /// ```
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        // only the node is needed, the value is never read.
+        matches!(self.root.search(key), Some(Ok(_)))
+    }
+
+    /// Returns the entry next to the entry of `key` in ascending order of key, or `None` if `key` is the last key or is not in the map.
+    ///
+    /// It starts from the node found for `key` and walks to its successor through the right subtree or the parents, so it takes `O(log n)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (4, "d")].into_iter().collect();
+    ///
+    /// assert_eq!(map.successor_of(&2), Some((&4, &"d")));
+    /// assert_eq!(map.successor_of(&4), None);
+    /// assert_eq!(map.successor_of(&3), None);
+    /// ```
+    pub fn successor_of<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.root.search(key)?.ok()?;
+        // Safety: The reference will not live longer than `self`.
+        node.successor().map(|n| unsafe { n.key_value() })
+    }
+
+    /// Returns the entry previous to the entry of `key` in ascending order of key, or `None` if `key` is the first key or is not in the map.
+    ///
+    /// It starts from the node found for `key` and walks to its predecessor through the left subtree or the parents, so it takes `O(log n)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (4, "d")].into_iter().collect();
+    ///
+    /// assert_eq!(map.predecessor_of(&4), Some((&2, &"b")));
+    /// assert_eq!(map.predecessor_of(&1), None);
+    /// assert_eq!(map.predecessor_of(&3), None);
+    /// ```
+    pub fn predecessor_of<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.root.search(key)?.ok()?;
+        // Safety: The reference will not live longer than `self`.
+        node.predecessor().map(|n| unsafe { n.key_value() })
+    }
+
+    /// Searches the entry by `f`, which returns the ordering of each entry against the target like [`slice::binary_search_by`]. The ordering must be consistent with the order of the keys in the map, but the target does not need to be a key, such as a part of the key or an order derived from the entry.
+    ///
+    /// It returns `Ok` with the entry found, or `Err` with the index where an entry of the target would be inserted. Finding the entry takes `O(log n)` time, but the index is counted by walking from the minimum because the tree does not track the sizes of the subtrees, so it takes `O(log n + i)` time for the index `i`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [((1, 'a'), "x"), ((2, 'b'), "y"), ((4, 'd'), "z")].into_iter().collect();
+    ///
+    /// assert_eq!(map.search_by(|&(n, _), _| n.cmp(&2)), Ok((&(2, 'b'), &"y")));
+    /// assert_eq!(map.search_by(|&(n, _), _| n.cmp(&3)), Err(2));
+    /// assert_eq!(map.search_by(|&(n, _), _| n.cmp(&0)), Err(0));
+    /// ```
+    pub fn search_by<F>(&self, mut f: F) -> Result<(&K, &V), usize>
+    where
+        F: FnMut(&K, &V) -> std::cmp::Ordering,
+    {
+        // the last node where the descent turned right, which precedes the target
+        let mut predecessor = None;
+        let mut current = self.root.inner();
+        while let Some(node) = current {
+            // Safety: The references will not live longer than `self`.
+            let (key, value) = unsafe { node.key_value() };
+            match f(key, value) {
+                std::cmp::Ordering::Less => {
+                    predecessor = Some(node);
+                    current = node.right();
+                }
+                std::cmp::Ordering::Greater => current = node.left(),
+                std::cmp::Ordering::Equal => return Ok((key, value)),
+            }
+        }
+        let index = if let Some(predecessor) = predecessor {
+            let min = self.root.inner().map(Node::min_child);
+            std::iter::successors(min, |node| node.successor())
+                .position(|node| node == predecessor)
+                .expect("the predecessor must be in the tree")
+                + 1
+        } else {
+            0
+        };
+        Err(index)
+    }
+
+    /// Retains only the elements specified by the predicate. In other words, remove all pairs `(k, v)` such that the predicate `f(&k, &mut v)` returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    /// map.retain(|&k, _| k % 2 == 0);
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![(0, 0), (2, 20), (4, 40), (6, 60)]);
+    /// ```
+    #[inline]
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        self.drain_filter(move |k, v| !f(k, v));
+    }
+
+    /// Retains only the elements specified by the predicate like [`retain`](Self::retain), and returns the number of removed elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<i32, i32> = (0..10).map(|x| (x, x * 10)).collect();
+    /// assert_eq!(map.retain_count(|&k, _| k % 2 == 0), 5);
+    /// assert_eq!(map.len(), 5);
+    /// ```
+    #[inline]
+    pub fn retain_count<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) -> usize {
+        self.drain_filter(move |k, v| !f(k, v)).count()
+    }
+
+    /// Removes the elements specified by the predicate and returns them as a new map, leaving the rest in `self`. In other words, moves all pairs `(k, v)` such that `f(&k, &mut v)` returns `true` into the returned map.
+    ///
+    /// The map is walked once, and then the nodes of the both maps are re-linked as [`rebuild`](Self::rebuild) does without reallocating nor comparing the keys, so it takes `O(n)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    /// let odds = map.drain_partition(|&k, _| k % 2 == 1);
+    ///
+    /// assert!(map.keys().eq(&[0, 2, 4, 6]));
+    /// assert!(odds.keys().eq(&[1, 3, 5, 7]));
+    /// ```
+    pub fn drain_partition<F>(&mut self, f: F) -> Self
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        Self {
+            root: self.root.partition(f),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate like [`retain`](Self::retain), but the predicate can stop the scan. The scan visits the elements in ascending key order, and the predicate returns `Continue(true)` to keep the element, `Continue(false)` to remove it, or `Break(())` to keep it and all of the remaining elements untouched.
+    ///
+    /// The elements are removed after the scan, so none of them is removed if the predicate panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::ControlFlow;
+    ///
+    /// // expires the sessions started before time 30, in order of the start time
+    /// let mut sessions: RbTreeMap<u32, &str> = [(10, "a"), (20, "b"), (30, "c"), (40, "d")].into_iter().collect();
+    /// sessions.retain_while(|&start, _| {
+    ///     if start < 30 {
+    ///         ControlFlow::Continue(false)
+    ///     } else {
+    ///         ControlFlow::Break(())
+    ///     }
+    /// });
+    /// assert!(sessions.keys().copied().eq([30, 40]));
+    /// ```
+    pub fn retain_while<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> ops::ControlFlow<(), bool>,
+    {
+        let mut to_remove = vec![];
+        let mut current = self.root.inner().map(Node::min_child);
+        while let Some(node) = current {
+            // Safety: The mutable reference will not live longer than the call of `f`.
+            let (key, value) = unsafe { node.key_value_mut() };
+            match f(key, value) {
+                ops::ControlFlow::Continue(true) => {}
+                ops::ControlFlow::Continue(false) => to_remove.push(node),
+                ops::ControlFlow::Break(()) => break,
+            }
+            current = node.successor();
+        }
+        // the removals re-link the nodes, so they are done after the walk.
+        for node in to_remove {
+            self.root.delete_node(node);
+        }
+    }
+
+    /// Returns the first key-value pair in the map. The key in this pair is the minimum key in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// assert_eq!(map.first(), None);
+    /// map.insert(1, "b");
+    /// map.insert(2, "a");
+    /// assert_eq!(map.first(), Some((&1, &"b")));
+    /// ```
+    pub fn first(&self) -> Option<(&K, &V)> {
+        Some(unsafe { self.root.inner()?.min_child().key_value() })
+    }
+
+    /// Returns the last key-value pair in the map. The key in this pair is the maximum key in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "b");
+    /// map.insert(2, "a");
+    /// assert_eq!(map.last(), Some((&2, &"a")));
+    /// ```
+    pub fn last(&self) -> Option<(&K, &V)> {
+        Some(unsafe { self.root.inner()?.max_child().key_value() })
+    }
+
+    /// Returns the first key in the map, that is the minimum key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// assert_eq!(map.first_key(), None);
+    /// map.insert(1, "b");
+    /// map.insert(2, "a");
+    /// assert_eq!(map.first_key(), Some(&1));
+    /// ```
+    pub fn first_key(&self) -> Option<&K> {
+        self.first().map(|(k, _)| k)
+    }
+
+    /// Returns the last key in the map, that is the maximum key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// assert_eq!(map.last_key(), None);
+    /// map.insert(1, "b");
+    /// map.insert(2, "a");
+    /// assert_eq!(map.last_key(), Some(&2));
+    /// ```
+    pub fn last_key(&self) -> Option<&K> {
+        self.last().map(|(k, _)| k)
+    }
+
+    /// Returns the entry at the ascending position `index`, walking from the nearer end of the map in `O(min(index, len - index))` time.
+    pub(crate) fn entry_at(&self, index: usize) -> Option<(&K, &V)> {
+        let len = self.len();
+        if len <= index {
+            None
+        } else if index <= len / 2 {
+            self.iter().nth(index)
+        } else {
+            self.iter().nth_back(len - 1 - index)
+        }
+    }
+
+    /// Returns the entry at the position `rng_value % len` in ascending order of key, or `None` if the map is empty. Passing a uniformly random value samples an entry uniformly.
+    ///
+    /// The subtree sizes are not tracked, so the entry is found by walking from the nearer end of the map in `O(n)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    /// assert_eq!(map.select_random(1), Some((&2, &"b")));
+    /// assert_eq!(map.select_random(5), Some((&3, &"c")));
+    ///
+    /// assert_eq!(RbTreeMap::<i32, ()>::new().select_random(5), None);
+    /// ```
+    pub fn select_random(&self, rng_value: usize) -> Option<(&K, &V)> {
+        match self.len() {
+            0 => None,
+            len => self.entry_at(rng_value % len),
+        }
+    }
+
+    pub fn first_mut(&mut self) -> Option<(&K, &mut V)> {
+        Some(unsafe { self.root.inner()?.min_child().key_value_mut() })
+    }
+
+    pub fn last_mut(&mut self) -> Option<(&K, &mut V)> {
+        Some(unsafe { self.root.inner()?.max_child().key_value_mut() })
+    }
+
+    /// Removes and returns the first element in the map. The key of this element is the minimum key that was in the map.
+    ///
+    /// # Examples
+    ///
+    /// Draining elements in ascending order, while keeping a usable map each iteration.
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// while let Some((key, _val)) = map.pop_first() {
+    ///     assert!(map.iter().all(|(k, _v)| *k > key));
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        self.root.remove_min()
+    }
+
+    /// Removes and returns the first element in the map as [`pop_first`](Self::pop_first) does, together with the key of the new first element. The new first key is found before removal as the successor of the removed node, so it costs no additional search.
+    ///
+    /// # Examples
+    ///
+    /// Merging sorted maps, peeking the next boundary of the map drained.
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (3, "c"), (5, "e")].into_iter().collect();
+    ///
+    /// assert_eq!(map.pop_first_with_next(), Some(((1, "a"), Some(&3))));
+    /// assert_eq!(map.pop_first_with_next(), Some(((3, "c"), Some(&5))));
+    /// assert_eq!(map.pop_first_with_next(), Some(((5, "e"), None)));
+    /// assert_eq!(map.pop_first_with_next(), None);
+    /// ```
+    pub fn pop_first_with_next(&mut self) -> Option<((K, V), Option<&K>)> {
+        let min = self.root.inner()?.min_child();
+        let next = min.successor();
+        let entry = self.root.delete_node(min)?;
+        // the deletion only relinks nodes, so `next` is still alive and now the minimum.
+        Some((entry, next.map(|node| node.key())))
     }
 
     /// Removes and returns the last element in the map. The key of this element is the maximum key that was in the map.
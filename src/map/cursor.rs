@@ -0,0 +1,272 @@
+use crate::{
+    node::{ChildIndex, Node},
+    RbTreeMap,
+};
+
+use std::{borrow::Borrow, fmt, ops::Bound};
+
+impl<K: Ord, V> RbTreeMap<K, V> {
+    /// Returns a cursor pointing at the gap before the smallest key above the given bound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (3, "c"), (4, "d")].into_iter().collect();
+    ///
+    /// let mut cursor = map.lower_bound_mut(Bound::Included(&2));
+    /// assert_eq!(cursor.peek_prev(), Some((&1, &mut "a")));
+    /// assert_eq!(cursor.peek_next(), Some((&2, &mut "b")));
+    ///
+    /// let mut cursor = map.lower_bound_mut(Bound::Excluded(&2));
+    /// assert_eq!(cursor.peek_prev(), Some((&2, &mut "b")));
+    /// assert_eq!(cursor.peek_next(), Some((&3, &mut "c")));
+    /// ```
+    pub fn lower_bound_mut<Q>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let next = match bound {
+            Bound::Included(key) => self.first_node_by(|k| key <= k),
+            Bound::Excluded(key) => self.first_node_by(|k| key < k),
+            Bound::Unbounded => self.root.inner().map(Node::min_child),
+        };
+        CursorMut { next, tree: self }
+    }
+
+    /// Returns a cursor pointing at the gap after the greatest key below the given bound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (3, "c"), (4, "d")].into_iter().collect();
+    ///
+    /// let mut cursor = map.upper_bound_mut(Bound::Included(&3));
+    /// assert_eq!(cursor.peek_prev(), Some((&3, &mut "c")));
+    /// assert_eq!(cursor.peek_next(), Some((&4, &mut "d")));
+    ///
+    /// let mut cursor = map.upper_bound_mut(Bound::Excluded(&3));
+    /// assert_eq!(cursor.peek_prev(), Some((&2, &mut "b")));
+    /// assert_eq!(cursor.peek_next(), Some((&3, &mut "c")));
+    /// ```
+    pub fn upper_bound_mut<Q>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let next = match bound {
+            Bound::Included(key) => self.first_node_by(|k| key < k),
+            Bound::Excluded(key) => self.first_node_by(|k| key <= k),
+            Bound::Unbounded => None,
+        };
+        CursorMut { next, tree: self }
+    }
+
+    /// Finds the minimum node satisfying `pred`, where `pred` must be monotone over the keys.
+    fn first_node_by<Q>(&self, pred: impl Fn(&Q) -> bool) -> Option<Node<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+    {
+        let mut found = None;
+        let mut current = self.root.inner();
+        while let Some(node) = current {
+            if pred(node.key()) {
+                // the node is a candidate, but smaller ones may be in the left
+                found = Some(node);
+                current = node.left();
+            } else {
+                current = node.right();
+            }
+        }
+        found
+    }
+}
+
+/// A cursor over a [`RbTreeMap`] with editing operations. It points at a gap between two entries, or before the first or after the last entry.
+pub struct CursorMut<'a, K, V> {
+    /// The node just after the gap, or `None` if the cursor is at the end.
+    next: Option<Node<K, V>>,
+    tree: &'a mut RbTreeMap<K, V>,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for CursorMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Safety: The references will not live longer than `self`.
+        f.debug_tuple("CursorMut")
+            .field(&self.prev_node().map(|n| unsafe { n.key_value() }))
+            .field(&self.next.map(|n| unsafe { n.key_value() }))
+            .finish()
+    }
+}
+
+impl<K, V> CursorMut<'_, K, V> {
+    fn prev_node(&self) -> Option<Node<K, V>> {
+        match self.next {
+            Some(next) => next.predecessor(),
+            None => self.tree.root.inner().map(Node::max_child),
+        }
+    }
+
+    /// Advances the cursor to the next gap, and returns the entry that it moved over. If the cursor is at the end, it returns `None` and does not move.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    ///
+    /// let mut cursor = map.lower_bound_mut(Bound::Unbounded);
+    /// assert_eq!(cursor.next(), Some((&1, &mut "a")));
+    /// assert_eq!(cursor.next(), Some((&2, &mut "b")));
+    /// assert_eq!(cursor.next(), None);
+    /// ```
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(&K, &mut V)> {
+        let current = self.next?;
+        self.next = current.successor();
+        // Safety: The mutable reference will not live longer than `self`.
+        Some(unsafe { current.key_value_mut() })
+    }
+
+    /// Moves the cursor to the previous gap, and returns the entry that it moved over. If the cursor is at the start, it returns `None` and does not move.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    ///
+    /// let mut cursor = map.upper_bound_mut(Bound::Unbounded);
+    /// assert_eq!(cursor.prev(), Some((&2, &mut "b")));
+    /// assert_eq!(cursor.prev(), Some((&1, &mut "a")));
+    /// assert_eq!(cursor.prev(), None);
+    /// ```
+    pub fn prev(&mut self) -> Option<(&K, &mut V)> {
+        let current = self.prev_node()?;
+        self.next = Some(current);
+        // Safety: The mutable reference will not live longer than `self`.
+        Some(unsafe { current.key_value_mut() })
+    }
+
+    /// Returns the entry just after the cursor without moving it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    ///
+    /// let mut cursor = map.lower_bound_mut(Bound::Unbounded);
+    /// if let Some((_, value)) = cursor.peek_next() {
+    ///     *value = "c";
+    /// }
+    /// assert_eq!(cursor.peek_next(), Some((&1, &mut "c")));
+    /// ```
+    pub fn peek_next(&mut self) -> Option<(&K, &mut V)> {
+        // Safety: The mutable reference will not live longer than `self`.
+        self.next.map(|n| unsafe { n.key_value_mut() })
+    }
+
+    /// Returns the entry just before the cursor without moving it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    ///
+    /// let mut cursor = map.upper_bound_mut(Bound::Unbounded);
+    /// assert_eq!(cursor.peek_prev(), Some((&2, &mut "b")));
+    /// ```
+    pub fn peek_prev(&mut self) -> Option<(&K, &mut V)> {
+        // Safety: The mutable reference will not live longer than `self`.
+        self.prev_node().map(|n| unsafe { n.key_value_mut() })
+    }
+}
+
+impl<K: Ord, V> CursorMut<'_, K, V> {
+    /// Inserts a new entry into the gap of the cursor, and returns the node inserted. If `key` is not ordered between the neighbors, it is inserted at the sorted position as [`RbTreeMap::insert`] does, and `None` is returned.
+    fn insert_into_gap(&mut self, key: K, value: V) -> Option<Node<K, V>> {
+        let prev = self.prev_node();
+        let in_order = prev.is_none_or(|p| p.key::<K>() < &key)
+            && self.next.is_none_or(|n| &key < n.key::<K>());
+        debug_assert!(
+            in_order,
+            "the key must be ordered between the neighbors of the cursor"
+        );
+        if !in_order {
+            self.tree.insert(key, value);
+            return None;
+        }
+        // the left of `next` is vacant, or the right of `prev` is vacant because it is the maximum in the left of `next`.
+        let position = match (self.next, prev) {
+            (Some(next), _) if next.left().is_none() => Some((next, ChildIndex::Left)),
+            (_, Some(prev)) => Some((prev, ChildIndex::Right)),
+            _ => None,
+        };
+        Some(self.tree.root.insert_at(position, key, value))
+    }
+
+    /// Inserts a new entry into the gap of the cursor. The cursor is placed before the new entry.
+    ///
+    /// `key` must be greater than the key before the cursor and less than the key after the cursor. It is checked only in debug builds, and an unordered key is inserted at the sorted position as [`RbTreeMap::insert`] does in release builds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: RbTreeMap<_, _> = [(1, "a"), (3, "c")].into_iter().collect();
+    ///
+    /// let mut cursor = map.lower_bound_mut(Bound::Included(&3));
+    /// cursor.insert_after(2, "b");
+    /// assert_eq!(cursor.peek_next(), Some((&2, &mut "b")));
+    ///
+    /// assert!(map.into_iter().eq([(1, "a"), (2, "b"), (3, "c")]));
+    /// ```
+    pub fn insert_after(&mut self, key: K, value: V) {
+        if let Some(node) = self.insert_into_gap(key, value) {
+            self.next = Some(node);
+        }
+    }
+
+    /// Inserts a new entry into the gap of the cursor. The cursor is placed after the new entry.
+    ///
+    /// `key` must be greater than the key before the cursor and less than the key after the cursor. It is checked only in debug builds, and an unordered key is inserted at the sorted position as [`RbTreeMap::insert`] does in release builds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map = RbTreeMap::new();
+    ///
+    /// let mut cursor = map.lower_bound_mut(Bound::Unbounded);
+    /// for i in 0..5 {
+    ///     cursor.insert_before(i, i * 10);
+    /// }
+    /// assert_eq!(cursor.peek_prev(), Some((&4, &mut 40)));
+    ///
+    /// assert!(map.into_iter().eq((0..5).map(|i| (i, i * 10))));
+    /// ```
+    pub fn insert_before(&mut self, key: K, value: V) {
+        self.insert_into_gap(key, value);
+    }
+}
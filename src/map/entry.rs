@@ -1,4 +1,9 @@
-use crate::RbTreeMap;
+use crate::{
+    node::{ChildIndex, Node},
+    RbTreeMap,
+};
+
+use std::{borrow::Borrow, cmp::Ordering};
 
 impl<K: Ord, V> RbTreeMap<K, V> {
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
@@ -19,22 +24,129 @@ impl<K: Ord, V> RbTreeMap<K, V> {
     /// assert_eq!(count["c"], 1);
     /// ```
     #[inline]
-    pub fn entry(&mut self, key: K) -> Entry<K, V> {
-        Entry { key, tree: self }
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.root.search(&key) {
+            Some(Ok(node)) => Entry::Occupied(OccupiedEntry { node, tree: self }),
+            Some(Err(position)) => Entry::Vacant(VacantEntry {
+                key,
+                position: Some(position),
+                tree: self,
+            }),
+            None => Entry::Vacant(VacantEntry {
+                key,
+                position: None,
+                tree: self,
+            }),
+        }
     }
+
+    /// Returns the mutable reference to the value of the key, inserting the default value if the key is vacant. It is a shortcut of `self.entry(key).or_default()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut index: RbTreeMap<&str, RbTreeMap<usize, ()>> = RbTreeMap::new();
+    ///
+    /// for (line, words) in ["a b", "b c", "a"].into_iter().enumerate() {
+    ///     for word in words.split(' ') {
+    ///         index.entry_or_default(word).insert(line, ());
+    ///     }
+    /// }
+    ///
+    /// assert!(index["a"].keys().copied().eq([0, 2]));
+    /// assert!(index["b"].keys().copied().eq([0, 1]));
+    /// ```
+    #[inline]
+    pub fn entry_or_default(&mut self, key: K) -> &mut V
+    where
+        V: Default,
+    {
+        self.entry(key).or_default()
+    }
+
+    /// Gets the entry corresponding to the borrowed key for in-place manipulation. The owned key is created by [`ToOwned`] only if the entry is vacant and a value is inserted into it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut count: RbTreeMap<String, usize> = RbTreeMap::new();
+    ///
+    /// for x in ["a", "b", "a", "c", "a", "b"] {
+    ///     *count.entry_ref(x).or_insert(0) += 1;
+    /// }
+    ///
+    /// assert_eq!(count["a"], 3);
+    /// assert_eq!(count["b"], 2);
+    /// assert_eq!(count["c"], 1);
+    /// ```
+    pub fn entry_ref<'a, 'b, Q>(&'a mut self, key: &'b Q) -> EntryRef<'a, 'b, K, Q, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.root.search(key) {
+            Some(Ok(node)) => EntryRef::Occupied(OccupiedEntryRef { node, tree: self }),
+            Some(Err(position)) => EntryRef::Vacant(VacantEntryRef {
+                key,
+                position: Some(position),
+                tree: self,
+            }),
+            None => EntryRef::Vacant(VacantEntryRef {
+                key,
+                position: None,
+                tree: self,
+            }),
+        }
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is created by the [`RbTreeMap::entry`] method.
+#[derive(Debug)]
+pub enum Entry<'a, K: Ord, V> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V>),
 }
 
+/// A view into an occupied entry in a `RbTreeMap`. It is part of the [`Entry`] enum.
 #[derive(Debug)]
-pub struct Entry<'a, K: Ord, V> {
+pub struct OccupiedEntry<'a, K: Ord, V> {
+    node: Node<K, V>,
+    tree: &'a mut RbTreeMap<K, V>,
+}
+
+/// A view into a vacant entry in a `RbTreeMap`. It is part of the [`Entry`] enum.
+#[derive(Debug)]
+pub struct VacantEntry<'a, K: Ord, V> {
     key: K,
+    position: Option<(Node<K, V>, ChildIndex)>,
     tree: &'a mut RbTreeMap<K, V>,
 }
 
 impl<'a, K: Ord, V> Entry<'a, K, V> {
     /// Returns a reference to this entry's key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<&str, usize> = RbTreeMap::new();
+    /// assert_eq!(map.entry("poneyland").key(), &"poneyland");
+    /// ```
     #[inline]
     pub fn key(&self) -> &K {
-        &self.key
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
     }
 
     /// Ensures a value is in the entry by inserting `default` if empty, and returns a mutable reference to the value in the entry.
@@ -51,17 +163,9 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
     /// ```
     #[inline]
     pub fn or_insert(self, default: V) -> &'a mut V {
-        // Safety: The return value will not live longer than `tree`.
-        unsafe {
-            if self.tree.is_empty() || self.tree.root.search(&self.key).transpose().is_err() {
-                self.tree
-                    .root
-                    .insert_node(self.key, default)
-                    .unwrap_unchecked()
-                    .value_mut()
-            } else {
-                self.tree.get_mut(&self.key).unwrap()
-            }
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
         }
     }
 
@@ -98,18 +202,9 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
     /// ```
     #[inline]
     pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
-        // Safety: The return value will not live longer than `tree`.
-        unsafe {
-            if self.tree.is_empty() || self.tree.root.search(&self.key).transpose().is_err() {
-                let value = default(&self.key);
-                self.tree
-                    .root
-                    .insert_node(self.key, value)
-                    .unwrap_unchecked()
-                    .value_mut()
-            } else {
-                self.tree.get_mut(&self.key).unwrap()
-            }
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert_with(default),
         }
     }
 
@@ -134,9 +229,9 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
     /// ```
     #[must_use]
     #[inline]
-    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
-        if let Some(entry) = self.tree.get_mut(&self.key) {
-            f(entry);
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
         }
         self
     }
@@ -161,3 +256,625 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
         self.or_insert_with(V::default)
     }
 }
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    /// Returns a reference to the key in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    ///     assert_eq!(o.key(), &"poneyland");
+    /// }
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &K {
+        self.node.key()
+    }
+
+    /// Replaces the key stored in the entry with `key`, and returns the old key. The value and the structure of the tree are left untouched.
+    ///
+    /// It is useful for the key type whose fields used for ordering are only a part of it. `key` must be equal to the stored key in terms of [`Ord`], otherwise the order of the map will be broken. This condition is checked only in debug builds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    /// use std::cmp::Ordering;
+    ///
+    /// #[derive(Debug, Eq)]
+    /// struct Tagged(u32, &'static str);
+    ///
+    /// impl PartialEq for Tagged {
+    ///     fn eq(&self, other: &Self) -> bool {
+    ///         self.0 == other.0
+    ///     }
+    /// }
+    /// impl PartialOrd for Tagged {
+    ///     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    ///         Some(self.cmp(other))
+    ///     }
+    /// }
+    /// impl Ord for Tagged {
+    ///     fn cmp(&self, other: &Self) -> Ordering {
+    ///         self.0.cmp(&other.0)
+    ///     }
+    /// }
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(Tagged(1, "old"), "a");
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry(Tagged(1, "new")) {
+    ///     assert_eq!(o.replace_key(Tagged(1, "new")).1, "old");
+    /// }
+    /// assert_eq!(map.keys().next().unwrap().1, "new");
+    /// ```
+    #[inline]
+    pub fn replace_key(&mut self, key: K) -> K {
+        debug_assert!(
+            key.cmp(self.node.key()) == Ordering::Equal,
+            "the new key must be equal to the stored key"
+        );
+        self.node.replace_key(key)
+    }
+
+    /// Returns a reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    ///     assert_eq!(o.get(), &12);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get(&self) -> &V {
+        // Safety: The reference will not live longer than `self`.
+        unsafe { self.node.value() }
+    }
+
+    /// Returns a mutable reference to the value in the entry. Use [`into_mut`](Self::into_mut) for the reference which outlives the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry("poneyland") {
+    ///     *o.get_mut() += 10;
+    /// }
+    /// assert_eq!(map["poneyland"], 22);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        // Safety: The reference will not live longer than `self`.
+        unsafe { self.node.value_mut() }
+    }
+
+    /// Converts the entry into a mutable reference to its value, with a lifetime bound to the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    ///     *o.into_mut() += 10;
+    /// }
+    /// assert_eq!(map["poneyland"], 22);
+    /// ```
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        // Safety: The reference will not live longer than `tree`.
+        unsafe { self.node.value_mut() }
+    }
+
+    /// Sets the value of the entry, and returns the old value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry("poneyland") {
+    ///     assert_eq!(o.insert(15), 12);
+    /// }
+    /// assert_eq!(map["poneyland"], 15);
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the key-value pair out of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    ///     assert_eq!(o.remove_entry(), ("poneyland", 12));
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        self.tree.root.delete_node(self.node).unwrap()
+    }
+
+    /// Takes the value out of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    ///     assert_eq!(o.remove(), 12);
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    /// Returns a reference to the key which would be used when inserting a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<&str, u32> = RbTreeMap::new();
+    ///
+    /// if let Entry::Vacant(v) = map.entry("poneyland") {
+    ///     assert_eq!(v.key(), &"poneyland");
+    /// }
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes the ownership of the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<&str, u32> = RbTreeMap::new();
+    ///
+    /// if let Entry::Vacant(v) = map.entry("poneyland") {
+    ///     assert_eq!(v.into_key(), "poneyland");
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the value with the key of the entry, and returns a mutable reference to the value. The position found by [`RbTreeMap::entry`] is reused, so the tree is not searched again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<&str, u32> = RbTreeMap::new();
+    ///
+    /// if let Entry::Vacant(v) = map.entry("poneyland") {
+    ///     *v.insert(37) += 1;
+    /// }
+    /// assert_eq!(map["poneyland"], 38);
+    /// ```
+    #[inline]
+    pub fn insert(self, value: V) -> &'a mut V {
+        let node = self.tree.root.insert_at(self.position, self.key, value);
+        // Safety: The return value will not live longer than `tree`.
+        unsafe { node.value_mut() }
+    }
+
+    /// Inserts the value computed by `f` from the key of the entry, and returns a mutable reference to the value. The reference to the key is provided so that cloning the key is unnecessary, and the tree is not searched again as [`insert`](Self::insert).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::Entry, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, usize> = RbTreeMap::new();
+    ///
+    /// if let Entry::Vacant(v) = map.entry("poneyland".to_owned()) {
+    ///     v.insert_with(|key| key.len());
+    /// }
+    /// assert_eq!(map["poneyland"], 9);
+    /// ```
+    #[inline]
+    pub fn insert_with<F: FnOnce(&K) -> V>(self, f: F) -> &'a mut V {
+        let value = f(&self.key);
+        self.insert(value)
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied, looked up by a borrowed key.
+///
+/// This `enum` is created by the [`RbTreeMap::entry_ref`] method.
+#[derive(Debug)]
+pub enum EntryRef<'a, 'b, K: Ord, Q: ?Sized, V> {
+    /// An occupied entry.
+    Occupied(OccupiedEntryRef<'a, K, V>),
+    /// A vacant entry.
+    Vacant(VacantEntryRef<'a, 'b, K, Q, V>),
+}
+
+/// A view into an occupied entry in a `RbTreeMap`. It is part of the [`EntryRef`] enum.
+#[derive(Debug)]
+pub struct OccupiedEntryRef<'a, K: Ord, V> {
+    node: Node<K, V>,
+    tree: &'a mut RbTreeMap<K, V>,
+}
+
+/// A view into a vacant entry in a `RbTreeMap`. It is part of the [`EntryRef`] enum.
+#[derive(Debug)]
+pub struct VacantEntryRef<'a, 'b, K: Ord, Q: ?Sized, V> {
+    key: &'b Q,
+    position: Option<(Node<K, V>, ChildIndex)>,
+    tree: &'a mut RbTreeMap<K, V>,
+}
+
+impl<'a, K, Q, V> EntryRef<'a, '_, K, Q, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ToOwned<Owned = K> + ?Sized,
+{
+    /// Returns a reference to this entry's key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<String, usize> = RbTreeMap::new();
+    /// assert_eq!(map.entry_ref("poneyland").key(), "poneyland");
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &Q {
+        match self {
+            EntryRef::Occupied(entry) => entry.key().borrow(),
+            EntryRef::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if empty, and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    /// map.entry_ref("poneyland").or_insert(12);
+    ///
+    /// assert_eq!(map["poneyland"], 12);
+    /// ```
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            EntryRef::Occupied(entry) => entry.into_mut(),
+            EntryRef::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default` function if empty, and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<String, String> = RbTreeMap::new();
+    /// map.entry_ref("poneyland").or_insert_with(|| "hoho".to_string());
+    ///
+    /// assert_eq!(map["poneyland"], "hoho".to_string());
+    /// ```
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        self.or_insert_with_key(move |_| default())
+    }
+
+    /// Ensures a value is in the entry by inserting, if empty, the result of `default` function. The borrowed key is provided to `default`, so the owned key is not needed to generate the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<String, usize> = RbTreeMap::new();
+    /// map.entry_ref("poneyland").or_insert_with_key(|key| key.chars().count());
+    ///
+    /// assert_eq!(map["poneyland"], 9);
+    /// ```
+    #[inline]
+    pub fn or_insert_with_key<F: FnOnce(&Q) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            EntryRef::Occupied(entry) => entry.into_mut(),
+            EntryRef::Vacant(entry) => {
+                let value = default(entry.key);
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts into the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    ///
+    /// map.entry_ref("poneyland")
+    ///     .and_modify(|e| *e += 1)
+    ///     .or_insert(42);
+    /// assert_eq!(map["poneyland"], 42);
+    ///
+    /// map.entry_ref("poneyland")
+    ///     .and_modify(|e| *e += 1)
+    ///     .or_insert(42);
+    /// assert_eq!(map["poneyland"], 43);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let EntryRef::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+
+    /// Ensures a value is in the entry by inserting [`Default::default`] value if empty, and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<String, Option<usize>> = RbTreeMap::new();
+    /// map.entry_ref("poneyland").or_default();
+    ///
+    /// assert_eq!(map["poneyland"], None);
+    /// ```
+    #[inline]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntryRef<'a, K, V> {
+    /// Returns a reference to the key in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    /// map.insert("poneyland".to_string(), 12);
+    ///
+    /// if let EntryRef::Occupied(o) = map.entry_ref("poneyland") {
+    ///     assert_eq!(o.key(), "poneyland");
+    /// }
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &K {
+        self.node.key()
+    }
+
+    /// Returns a reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    /// map.insert("poneyland".to_string(), 12);
+    ///
+    /// if let EntryRef::Occupied(o) = map.entry_ref("poneyland") {
+    ///     assert_eq!(o.get(), &12);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get(&self) -> &V {
+        // Safety: The reference will not live longer than `self`.
+        unsafe { self.node.value() }
+    }
+
+    /// Returns a mutable reference to the value in the entry. Use [`into_mut`](Self::into_mut) for the reference which outlives the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    /// map.insert("poneyland".to_string(), 12);
+    ///
+    /// if let EntryRef::Occupied(mut o) = map.entry_ref("poneyland") {
+    ///     *o.get_mut() += 10;
+    /// }
+    /// assert_eq!(map["poneyland"], 22);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        // Safety: The reference will not live longer than `self`.
+        unsafe { self.node.value_mut() }
+    }
+
+    /// Converts the entry into a mutable reference to its value, with a lifetime bound to the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    /// map.insert("poneyland".to_string(), 12);
+    ///
+    /// if let EntryRef::Occupied(o) = map.entry_ref("poneyland") {
+    ///     *o.into_mut() += 10;
+    /// }
+    /// assert_eq!(map["poneyland"], 22);
+    /// ```
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        // Safety: The reference will not live longer than `tree`.
+        unsafe { self.node.value_mut() }
+    }
+
+    /// Sets the value of the entry, and returns the old value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    /// map.insert("poneyland".to_string(), 12);
+    ///
+    /// if let EntryRef::Occupied(mut o) = map.entry_ref("poneyland") {
+    ///     assert_eq!(o.insert(15), 12);
+    /// }
+    /// assert_eq!(map["poneyland"], 15);
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the key-value pair out of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    /// map.insert("poneyland".to_string(), 12);
+    ///
+    /// if let EntryRef::Occupied(o) = map.entry_ref("poneyland") {
+    ///     assert_eq!(o.remove_entry(), ("poneyland".to_string(), 12));
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        self.tree.root.delete_node(self.node).unwrap()
+    }
+
+    /// Takes the value out of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    /// map.insert("poneyland".to_string(), 12);
+    ///
+    /// if let EntryRef::Occupied(o) = map.entry_ref("poneyland") {
+    ///     assert_eq!(o.remove(), 12);
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
+
+impl<'a, 'b, K: Ord, Q: ?Sized, V> VacantEntryRef<'a, 'b, K, Q, V> {
+    /// Returns a reference to the borrowed key which would be used when inserting a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    ///
+    /// if let EntryRef::Vacant(v) = map.entry_ref("poneyland") {
+    ///     assert_eq!(v.key(), "poneyland");
+    /// }
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &'b Q {
+        self.key
+    }
+
+    /// Inserts the value with the owned key created from the borrowed key, and returns a mutable reference to the value. The position found by [`RbTreeMap::entry_ref`] is reused, so the tree is not searched again.
+    ///
+    /// The owned key must be equal to the borrowed key, which is checked only in debug builds. Otherwise the map will be broken for searching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::entry::EntryRef, RbTreeMap};
+    ///
+    /// let mut map: RbTreeMap<String, u32> = RbTreeMap::new();
+    ///
+    /// if let EntryRef::Vacant(v) = map.entry_ref("poneyland") {
+    ///     *v.insert(37) += 1;
+    /// }
+    /// assert_eq!(map["poneyland"], 38);
+    /// ```
+    #[inline]
+    pub fn insert(self, value: V) -> &'a mut V
+    where
+        K: Borrow<Q>,
+        Q: Ord + ToOwned<Owned = K>,
+    {
+        let key = self.key.to_owned();
+        debug_assert!(
+            key.borrow() == self.key,
+            "the owned key must be equal to the borrowed key"
+        );
+        let node = self.tree.root.insert_at(self.position, key, value);
+        // Safety: The return value will not live longer than `tree`.
+        unsafe { node.value_mut() }
+    }
+}
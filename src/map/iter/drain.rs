@@ -28,7 +28,7 @@ impl<K: Ord, V> RbTreeMap<K, V> {
     /// assert_eq!(odds.into_keys().collect::<Vec<_>>(), vec![1, 3, 5, 7]);
     /// ```
     #[inline]
-    pub fn drain_filter<F: FnMut(&K, &mut V) -> bool>(&mut self, f: F) -> DrainFilter<K, V, F> {
+    pub fn drain_filter<F: FnMut(&K, &mut V) -> bool>(&mut self, f: F) -> DrainFilter<'_, K, V, F> {
         DrainFilter {
             pred: f,
             nav: DrainFilterNavigator::new(self),
@@ -1,8 +1,8 @@
-use std::iter::FusedIterator;
+use std::{iter::FusedIterator, ops::RangeInclusive};
 
-use crate::RbTreeMap;
+use crate::{ord::Step, RbTreeMap};
 
-use super::{IntoIter, Iter};
+use super::{IntoIter, Iter, Range};
 
 impl<K, V> RbTreeMap<K, V> {
     /// Creates a consuming iterator visiting all the keys, in sorted order.
@@ -41,9 +41,64 @@ impl<K, V> RbTreeMap<K, V> {
     /// assert_eq!(keys, [1, 2]);
     /// ```
     #[inline]
-    pub fn keys(&self) -> Keys<K, V> {
+    pub fn keys(&self) -> Keys<'_, K, V> {
         Keys(self.into_iter(), self.len())
     }
+
+    /// Gets an iterator over the runs of consecutive keys in the map, in sorted order. Each run is yielded as an inclusive range from its first key to its last key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<u32, ()> = [1, 2, 3, 7, 8, 10].into_iter().map(|k| (k, ())).collect();
+    ///
+    /// assert!(map.key_runs().eq([1..=3, 7..=8, 10..=10]));
+    /// ```
+    pub fn key_runs(&self) -> impl Iterator<Item = RangeInclusive<K>> + '_
+    where
+        K: Step + Clone,
+    {
+        let mut keys = self.keys().peekable();
+        std::iter::from_fn(move || {
+            let start = keys.next()?;
+            let mut end = start;
+            while let Some(next) = keys.next_if(|&next| end.forward().as_ref() == Some(next)) {
+                end = next;
+            }
+            Some(start.clone()..=end.clone())
+        })
+    }
+
+    /// Returns the least key not less than `start` that is absent from the map, or `None` if all of the keys from `start` to the maximum value of the type are present.
+    ///
+    /// It walks the keys only from `start` to the gap found, so it takes `O(log n + k)` time for `k` keys skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let ids: RbTreeMap<u32, &str> = [(1, "a"), (2, "b"), (3, "c"), (5, "e")].into_iter().collect();
+    ///
+    /// assert_eq!(ids.first_gap(1), Some(4));
+    /// assert_eq!(ids.first_gap(5), Some(6));
+    /// assert_eq!(ids.first_gap(0), Some(0));
+    /// ```
+    pub fn first_gap(&self, start: K) -> Option<K>
+    where
+        K: Step,
+    {
+        let mut candidate = start;
+        for (key, _) in self.range(&candidate..) {
+            if key != &candidate {
+                break;
+            }
+            candidate = candidate.forward()?;
+        }
+        Some(candidate)
+    }
 }
 
 #[derive(Debug)]
@@ -60,6 +115,10 @@ impl<K, V> Iterator for IntoKeys<K, V> {
         self.0.size_hint()
     }
 
+    fn count(self) -> usize {
+        self.0.count()
+    }
+
     fn last(mut self) -> Option<Self::Item> {
         self.next_back()
     }
@@ -110,6 +169,10 @@ impl<'a, K: 'a, V: 'a> Iterator for Keys<'a, K, V> {
         self.0.size_hint()
     }
 
+    fn count(self) -> usize {
+        self.1
+    }
+
     fn last(mut self) -> Option<Self::Item> {
         self.next_back()
     }
@@ -139,3 +202,64 @@ impl<'a, K: 'a, V: 'a> ExactSizeIterator for Keys<'a, K, V> {
 }
 
 impl<'a, K: 'a, V: 'a> FusedIterator for Keys<'a, K, V> {}
+
+/// An iterator over the keys in a sub-range of [`RbTreeMap`], created by [`RbTreeMap::range_keys`].
+#[derive(Debug)]
+pub struct RangeKeys<'a, K, V>(pub(super) Range<'a, K, V>);
+
+impl<K, V> Clone for RangeKeys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for RangeKeys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn min(mut self) -> Option<Self::Item> {
+        self.next()
+    }
+
+    fn max(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for RangeKeys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V> RangeKeys<'_, K, V> {
+    /// Counts the rest keys of the range without advancing the iterator, as [`Range::count_remaining`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = (0..10).map(|i| (i, ())).collect();
+    ///
+    /// let mut keys = map.range_keys(..5);
+    /// keys.next_back();
+    /// assert_eq!(keys.count_remaining(), 4);
+    /// ```
+    pub fn count_remaining(&self) -> usize {
+        self.0.count_remaining()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> FusedIterator for RangeKeys<'a, K, V> {}
@@ -1,17 +1,15 @@
 use std::{borrow, ops};
 
-use super::PreviousStep;
 use crate::{
-    node::{ChildIndex, Node},
+    node::{ModGuard, Node},
     RbTreeMap,
 };
 
+/// A range of nodes which deallocates the nodes while cutting. `start` and `end` always point the next nodes to be cut from each side.
 #[derive(Debug)]
 pub struct DyingLeafRange<K, V> {
     start: Option<Node<K, V>>,
-    start_prev: PreviousStep,
     end: Option<Node<K, V>>,
-    end_prev: PreviousStep,
 }
 
 impl<K, V> DyingLeafRange<K, V> {
@@ -19,107 +17,86 @@ impl<K, V> DyingLeafRange<K, V> {
         let start = tree.root.inner().map(|r| r.min_child());
         let end = tree.root.inner().map(|r| r.max_child());
         std::mem::forget(tree);
-        Self {
-            start,
-            start_prev: PreviousStep::LeftChild,
-            end,
-            end_prev: PreviousStep::RightChild,
-        }
+        Self { start, end }
     }
 
     pub fn cut_left(&mut self) -> Option<(K, V)> {
-        while let Some(curr) = self.start {
-            match self.start_prev {
-                PreviousStep::Parent => {
-                    // descended
-                    if let Some(left) = curr.left() {
-                        // go to left
-                        self.start = Some(left);
-                        continue;
-                    }
-                    self.start_prev = PreviousStep::LeftChild;
-                }
-                PreviousStep::LeftChild => {
-                    // ascended from left
-                    if self.start == self.end && self.end_prev.is_right_child() {
-                        // finish
-                        self.start = None;
-                        self.end = None;
-                    } else if let Some(right) = curr.right() {
-                        // go to right
-                        self.start_prev = PreviousStep::Parent;
-                        self.start = Some(right);
-                    } else {
-                        self.start_prev = PreviousStep::RightChild;
-                    }
-                    unsafe {
-                        return Some((std::ptr::read(curr.key()), std::ptr::read(curr.value())));
-                    }
-                }
-                PreviousStep::RightChild => {
-                    // ascended from right, so ascend again
-                    self.start = curr.parent();
-                    if let Some(ChildIndex::Left) = curr.index_on_parent() {
-                        self.start_prev = PreviousStep::LeftChild;
-                    }
-                    // deallocate now and forget, because it will be dropped on outside.
-                    std::mem::forget(unsafe { curr.deallocate() });
-                }
+        let curr = self.start?;
+        // Safety: The key and value are moved out only once, and the node will be deallocated without dropping them.
+        let entry = unsafe { (std::ptr::read(curr.key()), std::ptr::read(curr.value())) };
+        if self.start == self.end {
+            self.finish(curr);
+            return Some(entry);
+        }
+        if let Some(right) = curr.right() {
+            // `curr` will be deallocated after its right subtree
+            self.start = Some(right.min_child());
+            return Some(entry);
+        }
+        // the subtree of `curr` was cut, so ascend until from the left with deallocating
+        let mut current = curr;
+        loop {
+            let (idx, parent) = current
+                .index_and_parent()
+                .expect("the next node must exist");
+            // Safety: The key and value were already moved out.
+            std::mem::forget(unsafe { current.deallocate() });
+            if idx.is_left() {
+                self.start = Some(parent);
+                return Some(entry);
             }
+            current = parent;
         }
-        None
     }
 
     pub fn cut_right(&mut self) -> Option<(K, V)> {
-        while let Some(curr) = self.end {
-            match self.end_prev {
-                PreviousStep::Parent => {
-                    // descended
-                    if let Some(right) = curr.right() {
-                        // go to right
-                        self.end = Some(right);
-                        continue;
-                    }
-                    self.end_prev = PreviousStep::RightChild;
-                }
-                PreviousStep::RightChild => {
-                    // ascended from right
-                    if self.start == self.end && self.start_prev.is_left_child() {
-                        // finish
-                        self.start = None;
-                        self.end = None;
-                    } else if let Some(left) = curr.left() {
-                        // go to left
-                        self.end_prev = PreviousStep::Parent;
-                        self.end = Some(left);
-                    } else {
-                        self.end_prev = PreviousStep::LeftChild;
-                    }
-                    unsafe {
-                        return Some((std::ptr::read(curr.key()), std::ptr::read(curr.value())));
-                    }
-                }
-                PreviousStep::LeftChild => {
-                    // ascended from left, so ascend again
-                    self.end = curr.parent();
-                    if let Some(ChildIndex::Right) = curr.index_on_parent() {
-                        self.start_prev = PreviousStep::RightChild;
-                    }
-                    // deallocate now and forget, because it will be dropped on outside.
-                    std::mem::forget(unsafe { curr.deallocate() });
-                }
+        let curr = self.end?;
+        // Safety: The key and value are moved out only once, and the node will be deallocated without dropping them.
+        let entry = unsafe { (std::ptr::read(curr.key()), std::ptr::read(curr.value())) };
+        if self.start == self.end {
+            self.finish(curr);
+            return Some(entry);
+        }
+        if let Some(left) = curr.left() {
+            // `curr` will be deallocated after its left subtree
+            self.end = Some(left.max_child());
+            return Some(entry);
+        }
+        // the subtree of `curr` was cut, so ascend until from the right with deallocating
+        let mut current = curr;
+        loop {
+            let (idx, parent) = current
+                .index_and_parent()
+                .expect("the previous node must exist");
+            // Safety: The key and value were already moved out.
+            std::mem::forget(unsafe { current.deallocate() });
+            if idx.is_right() {
+                self.end = Some(parent);
+                return Some(entry);
             }
+            current = parent;
+        }
+    }
+
+    /// Deallocates `last` and its ancestors, which are all of the rest nodes after the both sides met at `last`.
+    fn finish(&mut self, last: Node<K, V>) {
+        self.start = None;
+        self.end = None;
+        let mut current = Some(last);
+        while let Some(curr) = current {
+            current = curr.parent();
+            // Safety: The key and value were already moved out.
+            std::mem::forget(unsafe { curr.deallocate() });
         }
-        None
     }
 }
 
+/// A range of nodes to be referred. `start` and `end` always point the next nodes to be cut from each side.
 #[derive(Debug)]
 pub struct RefLeafRange<K, V> {
     start: Option<Node<K, V>>,
-    start_prev: PreviousStep,
     end: Option<Node<K, V>>,
-    end_prev: PreviousStep,
+    guard: ModGuard,
 }
 
 impl<K, V> Clone for RefLeafRange<K, V> {
@@ -131,18 +108,19 @@ impl<K, V> Clone for RefLeafRange<K, V> {
 impl<K, V> RefLeafRange<K, V> {
     pub fn all(tree: &RbTreeMap<K, V>) -> Self {
         let root = tree.root.inner();
-        let (start, end) = if let Some((start, end)) =
-            root.map(|r| r.min_child()).zip(root.map(|r| r.max_child()))
-        {
-            (Some(start), Some(end))
-        } else {
-            (None, None)
-        };
         Self {
-            start,
-            start_prev: PreviousStep::LeftChild,
-            end,
-            end_prev: PreviousStep::RightChild,
+            start: root.map(|r| r.min_child()),
+            end: root.map(|r| r.max_child()),
+            guard: tree.root.modifications().guard(),
+        }
+    }
+
+    /// Creates a range over `root` and its descendants, where `root` is a node of `tree`.
+    pub fn subtree(tree: &RbTreeMap<K, V>, root: Node<K, V>) -> Self {
+        Self {
+            start: Some(root.min_child()),
+            end: Some(root.max_child()),
+            guard: tree.root.modifications().guard(),
         }
     }
 
@@ -161,88 +139,76 @@ impl<K, V> RefLeafRange<K, V> {
         };
         Self {
             start,
-            start_prev: PreviousStep::LeftChild,
             end,
-            end_prev: PreviousStep::RightChild,
+            guard: tree.root.modifications().guard(),
         }
     }
 
+    /// Returns the node which will be cut from the left next, without cutting it.
+    pub fn peek_left(&self) -> Option<Node<K, V>> {
+        self.guard.check();
+        self.start
+    }
+
+    /// Returns the node which will be cut from the right next, without cutting it.
+    pub fn peek_right(&self) -> Option<Node<K, V>> {
+        self.guard.check();
+        self.end
+    }
+
     pub fn cut_left(&mut self) -> Option<Node<K, V>> {
-        while let Some(curr) = self.start {
-            match self.start_prev {
-                PreviousStep::Parent => {
-                    // descended
-                    if let Some(left) = curr.left() {
-                        // go to left
-                        self.start = Some(left);
-                        continue;
-                    }
-                    self.start_prev = PreviousStep::LeftChild;
-                }
-                PreviousStep::LeftChild => {
-                    // ascended from left
-                    if self.start == self.end && self.end_prev.is_right_child() {
-                        // finish
-                        self.start = None;
-                        self.end = None;
-                    } else if let Some(right) = curr.right() {
-                        // go to right
-                        self.start_prev = PreviousStep::Parent;
-                        self.start = Some(right);
-                    } else {
-                        self.start_prev = PreviousStep::RightChild;
-                    }
-                    return Some(curr);
-                }
-                PreviousStep::RightChild => {
-                    // ascended from right, so ascend again
-                    self.start = curr.parent();
-                    if let Some(ChildIndex::Left) = curr.index_on_parent() {
-                        self.start_prev = PreviousStep::LeftChild;
-                    }
-                }
-            }
+        self.guard.check();
+        let curr = self.start?;
+        if self.start == self.end {
+            // finish
+            self.start = None;
+            self.end = None;
+        } else {
+            self.start = curr.successor();
         }
-        None
+        Some(curr)
     }
 
     pub fn cut_right(&mut self) -> Option<Node<K, V>> {
-        while let Some(curr) = self.end {
-            match self.end_prev {
-                PreviousStep::Parent => {
-                    // descended
-                    if let Some(right) = curr.right() {
-                        // go to right
-                        self.end = Some(right);
-                        continue;
-                    }
-                    self.end_prev = PreviousStep::RightChild;
-                }
-                PreviousStep::RightChild => {
-                    // ascended from right
-                    if self.start == self.end && self.start_prev.is_left_child() {
-                        // finish
-                        self.start = None;
-                        self.end = None;
-                    } else if let Some(left) = curr.left() {
-                        // go to left
-                        self.end_prev = PreviousStep::Parent;
-                        self.end = Some(left);
-                    } else {
-                        self.end_prev = PreviousStep::LeftChild;
-                    }
-                    return Some(curr);
-                }
-                PreviousStep::LeftChild => {
-                    // ascended from left, so ascend again
-                    self.end = curr.parent();
-                    if let Some(ChildIndex::Right) = curr.index_on_parent() {
-                        self.start_prev = PreviousStep::RightChild;
-                    }
-                }
-            }
+        self.guard.check();
+        let curr = self.end?;
+        if self.start == self.end {
+            // finish
+            self.start = None;
+            self.end = None;
+        } else {
+            self.end = curr.predecessor();
         }
-        None
+        Some(curr)
+    }
+
+    /// Bounds the number of the rest nodes without walking. The both sides tell only whether zero, one or more nodes are left.
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) if start == end => (1, Some(1)),
+            (Some(_), Some(_)) => (2, None),
+            _ => (0, Some(0)),
+        }
+    }
+
+    /// Counts the rest nodes by walking from `start` to `end`, which takes `O(k)` time for `k` nodes.
+    pub fn walk_len(&self) -> usize {
+        let mut range = self.clone();
+        let mut len = 0;
+        while range.cut_left().is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Cuts `n` nodes from the left without referring them. Returns whether `n` nodes were cut.
+    pub fn skip_left(&mut self, n: usize) -> bool {
+        (0..n).all(|_| self.cut_left().is_some())
+    }
+
+    /// Cuts `n` nodes from the right without referring them. Returns whether `n` nodes were cut.
+    pub fn skip_right(&mut self, n: usize) -> bool {
+        (0..n).all(|_| self.cut_right().is_some())
     }
 }
 
@@ -252,62 +218,47 @@ where
     Q: ?Sized + Ord,
     R: ops::RangeBounds<Q>,
 {
-    use std::cmp::Ordering;
+    // the minimum node in the start bound
     let lower = {
-        let cmp = |key: &Q| match range.start_bound() {
-            ops::Bound::Included(b) => b.cmp(key),
-            ops::Bound::Excluded(b) => b.cmp(key).then(Ordering::Less),
-            ops::Bound::Unbounded => Ordering::Less,
+        let in_bound = |key: &Q| match range.start_bound() {
+            ops::Bound::Included(b) => b <= key,
+            ops::Bound::Excluded(b) => b < key,
+            ops::Bound::Unbounded => true,
         };
-        let mut current = root;
-        loop {
-            match cmp(current.key()) {
-                Ordering::Less => {
-                    if let Some(left) = current.left().filter(|left| cmp(left.key()).is_le()) {
-                        current = left;
-                        continue;
-                    }
-                }
-                Ordering::Equal => {}
-                Ordering::Greater => {
-                    if let Some(right) = current.right() {
-                        current = right;
-                        continue;
-                    }
-                }
+        let mut found = None;
+        let mut current = Some(root);
+        while let Some(node) = current {
+            if in_bound(node.key()) {
+                // the node is a candidate, but smaller ones may be in the left
+                found = Some(node);
+                current = node.left();
+            } else {
+                current = node.right();
             }
-            break;
         }
-        current
+        found?
     };
+    // the maximum node in the end bound
     let upper = {
-        let cmp = |key: &Q| match range.end_bound() {
-            ops::Bound::Included(b) => key.cmp(b),
-            ops::Bound::Excluded(b) => key.cmp(b).then(Ordering::Less),
-            ops::Bound::Unbounded => Ordering::Less,
+        let in_bound = |key: &Q| match range.end_bound() {
+            ops::Bound::Included(b) => key <= b,
+            ops::Bound::Excluded(b) => key < b,
+            ops::Bound::Unbounded => true,
         };
-        let mut current = root;
-        loop {
-            match cmp(current.key()) {
-                Ordering::Greater => {
-                    if let Some(left) = current.left() {
-                        current = left;
-                        continue;
-                    }
-                }
-                Ordering::Equal => {}
-                Ordering::Less => {
-                    if let Some(right) = current.right().filter(|right| cmp(right.key()).is_le()) {
-                        current = right;
-                        continue;
-                    }
-                }
+        let mut found = None;
+        let mut current = Some(root);
+        while let Some(node) = current {
+            if in_bound(node.key()) {
+                // the node is a candidate, but larger ones may be in the right
+                found = Some(node);
+                current = node.right();
+            } else {
+                current = node.left();
             }
-            break;
         }
-        current
+        found?
     };
-    if upper.key() < lower.key() {
+    if upper.key::<Q>() < lower.key() {
         // if empty range
         None
     } else {
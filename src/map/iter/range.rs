@@ -1,8 +1,8 @@
 use std::{borrow, fmt, iter::FusedIterator, marker::PhantomData, ops};
 
-use crate::RbTreeMap;
+use crate::{node::Node, RbTreeMap};
 
-use super::RefLeafRange;
+use super::{RangeKeys, RefLeafRange};
 
 impl<K: Ord, V> RbTreeMap<K, V> {
     /// Constructs a double-ended iterator over a sub-range of elements in the map.
@@ -23,7 +23,7 @@ impl<K: Ord, V> RbTreeMap<K, V> {
     /// assert_eq!(map.range(4..).next(), Some((&5, &"b")));
     /// ```
     #[inline]
-    pub fn range<I, R>(&self, range: R) -> Range<K, V>
+    pub fn range<I, R>(&self, range: R) -> Range<'_, K, V>
     where
         I: Ord + ?Sized,
         K: borrow::Borrow<I>,
@@ -32,6 +32,75 @@ impl<K: Ord, V> RbTreeMap<K, V> {
         Range(RefLeafRange::new(self, range), PhantomData)
     }
 
+    /// Constructs a double-ended iterator over the keys in a sub-range of the map, in sorted order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = (0..10).map(|i| (i, i * i)).collect();
+    ///
+    /// assert!(map.range_keys(3..6).copied().eq([3, 4, 5]));
+    /// assert!(map.range_keys(..=2).rev().copied().eq([2, 1, 0]));
+    /// ```
+    #[inline]
+    pub fn range_keys<I, R>(&self, range: R) -> RangeKeys<'_, K, V>
+    where
+        I: Ord + ?Sized,
+        K: borrow::Borrow<I>,
+        R: ops::RangeBounds<I>,
+    {
+        RangeKeys(self.range(range))
+    }
+
+    /// Constructs a double-ended iterator over the elements from the least key not less than `key` to the end of the map. It is the same as `range(key..)`, but `key` can be unsized such as `str`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<String, i32> = [("apple", 1), ("banana", 2), ("cherry", 3)]
+    ///     .into_iter()
+    ///     .map(|(k, v)| (k.to_owned(), v))
+    ///     .collect();
+    ///
+    /// assert!(map.iter_from("b").map(|(_, &v)| v).eq([2, 3]));
+    /// assert_eq!(map.iter_from("cherry").next(), Some((&"cherry".to_owned(), &3)));
+    /// ```
+    #[inline]
+    pub fn iter_from<Q>(&self, key: &Q) -> Range<'_, K, V>
+    where
+        Q: Ord + ?Sized,
+        K: borrow::Borrow<Q>,
+    {
+        self.range((ops::Bound::Included(key), ops::Bound::Unbounded))
+    }
+
+    /// Constructs a mutable double-ended iterator over the elements from the least key not less than `key` to the end of the map, as [`iter_from`](Self::iter_from) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<_, _> = (1..=5).map(|k| (k, 0)).collect();
+    ///
+    /// for (_, v) in map.iter_from_mut(&3) {
+    ///     *v += 1;
+    /// }
+    /// assert!(map.values().eq(&[0, 0, 1, 1, 1]));
+    /// ```
+    #[inline]
+    pub fn iter_from_mut<Q>(&mut self, key: &Q) -> RangeMut<'_, K, V>
+    where
+        Q: Ord + ?Sized,
+        K: borrow::Borrow<Q>,
+    {
+        self.range_mut((ops::Bound::Included(key), ops::Bound::Unbounded))
+    }
+
     /// Constructs a mutable double-ended iterator over a sub-range of elements in the map.
     ///
     /// # Examples
@@ -51,7 +120,7 @@ impl<K: Ord, V> RbTreeMap<K, V> {
     /// }
     /// ```
     #[inline]
-    pub fn range_mut<I, R>(&mut self, range: R) -> RangeMut<K, V>
+    pub fn range_mut<I, R>(&mut self, range: R) -> RangeMut<'_, K, V>
     where
         I: Ord + ?Sized,
         K: borrow::Borrow<I>,
@@ -59,6 +128,135 @@ impl<K: Ord, V> RbTreeMap<K, V> {
     {
         RangeMut(RefLeafRange::new(self, range), PhantomData)
     }
+
+    /// Constructs a mutable double-ended iterator over the values whose keys are in a sub-range of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<i32, i32> = (0..10).map(|i| (i, 0)).collect();
+    /// for value in map.range_values_mut(3..7) {
+    ///     *value += 1;
+    /// }
+    /// assert_eq!(map.values().sum::<i32>(), 4);
+    /// assert_eq!(map[&3], 1);
+    /// assert_eq!(map[&7], 0);
+    /// ```
+    #[inline]
+    pub fn range_values_mut<I, R>(&mut self, range: R) -> impl DoubleEndedIterator<Item = &mut V>
+    where
+        I: Ord + ?Sized,
+        K: borrow::Borrow<I>,
+        R: ops::RangeBounds<I>,
+    {
+        self.range_mut(range).map(|(_, v)| v)
+    }
+
+    /// Scans the entries in a sub-range of the map in ascending order of key, and returns the first non-`None` result of `f`. The scan stops at the entry matched, so `f` is not called for the later entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let stock: RbTreeMap<u32, u32> = [(100, 0), (110, 3), (120, 0), (130, 8)].into_iter().collect();
+    ///
+    /// // the first price in the window which has any stock
+    /// assert_eq!(stock.find_map_range(105..=130, |&price, &n| (n > 0).then_some(price)), Some(110));
+    /// assert_eq!(stock.find_map_range(111..=125, |&price, &n| (n > 0).then_some(price)), None);
+    /// ```
+    pub fn find_map_range<I, R, F, B>(&self, range: R, mut f: F) -> Option<B>
+    where
+        I: Ord + ?Sized,
+        K: borrow::Borrow<I>,
+        R: ops::RangeBounds<I>,
+        F: FnMut(&K, &V) -> Option<B>,
+    {
+        let mut range = RefLeafRange::new(self, range);
+        while let Some(node) = range.cut_left() {
+            // Safety: The reference will not live longer than `self`.
+            let (key, value) = unsafe { node.key_value() };
+            if let Some(found) = f(key, value) {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// Returns the position of the first entry in a sub-range of the map, and the number of the entries in the sub-range. The position is the number of the entries before the sub-range, so it is also valid for the empty sub-range.
+    ///
+    /// The subtree sizes are not tracked in the tree, so the entries are counted by walking from the minimum, taking `O(start + count)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(10, "a"), (20, "b"), (30, "c"), (40, "d")].into_iter().collect();
+    ///
+    /// let (start, count) = map.range_indices(15..=30);
+    /// assert_eq!((start, count), (1, 2));
+    /// assert_eq!(map.iter().nth(start), Some((&20, &"b")));
+    ///
+    /// assert_eq!(map.range_indices(31..35), (3, 0));
+    /// ```
+    pub fn range_indices<I, R>(&self, range: R) -> (usize, usize)
+    where
+        I: Ord + ?Sized,
+        K: borrow::Borrow<I>,
+        R: ops::RangeBounds<I>,
+    {
+        let before_start = |key: &K| match range.start_bound() {
+            ops::Bound::Included(start) => key.borrow() < start,
+            ops::Bound::Excluded(start) => key.borrow() <= start,
+            ops::Bound::Unbounded => false,
+        };
+        let start = self.keys().take_while(|key| before_start(key)).count();
+        let count = self.range(range).count();
+        (start, count)
+    }
+
+    /// Constructs an iterator over successive chunks of at most `chunk_size` entries in a sub-range of the map, in ascending order of key. Only the last chunk may be shorter than `chunk_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<i32, char> = (0..10).zip('a'..).collect();
+    ///
+    /// let mut pages = map.range_chunks(3.., 3);
+    /// assert_eq!(pages.next(), Some(vec![(&3, &'d'), (&4, &'e'), (&5, &'f')]));
+    /// assert_eq!(pages.next(), Some(vec![(&6, &'g'), (&7, &'h'), (&8, &'i')]));
+    /// assert_eq!(pages.next(), Some(vec![(&9, &'j')]));
+    /// assert_eq!(pages.next(), None);
+    /// ```
+    pub fn range_chunks<I, R>(
+        &self,
+        range: R,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = Vec<(&K, &V)>>
+    where
+        I: Ord + ?Sized,
+        K: borrow::Borrow<I>,
+        R: ops::RangeBounds<I>,
+    {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        let mut range = self.range(range);
+        std::iter::from_fn(move || {
+            // the rest of the range is not known without walking, and `chunk_size` may be too large to allocate, so the chunk grows as filled.
+            let first = range.next()?;
+            let mut chunk = vec![first];
+            chunk.extend(range.by_ref().take(chunk_size - 1));
+            Some(chunk)
+        })
+    }
 }
 
 pub struct Range<'a, K, V>(RefLeafRange<K, V>, PhantomData<&'a ()>);
@@ -69,6 +267,70 @@ impl<K, V> Clone for Range<'_, K, V> {
     }
 }
 
+impl<'a, K, V> Range<'a, K, V> {
+    /// Creates a range over `root` and its descendants, where `root` is a node of `tree`.
+    pub(crate) fn subtree(tree: &'a RbTreeMap<K, V>, root: Node<K, V>) -> Self {
+        Self(RefLeafRange::subtree(tree, root), PhantomData)
+    }
+
+    /// Returns the entry which the next call of [`next`](Iterator::next) will return, without advancing the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    ///
+    /// let mut range = map.range(2..);
+    /// assert_eq!(range.peek(), Some((&2, &"b")));
+    /// assert_eq!(range.next(), Some((&2, &"b")));
+    /// assert_eq!(range.peek(), Some((&3, &"c")));
+    /// ```
+    pub fn peek(&self) -> Option<(&'a K, &'a V)> {
+        // Safety: The reference will not live longer than the tree.
+        self.0.peek_left().map(|n| unsafe { n.key_value() })
+    }
+
+    /// Returns the entry which the next call of [`next_back`](DoubleEndedIterator::next_back) will return, without advancing the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    ///
+    /// let mut range = map.range(..3);
+    /// assert_eq!(range.peek_back(), Some((&2, &"b")));
+    /// assert_eq!(range.next_back(), Some((&2, &"b")));
+    /// assert_eq!(range.peek_back(), Some((&1, &"a")));
+    /// ```
+    pub fn peek_back(&self) -> Option<(&'a K, &'a V)> {
+        // Safety: The reference will not live longer than the tree.
+        self.0.peek_right().map(|n| unsafe { n.key_value() })
+    }
+
+    /// Counts the rest entries of the range without advancing the iterator. It walks the rest of the range, so it takes `O(k)` time for `k` entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = (0..10).map(|i| (i, ())).collect();
+    ///
+    /// let mut range = map.range(3..8);
+    /// assert_eq!(range.count_remaining(), 5);
+    /// range.next();
+    /// range.next_back();
+    /// assert_eq!(range.count_remaining(), 3);
+    /// ```
+    pub fn count_remaining(&self) -> usize {
+        self.0.walk_len()
+    }
+}
+
 impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Range<'_, K, V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
@@ -87,6 +349,18 @@ where
         self.0.cut_left().map(|n| unsafe { n.key_value() })
     }
 
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.0.skip_left(n) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+
     fn last(mut self) -> Option<Self::Item> {
         // Safety: The reference will not live longer than the tree.
         self.0.cut_right().map(|n| unsafe { n.key_value() })
@@ -99,6 +373,18 @@ where
     fn max(self) -> Option<Self::Item> {
         self.last()
     }
+
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let mut acc = init;
+        while let Some(node) = self.0.cut_left() {
+            // Safety: The reference will not live longer than the tree.
+            acc = f(acc, unsafe { node.key_value() });
+        }
+        acc
+    }
 }
 
 impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V>
@@ -110,6 +396,14 @@ where
         // Safety: The reference will not live longer than the tree.
         self.0.cut_right().map(|n| unsafe { n.key_value() })
     }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if self.0.skip_right(n) {
+            self.next_back()
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, K, V> FusedIterator for Range<'a, K, V>
@@ -121,6 +415,26 @@ where
 
 pub struct RangeMut<'a, K, V>(RefLeafRange<K, V>, PhantomData<&'a mut ()>);
 
+impl<K, V> RangeMut<'_, K, V> {
+    /// Counts the rest entries of the range without advancing the iterator, as [`Range::count_remaining`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<_, _> = (0..10).map(|i| (i, 0)).collect();
+    ///
+    /// let mut range = map.range_mut(3..8);
+    /// assert_eq!(range.count_remaining(), 5);
+    /// range.next();
+    /// assert_eq!(range.count_remaining(), 4);
+    /// ```
+    pub fn count_remaining(&self) -> usize {
+        self.0.walk_len()
+    }
+}
+
 impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RangeMut<'_, K, V>
 where
     K: Ord,
@@ -142,6 +456,18 @@ where
         self.0.cut_left().map(|n| unsafe { n.key_value_mut() })
     }
 
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.0.skip_left(n) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+
     fn last(mut self) -> Option<Self::Item> {
         // Safety: The mutable reference will not live longer than the tree.
         self.0.cut_right().map(|n| unsafe { n.key_value_mut() })
@@ -165,6 +491,14 @@ where
         // Safety: The mutable reference will not live longer than the tree.
         self.0.cut_right().map(|n| unsafe { n.key_value_mut() })
     }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if self.0.skip_right(n) {
+            self.next_back()
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, K, V> FusedIterator for RangeMut<'a, K, V>
@@ -41,7 +41,7 @@ impl<K, V> RbTreeMap<K, V> {
     /// assert_eq!(values, ["hello", "goodbye"]);
     /// ```
     #[inline]
-    pub fn values(&self) -> Values<K, V> {
+    pub fn values(&self) -> Values<'_, K, V> {
         Values(self.into_iter(), self.len())
     }
 
@@ -67,7 +67,7 @@ impl<K, V> RbTreeMap<K, V> {
     /// ]);
     /// ```
     #[inline]
-    pub fn values_mut(&mut self) -> ValuesMut<K, V> {
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
         let len = self.len();
         ValuesMut(self.into_iter(), len)
     }
@@ -84,6 +84,10 @@ impl<K, V> Iterator for IntoValues<K, V> {
         self.0.size_hint()
     }
 
+    fn count(self) -> usize {
+        self.0.count()
+    }
+
     fn last(mut self) -> Option<Self::Item> {
         self.next_back()
     }
@@ -105,6 +109,12 @@ impl<K, V> FusedIterator for IntoValues<K, V> {}
 
 pub struct Values<'a, K, V>(Iter<'a, K, V>, usize);
 
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), self.1)
+    }
+}
+
 impl<'a, K: 'a + Ord, V: 'a> Iterator for Values<'a, K, V> {
     type Item = &'a V;
 
@@ -119,6 +129,10 @@ impl<'a, K: 'a + Ord, V: 'a> Iterator for Values<'a, K, V> {
         self.0.size_hint()
     }
 
+    fn count(self) -> usize {
+        self.1
+    }
+
     fn last(mut self) -> Option<Self::Item> {
         self.next_back()
     }
@@ -157,6 +171,10 @@ impl<'a, K: 'a + Ord, V: 'a> Iterator for ValuesMut<'a, K, V> {
         self.0.size_hint()
     }
 
+    fn count(self) -> usize {
+        self.1
+    }
+
     fn last(mut self) -> Option<Self::Item> {
         self.next_back()
     }
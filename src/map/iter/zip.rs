@@ -0,0 +1,144 @@
+use std::{cmp::Ordering, iter::FusedIterator, iter::Peekable};
+
+use crate::RbTreeMap;
+
+use super::Iter;
+
+impl<K: Ord, V> RbTreeMap<K, V> {
+    /// Gets an iterator which walks `self` and `other` together in ascending order of key. It yields each distinct key with the values of the key in `self` and `other`, at least one of which is present.
+    ///
+    /// It takes `O(m + n)` time and allocates nothing, so it is the basis to diff two maps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let a: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    /// let b: RbTreeMap<_, _> = [(2, 20), (3, 30)].into_iter().collect();
+    ///
+    /// let mut zip = a.zip_keys(&b);
+    /// assert_eq!(zip.next(), Some((&1, Some(&"a"), None)));
+    /// assert_eq!(zip.next(), Some((&2, Some(&"b"), Some(&20))));
+    /// assert_eq!(zip.next(), Some((&3, None, Some(&30))));
+    /// assert_eq!(zip.next(), None);
+    /// ```
+    pub fn zip_keys<'a, W>(&'a self, other: &'a RbTreeMap<K, W>) -> ZipKeys<'a, K, V, W> {
+        ZipKeys {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+        }
+    }
+
+    /// Gets an iterator over the entries of `self` whose keys are absent from `other`, in ascending order of key. It walks the both maps together as [`zip_keys`](Self::zip_keys) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let before: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    /// let after: RbTreeMap<_, _> = [(2, "B"), (4, "D")].into_iter().collect();
+    ///
+    /// // the records removed from `before`
+    /// assert!(before.difference(&after).eq([(&1, &"a"), (&3, &"c")]));
+    /// ```
+    pub fn difference<'a, W>(
+        &'a self,
+        other: &'a RbTreeMap<K, W>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> {
+        self.zip_keys(other).filter_map(|entry| match entry {
+            (key, Some(value), None) => Some((key, value)),
+            _ => None,
+        })
+    }
+
+    /// Gets an iterator over the changes from `self` to `other` by key, in ascending order of key. It walks the both maps together as [`zip_keys`](Self::zip_keys) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::iter::Change, RbTreeMap};
+    ///
+    /// let old: RbTreeMap<_, _> = [("host", "a"), ("port", "80")].into_iter().collect();
+    /// let new: RbTreeMap<_, _> = [("port", "8080"), ("tls", "on")].into_iter().collect();
+    ///
+    /// let mut diff = old.diff(&new);
+    /// assert_eq!(diff.next(), Some((&"host", Change::Removed(&"a"))));
+    /// assert_eq!(diff.next(), Some((&"port", Change::Common(&"80", &"8080"))));
+    /// assert_eq!(diff.next(), Some((&"tls", Change::Added(&"on"))));
+    /// assert_eq!(diff.next(), None);
+    /// ```
+    pub fn diff<'a, W>(
+        &'a self,
+        other: &'a RbTreeMap<K, W>,
+    ) -> impl Iterator<Item = (&'a K, Change<'a, V, W>)> {
+        self.zip_keys(other).map(|entry| match entry {
+            (key, Some(value), None) => (key, Change::Removed(value)),
+            (key, None, Some(other)) => (key, Change::Added(other)),
+            (key, Some(value), Some(other)) => (key, Change::Common(value, other)),
+            (_, None, None) => unreachable!("one of the values must be present"),
+        })
+    }
+}
+
+/// A change of a key from a map to another one, yielded by [`RbTreeMap::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change<'a, V, W> {
+    /// The key is only in the other map.
+    Added(&'a W),
+    /// The key is only in the map.
+    Removed(&'a V),
+    /// The key is in the both maps.
+    Common(&'a V, &'a W),
+}
+
+/// An iterator over the entries of two maps merged by key, created by [`RbTreeMap::zip_keys`].
+pub struct ZipKeys<'a, K, V, W> {
+    a: Peekable<Iter<'a, K, V>>,
+    b: Peekable<Iter<'a, K, W>>,
+}
+
+impl<K, V, W> Clone for ZipKeys<'_, K, V, W> {
+    fn clone(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+}
+
+impl<'a, K: Ord, V, W> Iterator for ZipKeys<'a, K, V, W> {
+    type Item = (&'a K, Option<&'a V>, Option<&'a W>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let order = match (self.a.peek(), self.b.peek()) {
+            (Some((a_key, _)), Some((b_key, _))) => a_key.cmp(b_key),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+        Some(match order {
+            Ordering::Less => {
+                let (key, value) = self.a.next()?;
+                (key, Some(value), None)
+            }
+            Ordering::Equal => {
+                let (key, value) = self.a.next()?;
+                let (_, other) = self.b.next()?;
+                (key, Some(value), Some(other))
+            }
+            Ordering::Greater => {
+                let (key, other) = self.b.next()?;
+                (key, None, Some(other))
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_len, b_len) = (self.a.len(), self.b.len());
+        (a_len.max(b_len), a_len.checked_add(b_len))
+    }
+}
+
+impl<K: Ord, V, W> FusedIterator for ZipKeys<'_, K, V, W> {}
@@ -0,0 +1,482 @@
+use crate::{
+    node::{ChildIndex, Node},
+    RbTreeMap,
+};
+
+use std::{borrow::Borrow, cmp::Ordering, fmt};
+
+impl<K, V> RbTreeMap<K, V> {
+    /// Creates a raw immutable entry builder for the map, which looks up an entry by a comparator instead of the key's [`Ord`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert((1, "a"), 10);
+    /// map.insert((2, "b"), 20);
+    ///
+    /// let found = map.raw_entry().search_by(|&(n, _)| n.cmp(&2));
+    /// assert_eq!(found, Some((&(2, "b"), &20)));
+    /// ```
+    #[inline]
+    pub fn raw_entry(&self) -> RawEntryBuilder<'_, K, V> {
+        RawEntryBuilder { tree: self }
+    }
+
+    /// Creates a raw mutable entry builder for the map, which looks up an entry by a comparator instead of the key's [`Ord`] and then inserts or mutates at the found position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert((1, "a"), 10);
+    ///
+    /// *map.raw_entry_mut()
+    ///     .from_key_by(|&(n, _)| n.cmp(&1))
+    ///     .or_insert((1, "z"), 0)
+    ///     .1 += 1;
+    /// map.raw_entry_mut()
+    ///     .from_key_by(|&(n, _)| n.cmp(&2))
+    ///     .or_insert((2, "b"), 20);
+    ///
+    /// assert_eq!(map[&(1, "a")], 11);
+    /// assert_eq!(map[&(2, "b")], 20);
+    /// ```
+    #[inline]
+    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<'_, K, V> {
+        RawEntryBuilderMut { tree: self }
+    }
+}
+
+/// A builder for looking up an entry of a map by a comparator.
+///
+/// This `struct` is created by the [`RbTreeMap::raw_entry`] method.
+pub struct RawEntryBuilder<'a, K, V> {
+    tree: &'a RbTreeMap<K, V>,
+}
+
+impl<K, V> fmt::Debug for RawEntryBuilder<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawEntryBuilder").finish_non_exhaustive()
+    }
+}
+
+impl<'a, K, V> RawEntryBuilder<'a, K, V> {
+    /// Finds the entry by the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    ///
+    /// assert_eq!(map.raw_entry().from_key(&1), Some((&1, &"a")));
+    /// assert_eq!(map.raw_entry().from_key(&2), None);
+    /// ```
+    #[inline]
+    pub fn from_key<Q>(self, key: &Q) -> Option<(&'a K, &'a V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search_by(|k| k.borrow().cmp(key))
+    }
+
+    /// Finds the entry by `cmp`, which returns the ordering of the key against the target like [`slice::binary_search_by`]. The ordering must be consistent with the order of the keys in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(("apple".to_string(), 3), "a");
+    /// map.insert(("banana".to_string(), 1), "b");
+    ///
+    /// let found = map.raw_entry().search_by(|(name, _)| name.as_str().cmp("banana"));
+    /// assert_eq!(found, Some((&("banana".to_string(), 1), &"b")));
+    /// ```
+    #[inline]
+    pub fn search_by<F>(self, cmp: F) -> Option<(&'a K, &'a V)>
+    where
+        F: FnMut(&K) -> Ordering,
+    {
+        let found = self.tree.root.search_by(cmp)?.ok()?;
+        // Safety: The references will not live longer than the map.
+        Some(unsafe { found.key_value() })
+    }
+}
+
+/// A builder for looking up an entry of a map by a comparator, to insert or mutate it.
+///
+/// This `struct` is created by the [`RbTreeMap::raw_entry_mut`] method.
+pub struct RawEntryBuilderMut<'a, K, V> {
+    tree: &'a mut RbTreeMap<K, V>,
+}
+
+impl<K, V> fmt::Debug for RawEntryBuilderMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawEntryBuilderMut").finish_non_exhaustive()
+    }
+}
+
+impl<'a, K, V> RawEntryBuilderMut<'a, K, V> {
+    /// Gets the raw entry by the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert(1, "a");
+    ///
+    /// assert!(matches!(map.raw_entry_mut().from_key(&1), RawEntryMut::Occupied(_)));
+    /// assert!(matches!(map.raw_entry_mut().from_key(&2), RawEntryMut::Vacant(_)));
+    /// ```
+    #[inline]
+    pub fn from_key<Q>(self, key: &Q) -> RawEntryMut<'a, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.from_key_by(|k| k.borrow().cmp(key))
+    }
+
+    /// Gets the raw entry by `cmp`, which returns the ordering of the key against the target like [`slice::binary_search_by`]. The ordering must be consistent with the order of the keys in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert((1, "a"), 10);
+    ///
+    /// if let RawEntryMut::Occupied(mut o) = map.raw_entry_mut().from_key_by(|&(n, _)| n.cmp(&1)) {
+    ///     *o.get_mut() += 1;
+    /// }
+    /// assert_eq!(map[&(1, "a")], 11);
+    /// ```
+    #[inline]
+    pub fn from_key_by<F>(self, cmp: F) -> RawEntryMut<'a, K, V>
+    where
+        F: FnMut(&K) -> Ordering,
+    {
+        match self.tree.root.search_by(cmp) {
+            Some(Ok(node)) => RawEntryMut::Occupied(RawOccupiedEntryMut {
+                node,
+                tree: self.tree,
+            }),
+            Some(Err(position)) => RawEntryMut::Vacant(RawVacantEntryMut {
+                position: Some(position),
+                tree: self.tree,
+            }),
+            None => RawEntryMut::Vacant(RawVacantEntryMut {
+                position: None,
+                tree: self.tree,
+            }),
+        }
+    }
+}
+
+/// A raw view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is created by the [`RawEntryBuilderMut`] methods.
+pub enum RawEntryMut<'a, K, V> {
+    /// An occupied entry.
+    Occupied(RawOccupiedEntryMut<'a, K, V>),
+    /// A vacant entry.
+    Vacant(RawVacantEntryMut<'a, K, V>),
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RawEntryMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawEntryMut::Occupied(entry) => f.debug_tuple("RawEntryMut").field(entry).finish(),
+            RawEntryMut::Vacant(entry) => f.debug_tuple("RawEntryMut").field(entry).finish(),
+        }
+    }
+}
+
+impl<'a, K, V> RawEntryMut<'a, K, V> {
+    /// Ensures a value is in the entry by inserting `default_key` and `default_val` if empty, and returns references to the key and the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    ///
+    /// map.raw_entry_mut().from_key("poneyland").or_insert("poneyland", 3);
+    /// assert_eq!(map["poneyland"], 3);
+    ///
+    /// *map.raw_entry_mut().from_key("poneyland").or_insert("poneyland", 10).1 *= 2;
+    /// assert_eq!(map["poneyland"], 6);
+    /// ```
+    #[inline]
+    pub fn or_insert(self, default_key: K, default_val: V) -> (&'a K, &'a mut V) {
+        self.or_insert_with(move || (default_key, default_val))
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default` function if empty, and returns references to the key and the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map: RbTreeMap<&str, String> = RbTreeMap::new();
+    ///
+    /// map.raw_entry_mut()
+    ///     .from_key("poneyland")
+    ///     .or_insert_with(|| ("poneyland", "hoho".to_string()));
+    ///
+    /// assert_eq!(map["poneyland"], "hoho".to_string());
+    /// ```
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> (K, V)>(self, default: F) -> (&'a K, &'a mut V) {
+        match self {
+            RawEntryMut::Occupied(entry) => entry.into_key_value(),
+            RawEntryMut::Vacant(entry) => {
+                let (key, value) = default();
+                entry.insert(key, value)
+            }
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts into the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let mut map = RbTreeMap::new();
+    ///
+    /// map.raw_entry_mut()
+    ///     .from_key("poneyland")
+    ///     .and_modify(|_, v| *v += 1)
+    ///     .or_insert("poneyland", 42);
+    /// assert_eq!(map["poneyland"], 42);
+    ///
+    /// map.raw_entry_mut()
+    ///     .from_key("poneyland")
+    ///     .and_modify(|_, v| *v += 1)
+    ///     .or_insert("poneyland", 42);
+    /// assert_eq!(map["poneyland"], 43);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn and_modify<F: FnOnce(&K, &mut V)>(mut self, f: F) -> Self {
+        if let RawEntryMut::Occupied(entry) = &mut self {
+            let (key, value) = entry.get_key_value_mut();
+            f(key, value);
+        }
+        self
+    }
+}
+
+/// A raw view into an occupied entry in a `RbTreeMap`. It is part of the [`RawEntryMut`] enum.
+pub struct RawOccupiedEntryMut<'a, K, V> {
+    node: Node<K, V>,
+    tree: &'a mut RbTreeMap<K, V>,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RawOccupiedEntryMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawOccupiedEntryMut")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+impl<'a, K, V> RawOccupiedEntryMut<'a, K, V> {
+    /// Returns a reference to the key in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let RawEntryMut::Occupied(o) = map.raw_entry_mut().from_key("poneyland") {
+    ///     assert_eq!(o.key(), &"poneyland");
+    /// }
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &K {
+        self.node.key()
+    }
+
+    /// Returns a reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let RawEntryMut::Occupied(o) = map.raw_entry_mut().from_key("poneyland") {
+    ///     assert_eq!(o.get(), &12);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get(&self) -> &V {
+        // Safety: The reference will not live longer than `self`.
+        unsafe { self.node.value() }
+    }
+
+    /// Returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let RawEntryMut::Occupied(mut o) = map.raw_entry_mut().from_key("poneyland") {
+    ///     *o.get_mut() += 10;
+    /// }
+    /// assert_eq!(map["poneyland"], 22);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        // Safety: The reference will not live longer than `self`.
+        unsafe { self.node.value_mut() }
+    }
+
+    /// Returns references to the key and the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let RawEntryMut::Occupied(mut o) = map.raw_entry_mut().from_key("poneyland") {
+    ///     let (k, v) = o.get_key_value_mut();
+    ///     *v += k.len();
+    /// }
+    /// assert_eq!(map["poneyland"], 21);
+    /// ```
+    #[inline]
+    pub fn get_key_value_mut(&mut self) -> (&K, &mut V) {
+        // Safety: The references will not live longer than `self`.
+        unsafe { self.node.key_value_mut() }
+    }
+
+    /// Converts the entry into references to its key and value, with a lifetime bound to the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let RawEntryMut::Occupied(o) = map.raw_entry_mut().from_key("poneyland") {
+    ///     *o.into_key_value().1 += 10;
+    /// }
+    /// assert_eq!(map["poneyland"], 22);
+    /// ```
+    #[inline]
+    pub fn into_key_value(self) -> (&'a K, &'a mut V) {
+        // Safety: The references will not live longer than `tree`.
+        unsafe { self.node.key_value_mut() }
+    }
+
+    /// Sets the value of the entry, and returns the old value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let RawEntryMut::Occupied(mut o) = map.raw_entry_mut().from_key("poneyland") {
+    ///     assert_eq!(o.insert(15), 12);
+    /// }
+    /// assert_eq!(map["poneyland"], 15);
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the key-value pair out of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    /// map.insert("poneyland", 12);
+    ///
+    /// if let RawEntryMut::Occupied(o) = map.raw_entry_mut().from_key("poneyland") {
+    ///     assert_eq!(o.remove_entry(), ("poneyland", 12));
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        self.tree.root.delete_node(self.node).unwrap()
+    }
+}
+
+/// A raw view into a vacant entry in a `RbTreeMap`. It is part of the [`RawEntryMut`] enum.
+pub struct RawVacantEntryMut<'a, K, V> {
+    position: Option<(Node<K, V>, ChildIndex)>,
+    tree: &'a mut RbTreeMap<K, V>,
+}
+
+impl<K, V> fmt::Debug for RawVacantEntryMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawVacantEntryMut").finish_non_exhaustive()
+    }
+}
+
+impl<'a, K, V> RawVacantEntryMut<'a, K, V> {
+    /// Inserts the key and the value at the position found by the comparator, and returns references to them.
+    ///
+    /// The key must be ordered consistently with the comparator, otherwise the order of the map will be broken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::{map::raw_entry::RawEntryMut, RbTreeMap};
+    ///
+    /// let mut map = RbTreeMap::new();
+    ///
+    /// if let RawEntryMut::Vacant(v) = map.raw_entry_mut().from_key("poneyland") {
+    ///     *v.insert("poneyland", 37).1 += 1;
+    /// }
+    /// assert_eq!(map["poneyland"], 38);
+    /// ```
+    #[inline]
+    pub fn insert(self, key: K, value: V) -> (&'a K, &'a mut V) {
+        let node = self.tree.root.insert_at(self.position, key, value);
+        // Safety: The references will not live longer than `tree`.
+        unsafe { node.key_value_mut() }
+    }
+}
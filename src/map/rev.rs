@@ -0,0 +1,251 @@
+use crate::RbTreeMap;
+
+use std::{borrow::Borrow, fmt, iter, ops};
+
+use super::iter::{Iter, Keys, Range, Values};
+
+impl<K, V> RbTreeMap<K, V> {
+    /// Gets a view of the map whose keys are ordered in reverse. It borrows the map without allocating anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    /// let rev = map.rev();
+    ///
+    /// assert_eq!(rev.first(), Some((&3, &"c")));
+    /// assert!(rev.keys().copied().eq([3, 2, 1]));
+    /// ```
+    #[inline]
+    pub fn rev(&self) -> RevView<'_, K, V> {
+        RevView { map: self }
+    }
+}
+
+/// A view of [`RbTreeMap`] whose keys are ordered in reverse, created by [`RbTreeMap::rev`].
+pub struct RevView<'a, K, V> {
+    map: &'a RbTreeMap<K, V>,
+}
+
+impl<K, V> Clone for RevView<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for RevView<'_, K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RevView<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> RevView<'a, K, V> {
+    /// Returns the original map in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    /// assert!(map.rev().rev().keys().copied().eq([1, 2]));
+    /// ```
+    #[inline]
+    pub fn rev(self) -> &'a RbTreeMap<K, V> {
+        self.map
+    }
+
+    /// Returns the number of elements in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a")].into_iter().collect();
+    /// assert_eq!(map.rev().len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map = RbTreeMap::<i32, ()>::new();
+    /// assert!(map.rev().is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Gets an iterator over the entries of the map, in descending order of key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    ///
+    /// let mut iter = map.rev().iter();
+    /// assert_eq!(iter.next(), Some((&2, &"b")));
+    /// assert_eq!(iter.next(), Some((&1, &"a")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter(self) -> iter::Rev<Iter<'a, K, V>> {
+        self.map.iter().rev()
+    }
+
+    /// Gets an iterator over the keys of the map, in descending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    /// assert!(map.rev().keys().copied().eq([2, 1]));
+    /// ```
+    #[inline]
+    pub fn keys(self) -> iter::Rev<Keys<'a, K, V>> {
+        self.map.keys().rev()
+    }
+}
+
+impl<'a, K: Ord, V> RevView<'a, K, V> {
+    /// Returns the first entry in the reversed order, that is the entry of the maximum key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    /// assert_eq!(map.rev().first(), Some((&2, &"b")));
+    /// ```
+    #[inline]
+    pub fn first(self) -> Option<(&'a K, &'a V)> {
+        self.map.last()
+    }
+
+    /// Returns the last entry in the reversed order, that is the entry of the minimum key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    /// assert_eq!(map.rev().last(), Some((&1, &"a")));
+    /// ```
+    #[inline]
+    pub fn last(self) -> Option<(&'a K, &'a V)> {
+        self.map.first()
+    }
+
+    /// Gets an iterator over the values of the map, in descending order of key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    /// assert!(map.rev().values().copied().eq(["b", "a"]));
+    /// ```
+    #[inline]
+    pub fn values(self) -> iter::Rev<Values<'a, K, V>> {
+        self.map.values().rev()
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a")].into_iter().collect();
+    /// assert_eq!(map.rev().get(&1), Some(&"a"));
+    /// assert_eq!(map.rev().get(&2), None);
+    /// ```
+    #[inline]
+    pub fn get<Q>(self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get(key)
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a")].into_iter().collect();
+    /// assert!(map.rev().contains_key(&1));
+    /// ```
+    #[inline]
+    pub fn contains_key<Q>(self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the reversed order. The start bound of `range` is the greater key, and the end bound is the less key.
+    ///
+    /// The neighbors of a key in the reversed order can be found by a range from it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    /// use std::ops::Bound::{Excluded, Unbounded};
+    ///
+    /// let map: RbTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+    /// let rev = map.rev();
+    ///
+    /// assert!(rev.range(7..3).map(|(&k, _)| k).eq([7, 6, 5, 4]));
+    /// assert!(rev.range(..=8).map(|(&k, _)| k).eq([9, 8]));
+    ///
+    /// // the next entry after 5 in the reversed order
+    /// assert_eq!(rev.range((Excluded(&5), Unbounded)).next(), Some((&4, &40)));
+    /// ```
+    pub fn range<I, R>(self, range: R) -> iter::Rev<Range<'a, K, V>>
+    where
+        I: Ord + ?Sized,
+        K: Borrow<I>,
+        R: ops::RangeBounds<I>,
+    {
+        self.map
+            .range((range.end_bound(), range.start_bound()))
+            .rev()
+    }
+}
+
+impl<'a, K, V> IntoIterator for RevView<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    type IntoIter = iter::Rev<Iter<'a, K, V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
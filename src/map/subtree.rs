@@ -0,0 +1,118 @@
+use std::{borrow::Borrow, fmt};
+
+use crate::{map::iter::Range, node::Node, RbTreeMap};
+
+impl<K: Ord, V> RbTreeMap<K, V> {
+    /// Returns a handle to the subtree rooted at the node whose key equals to `key`, or `None` if the key is not in the map.
+    ///
+    /// The shape of the tree depends on the history of insertions and removals, so which entries are in the subtree is not specified except that it contains the entry of `key`. It is useful for a hierarchical key space where the entries under a prefix key are grouped by the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = (0..7).map(|i| (i, i * 10)).collect();
+    ///
+    /// let subtree = map.subtree_from(&3).unwrap();
+    /// assert_eq!(subtree.root(), (&3, &30));
+    /// assert!(subtree.iter().any(|(&k, _)| k == 3));
+    /// assert!(map.subtree_from(&10).is_none());
+    /// ```
+    pub fn subtree_from<Q>(&self, key: &Q) -> Option<Subtree<'_, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if let Some(Ok(node)) = self.root.search(key) {
+            Some(Subtree {
+                tree: self,
+                root: node,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A handle to a subtree of [`RbTreeMap`], created by [`RbTreeMap::subtree_from`]. It refers the root node of the subtree and its descendants.
+pub struct Subtree<'a, K, V> {
+    tree: &'a RbTreeMap<K, V>,
+    root: Node<K, V>,
+}
+
+impl<K, V> Clone for Subtree<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for Subtree<'_, K, V> {}
+
+impl<'a, K, V> Subtree<'a, K, V> {
+    /// Returns the entry at the root of the subtree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+    ///
+    /// assert_eq!(map.subtree_from(&2).unwrap().root(), (&2, &"b"));
+    /// ```
+    pub fn root(&self) -> (&'a K, &'a V) {
+        // Safety: The reference will not live longer than the tree.
+        unsafe { self.root.key_value() }
+    }
+
+    /// Gets an iterator over the entries of the subtree, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = (0..15).map(|i| (i, ())).collect();
+    ///
+    /// let subtree = map.subtree_from(&5).unwrap();
+    /// let keys: Vec<_> = subtree.iter().map(|(&k, _)| k).collect();
+    /// assert!(keys.windows(2).all(|w| w[0] + 1 == w[1]));
+    /// assert!(keys.contains(&5));
+    /// ```
+    pub fn iter(&self) -> Range<'a, K, V> {
+        Range::subtree(self.tree, self.root)
+    }
+
+    /// Returns the number of the entries in the subtree. It is counted by walking the subtree, so it takes `O(k)` time for `k` entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = (0..15).map(|i| (i, ())).collect();
+    ///
+    /// let subtree = map.subtree_from(&5).unwrap();
+    /// assert_eq!(subtree.len(), subtree.iter().count());
+    /// ```
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Subtree<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &Subtree<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Range<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
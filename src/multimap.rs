@@ -0,0 +1,242 @@
+#[cfg(test)]
+mod tests;
+
+use crate::RbTreeMap;
+
+use std::{borrow::Borrow, fmt};
+
+/// A multimap based on a red-black tree, which can hold multiple values for equal keys.
+///
+/// Values for an equal key are kept in the order they were inserted.
+pub struct RbTreeMultiMap<K, V> {
+    map: RbTreeMap<K, Vec<V>>,
+    len: usize,
+}
+
+impl<K: fmt::Debug + Ord, V: fmt::Debug> fmt::Debug for RbTreeMultiMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> Default for RbTreeMultiMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for RbTreeMultiMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for RbTreeMultiMap<K, V> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V> RbTreeMultiMap<K, V> {
+    /// Creates an empty `RbTreeMultiMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    ///
+    /// map.insert(1, "a");
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            map: RbTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values in the map, counting every value of equal keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// assert_eq!(map.len(), 0);
+    /// map.insert(1, "a");
+    /// map.insert(1, "b");
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the map contains no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// assert!(map.is_empty());
+    /// map.insert(1, "a");
+    /// assert!(!map.is_empty());
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all values from the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// map.insert(1, "a");
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key. Values of an equal key are visited in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// map.insert(2, "c");
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// let entries: Vec<_> = map.iter().collect();
+    /// assert_eq!(entries, [(&1, &"a"), (&2, &"c"), (&2, &"b")]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.map
+            .iter()
+            .flat_map(|(k, values)| values.iter().map(move |v| (k, v)))
+    }
+}
+
+impl<K: Ord, V> RbTreeMultiMap<K, V> {
+    /// Inserts a key-value pair into the map. The value is always added, even if the map already has values for an equal key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// map.insert(37, "a");
+    /// map.insert(37, "b");
+    /// assert_eq!(map.count(&37), 2);
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.entry(key).or_default().push(value);
+        self.len += 1;
+    }
+
+    /// Gets an iterator over the values corresponding to the key, in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// map.insert(1, "c");
+    ///
+    /// assert_eq!(map.get_all(&1).collect::<Vec<_>>(), [&"a", &"c"]);
+    /// assert_eq!(map.get_all(&3).next(), None);
+    /// ```
+    pub fn get_all<Q>(&self, key: &Q) -> impl Iterator<Item = &V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get(key).into_iter().flatten()
+    }
+
+    /// Returns the number of values corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(1, "b");
+    /// assert_eq!(map.count(&1), 2);
+    /// assert_eq!(map.count(&2), 0);
+    /// ```
+    pub fn count<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get(key).map_or(0, Vec::len)
+    }
+
+    /// Returns whether the map contains any value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.contains_key(&1), true);
+    /// assert_eq!(map.contains_key(&2), false);
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Removes all values corresponding to the key from the map, returning them in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiMap;
+    ///
+    /// let mut map = RbTreeMultiMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(1, "b");
+    /// assert_eq!(map.remove_all(&1), ["a", "b"]);
+    /// assert!(map.remove_all(&1).is_empty());
+    /// ```
+    pub fn remove_all<Q>(&mut self, key: &Q) -> Vec<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let removed = self.map.remove(key).unwrap_or_default();
+        self.len -= removed.len();
+        removed
+    }
+}
@@ -0,0 +1,26 @@
+use crate::RbTreeMultiMap;
+
+#[test]
+fn three_equal_keys() {
+    let mut map = RbTreeMultiMap::new();
+    map.insert(2, 'x');
+    map.insert(1, 'a');
+    map.insert(1, 'b');
+    map.insert(3, 'y');
+    map.insert(1, 'c');
+
+    assert_eq!(map.len(), 5);
+    assert_eq!(map.count(&1), 3);
+    assert_eq!(map.get_all(&1).collect::<Vec<_>>(), vec![&'a', &'b', &'c']);
+    assert_eq!(
+        map.iter().collect::<Vec<_>>(),
+        vec![(&1, &'a'), (&1, &'b'), (&1, &'c'), (&2, &'x'), (&3, &'y')]
+    );
+
+    assert_eq!(map.remove_all(&1), vec!['a', 'b', 'c']);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.count(&1), 0);
+    assert_eq!(map.get_all(&1).next(), None);
+    assert_eq!(map.remove_all(&1), Vec::<char>::new());
+    assert_eq!(map.iter().collect::<Vec<_>>(), vec![(&2, &'x'), (&3, &'y')]);
+}
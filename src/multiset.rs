@@ -0,0 +1,215 @@
+#[cfg(test)]
+mod tests;
+
+use crate::RbTreeMap;
+
+use std::{borrow::Borrow, fmt};
+
+/// A multiset based on a red-black tree, which counts the multiplicity of each element.
+pub struct RbTreeMultiSet<T> {
+    map: RbTreeMap<T, usize>,
+    len: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for RbTreeMultiSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Default for RbTreeMultiSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for RbTreeMultiSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Ord> Extend<T> for RbTreeMultiSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+impl<T> RbTreeMultiSet<T> {
+    /// Creates a new, empty `RbTreeMultiSet`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let set: RbTreeMultiSet<i32> = RbTreeMultiSet::new();
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            map: RbTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements in the set, counted with multiplicity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let mut set = RbTreeMultiSet::new();
+    /// set.insert(1);
+    /// set.insert(1);
+    /// set.insert(2);
+    /// assert_eq!(set.len(), 3);
+    /// ```
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let mut set = RbTreeMultiSet::new();
+    /// assert!(set.is_empty());
+    /// set.insert(1);
+    /// assert!(!set.is_empty());
+    /// ```
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Clears the set, removing all elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let mut set = RbTreeMultiSet::new();
+    /// set.insert(1);
+    /// set.clear();
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Gets an iterator that visits the elements in ascending order, yielding each element as many times as its count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let set: RbTreeMultiSet<_> = [3, 1, 3, 2].into_iter().collect();
+    /// assert!(set.iter().eq([1, 2, 3, 3].iter()));
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.map
+            .iter()
+            .flat_map(|(item, &count)| std::iter::repeat_n(item, count))
+    }
+}
+
+impl<T: Ord> RbTreeMultiSet<T> {
+    /// Adds an element to the set, incrementing its count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let mut set = RbTreeMultiSet::new();
+    /// set.insert(2);
+    /// set.insert(2);
+    /// assert_eq!(set.count(&2), 2);
+    /// ```
+    pub fn insert(&mut self, value: T) {
+        *self.map.entry(value).or_insert(0) += 1;
+        self.len += 1;
+    }
+
+    /// Removes one occurrence of an element from the set. Returns whether the element was present in the set.
+    ///
+    /// The element is pruned from the underlying tree when its count reaches zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let mut set = RbTreeMultiSet::new();
+    /// set.insert(2);
+    /// set.insert(2);
+    /// assert_eq!(set.remove(&2), true);
+    /// assert_eq!(set.remove(&2), true);
+    /// assert_eq!(set.remove(&2), false);
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let count = if let Some(count) = self.map.get_mut(value) {
+            count
+        } else {
+            return false;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.map.remove(value);
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Returns the number of occurrences of an element in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let set: RbTreeMultiSet<_> = [1, 2, 2].into_iter().collect();
+    /// assert_eq!(set.count(&1), 1);
+    /// assert_eq!(set.count(&2), 2);
+    /// assert_eq!(set.count(&3), 0);
+    /// ```
+    pub fn count<Q>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get(value).copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if the set contains at least one occurrence of an element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMultiSet;
+    ///
+    /// let set: RbTreeMultiSet<_> = [1, 2, 2].into_iter().collect();
+    /// assert_eq!(set.contains(&2), true);
+    /// assert_eq!(set.contains(&3), false);
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+}
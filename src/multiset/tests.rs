@@ -0,0 +1,20 @@
+use crate::RbTreeMultiSet;
+
+#[test]
+fn counts() {
+    let mut set = RbTreeMultiSet::new();
+    set.insert(2);
+    set.insert(2);
+    set.insert(2);
+    assert!(set.remove(&2));
+
+    assert_eq!(set.count(&2), 2);
+    assert_eq!(set.len(), 2);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![&2, &2]);
+
+    assert!(set.remove(&2));
+    assert!(set.remove(&2));
+    assert!(!set.remove(&2));
+    assert!(set.is_empty());
+    assert!(!set.contains(&2));
+}
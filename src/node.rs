@@ -1,4 +1,19 @@
-use std::{borrow::Borrow, fmt, marker::PhantomData, ptr::NonNull};
+#[cfg(test)]
+mod tests;
+
+use crate::map::{AllocError, OrderError};
+
+use std::{
+    alloc::{alloc, Layout},
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    marker::PhantomData,
+    ptr::NonNull,
+};
+
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Color {
@@ -33,11 +48,14 @@ impl std::ops::Not for ChildIndex {
     }
 }
 
+/// The bit of `parent_and_color` which is set if the node is black. The nodes are aligned with pointers, so the lowest bit of the parent address is always zero.
+const BLACK_BIT: usize = 1;
+
 struct InnerNode<K, V> {
-    parent: Option<Node<K, V>>,
+    /// The pointer to the parent node tagged with the color on [`BLACK_BIT`], or null tagged if the node is root.
+    parent_and_color: *mut InnerNode<K, V>,
     #[allow(clippy::type_complexity)]
     children: (Option<Node<K, V>>, Option<Node<K, V>>),
-    color: Color,
     key: K,
     value: V,
 }
@@ -45,9 +63,69 @@ struct InnerNode<K, V> {
 pub struct Root<K, V> {
     root: Option<Node<K, V>>,
     len: usize,
+    modifications: ModCount,
     _phantom: PhantomData<(K, V)>,
 }
 
+/// The number of the insertions and removals on a tree, counted only in debug builds. The iterators take a [`ModGuard`] from it to detect the tree mutated through an unsafe path while iterating.
+#[derive(Debug, Default)]
+pub struct ModCount {
+    #[cfg(debug_assertions)]
+    count: AtomicUsize,
+}
+
+impl ModCount {
+    pub const fn new() -> Self {
+        Self {
+            #[cfg(debug_assertions)]
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    fn bump(&mut self) {
+        #[cfg(debug_assertions)]
+        {
+            let count = self.count.get_mut();
+            *count = count.wrapping_add(1);
+        }
+    }
+
+    pub fn guard(&self) -> ModGuard {
+        ModGuard {
+            #[cfg(debug_assertions)]
+            count: &self.count,
+            #[cfg(debug_assertions)]
+            expected: self.count.load(AtomicOrdering::Relaxed),
+        }
+    }
+}
+
+/// A snapshot of [`ModCount`], which asserts that the tree is not modified since taken. It is zero-sized and checks nothing in release builds.
+#[derive(Debug, Clone, Copy)]
+pub struct ModGuard {
+    #[cfg(debug_assertions)]
+    count: *const AtomicUsize,
+    #[cfg(debug_assertions)]
+    expected: usize,
+}
+
+// Safety: The pointer is only used to load the atomic counter.
+unsafe impl Send for ModGuard {}
+// Safety: The pointer is only used to load the atomic counter.
+unsafe impl Sync for ModGuard {}
+
+impl ModGuard {
+    #[inline]
+    pub fn check(&self) {
+        #[cfg(debug_assertions)]
+        {
+            // Safety: The iterator holding the guard borrows the tree, so the counter outlives the guard.
+            let count = unsafe { &*self.count }.load(AtomicOrdering::Relaxed);
+            assert!(count == self.expected, "map mutated during iteration");
+        }
+    }
+}
+
 impl<K, V> fmt::Debug for Root<K, V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Root")
@@ -62,6 +140,7 @@ impl<K, V> Default for Root<K, V> {
         Self {
             root: None,
             len: 0,
+            modifications: ModCount::new(),
             _phantom: PhantomData,
         }
     }
@@ -72,6 +151,7 @@ impl<K, V> Root<K, V> {
         Self {
             root: None,
             len: 0,
+            modifications: ModCount::new(),
             _phantom: PhantomData,
         }
     }
@@ -80,6 +160,11 @@ impl<K, V> Root<K, V> {
         self.len
     }
 
+    /// Returns the bytes of the nodes allocated for the tree, excluding the heap owned by the keys and values.
+    pub const fn node_bytes(&self) -> usize {
+        self.len * std::mem::size_of::<InnerNode<K, V>>()
+    }
+
     pub const fn is_empty(&self) -> bool {
         self.root.is_none()
     }
@@ -88,6 +173,10 @@ impl<K, V> Root<K, V> {
         self.root
     }
 
+    pub fn modifications(&self) -> &ModCount {
+        &self.modifications
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn search<Q>(&self, key: &Q) -> Option<Result<Node<K, V>, (Node<K, V>, ChildIndex)>>
     where
@@ -97,38 +186,484 @@ impl<K, V> Root<K, V> {
         self.root.map(|r| r.search(key))
     }
 
+    #[allow(clippy::type_complexity)]
+    pub fn search_by<F>(&self, cmp: F) -> Option<Result<Node<K, V>, (Node<K, V>, ChildIndex)>>
+    where
+        F: FnMut(&K) -> std::cmp::Ordering,
+    {
+        self.root.map(|r| r.search_by(cmp))
+    }
+
     // Inserts a new node and returns Ok(the node inserted) or Err(old key-value entry).
     pub fn insert_node(&mut self, key: K, value: V) -> Result<Node<K, V>, (K, V)>
     where
         K: Ord,
     {
-        if self.is_empty() {
-            let new_root = Node::new(key, value);
-            self.root = Some(new_root);
-            self.len += 1;
-            return Ok(new_root);
-        }
-        match self.root.unwrap().search(&key) {
-            Ok(found) => {
+        match self.search(&key) {
+            Some(Ok(found)) => {
+                self.modifications.bump();
                 // only replace the value
                 // Safety: The mutable reference is temporary.
                 let old_k = found.replace_key(key);
                 let old_v = std::mem::replace(unsafe { found.value_mut() }, value);
                 Err((old_k, old_v))
             }
-            Err((target, idx)) => {
-                let new_node = Node::new(key, value);
-                debug_assert!(target.child(idx).is_none());
+            Some(Err(position)) => Ok(self.insert_at(Some(position), key, value)),
+            None => Ok(self.insert_at(None, key, value)),
+        }
+    }
+
+    /// Inserts a new node at the vacant `position` found by searching, or as the root if `position` is `None`, and returns the node inserted. The key must be ordered between the neighbors of `position`.
+    pub fn insert_at(
+        &mut self,
+        position: Option<(Node<K, V>, ChildIndex)>,
+        key: K,
+        value: V,
+    ) -> Node<K, V> {
+        let new_node = Node::new(key, value);
+        self.link_at(position, new_node);
+        new_node
+    }
+
+    /// Links `new_node` at the vacant `position` as [`insert_at`](Self::insert_at) does, for the node allocated by the caller.
+    fn link_at(&mut self, position: Option<(Node<K, V>, ChildIndex)>, new_node: Node<K, V>) {
+        self.len += 1;
+        self.modifications.bump();
+        if let Some((target, idx)) = position {
+            debug_assert!(target.child(idx).is_none());
+
+            unsafe {
+                target.set_child(idx, new_node);
+            }
+
+            new_node.balance_after_insert(&mut self.root);
+        } else {
+            debug_assert!(self.is_empty());
+            self.root = Some(new_node);
+        }
+    }
+
+    /// Inserts a new node as [`insert_node`](Self::insert_node) does, but checks that the comparisons against the neighbors of the position found are consistent in both directions. Returns `Err(OrderError)` and leaves the tree unchanged if not.
+    #[allow(clippy::type_complexity)]
+    pub fn insert_node_checked(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<Result<(), (K, V)>, OrderError>
+    where
+        K: Ord,
+    {
+        // `a < b` must be answered consistently by both of `a.cmp(b)` and `b.cmp(a)`.
+        let less = |a: &K, b: &K| a.cmp(b) == Ordering::Less && b.cmp(a) == Ordering::Greater;
+        let position = match self.search_by(|k| k.cmp(&key)) {
+            Some(Ok(found)) => {
+                if key.cmp(found.key()) != Ordering::Equal {
+                    return Err(OrderError);
+                }
+                self.modifications.bump();
+                // Safety: The mutable reference is temporary.
+                let old_k = found.replace_key(key);
+                let old_v = std::mem::replace(unsafe { found.value_mut() }, value);
+                return Ok(Err((old_k, old_v)));
+            }
+            Some(Err(position)) => Some(position),
+            None => None,
+        };
+        if let Some((node, idx)) = position {
+            let (prev, next) = if idx.is_left() {
+                (node.predecessor(), Some(node))
+            } else {
+                (Some(node), node.successor())
+            };
+            if !prev.is_none_or(|prev| less(prev.key(), &key))
+                || !next.is_none_or(|next| less(&key, next.key()))
+            {
+                return Err(OrderError);
+            }
+        }
+        self.insert_at(position, key, value);
+        Ok(Ok(()))
+    }
+
+    /// Inserts a new node as [`insert_node`](Self::insert_node) does, but returns `Err(AllocError)` instead of aborting if the node cannot be allocated. The tree is left unchanged on the error.
+    #[allow(clippy::type_complexity)]
+    pub fn try_insert_node(&mut self, key: K, value: V) -> Result<Result<(), (K, V)>, AllocError>
+    where
+        K: Ord,
+    {
+        let position = match self.search(&key) {
+            Some(Ok(found)) => {
+                self.modifications.bump();
+                // Safety: The mutable reference is temporary.
+                let old_k = found.replace_key(key);
+                let old_v = std::mem::replace(unsafe { found.value_mut() }, value);
+                return Ok(Err((old_k, old_v)));
+            }
+            Some(Err(position)) => Some(position),
+            None => None,
+        };
+        let new_node = Node::try_new(key, value)?;
+        self.link_at(position, new_node);
+        Ok(Ok(()))
+    }
+
+    /// Consumes the tree and builds a new tree of the same shape and colors, whose values are mapped by `f` in ascending key order.
+    pub fn map_values<W, F: FnMut(V) -> W>(mut self, mut f: F) -> Root<K, W> {
+        fn build<K, V, W>(node: Node<K, V>, f: &mut impl FnMut(V) -> W) -> Node<K, W> {
+            let (left, right) = node.children();
+            let color = node.color();
+            let new_left = left.map(|left| build(left, f));
+            // Safety: The children of `node` were read out, and `node` will not be used after.
+            let (key, value) = unsafe { node.deallocate() };
+            let new_node = Node::new(key, f(value));
+            new_node.set_color(color);
+            let new_right = right.map(|right| build(right, f));
+            // Safety: `new_node` was just allocated and has no children yet.
+            unsafe {
+                new_node.set_child(ChildIndex::Left, new_left);
+                new_node.set_child(ChildIndex::Right, new_right);
+            }
+            new_node
+        }
+
+        let root = self.root.take().map(|root| build(root, &mut f));
+        Root {
+            root,
+            len: self.len,
+            modifications: ModCount::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Builds a tree from the entries sorted by key in ascending order, without comparing the keys. It takes `O(n)` time.
+    pub fn from_sorted_iter<I>(iter: I) -> Self
+    where
+        I: ExactSizeIterator<Item = (K, V)>,
+    {
+        Self::from_sorted_nodes(iter.map(|(key, value)| Node::new(key, value)))
+    }
+
+    /// Builds a tree by re-linking the nodes sorted by key in ascending order. Every link and color of the nodes are overwritten, so the nodes can be taken from another tree.
+    ///
+    /// The tree is built from the midpoints of the nodes, so the nodes on the deepest level are colored red if the level is not filled, and the others are colored black.
+    fn from_sorted_nodes<I>(iter: I) -> Self
+    where
+        I: ExactSizeIterator<Item = Node<K, V>>,
+    {
+        fn build<K, V>(
+            iter: &mut impl Iterator<Item = Node<K, V>>,
+            len: usize,
+            depth: u32,
+            red_depth: u32,
+        ) -> Option<Node<K, V>> {
+            if len == 0 {
+                return None;
+            }
+            let left_len = (len - 1) / 2;
+            let left = build(iter, left_len, depth + 1, red_depth);
+            let node = iter.next().expect("the iterator must yield `len` nodes");
+            let right = build(iter, len - 1 - left_len, depth + 1, red_depth);
+            // Safety: The children are re-linked only to `node`, and its parent will be overwritten by the caller.
+            unsafe {
+                node.set_child(ChildIndex::Left, left);
+                node.set_child(ChildIndex::Right, right);
+            }
+            node.set_color(if depth < red_depth {
+                Color::Black
+            } else {
+                Color::Red
+            });
+            Some(node)
+        }
+
+        let mut iter = iter;
+        let len = iter.len();
+        // the levels above `red_depth` are filled completely.
+        let red_depth = (len + 1).ilog2();
+        // Safety: The root is detached from any other node.
+        let root = build(&mut iter, len, 0, red_depth).and_then(|root| unsafe { root.make_root() });
+        Root {
+            root,
+            len,
+            modifications: ModCount::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Re-links the tree from the nodes sorted by key in ascending order as [`from_sorted_nodes`](Self::from_sorted_nodes) builds, keeping the modification count of the tree and bumping it.
+    fn relink_sorted<I>(&mut self, nodes: I)
+    where
+        I: ExactSizeIterator<Item = Node<K, V>>,
+    {
+        let modifications = std::mem::take(&mut self.modifications);
+        *self = Self::from_sorted_nodes(nodes);
+        self.modifications = modifications;
+        self.modifications.bump();
+    }
+
+    /// Merges the entries sorted by key in ascending order into the tree. The nodes in the tree are reused by re-linking, and the pairs on equal keys are replaced.
+    pub fn extend_sorted<I>(&mut self, iter: I)
+    where
+        K: Ord,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let iter = iter.into_iter();
+        let mut nodes: Vec<Node<K, V>> = Vec::with_capacity(self.len + iter.size_hint().0);
+        let mut old = self.root.map(Node::min_child);
+        for (key, value) in iter {
+            debug_assert!(
+                nodes.last().is_none_or(|last| last.key() <= &key),
+                "the entries must be sorted by key in ascending order"
+            );
+            while let Some(node) = old {
+                if node.key() < &key {
+                    nodes.push(node);
+                    old = node.successor();
+                } else {
+                    break;
+                }
+            }
+            if let Some(node) = old.filter(|node| node.key() == &key) {
+                nodes.push(node);
+                old = node.successor();
+            }
+            if let Some(&last) = nodes.last().filter(|last| last.key() == &key) {
+                last.replace_key(key);
+                // Safety: No other reference to the value exists.
+                *unsafe { last.value_mut() } = value;
+            } else {
+                nodes.push(Node::new(key, value));
+            }
+        }
+        while let Some(node) = old {
+            nodes.push(node);
+            old = node.successor();
+        }
+        self.relink_sorted(nodes.into_iter());
+    }
+
+    /// Re-links the nodes into a tree of the minimum height as [`from_sorted_nodes`](Self::from_sorted_nodes) builds, without comparing the keys. It takes `O(n)` time.
+    pub fn rebuild(&mut self) {
+        let mut nodes = Vec::with_capacity(self.len);
+        let mut current = self.root.map(Node::min_child);
+        while let Some(node) = current {
+            nodes.push(node);
+            current = node.successor();
+        }
+        self.relink_sorted(nodes.into_iter());
+    }
+
+    /// Removes the nodes whose keys are in `keys`, which must be sorted in ascending order, by walking the tree and `keys` together. The remaining nodes are re-linked as [`rebuild`](Self::rebuild) does, so it takes `O(n + m)` time. Returns the number of the nodes removed.
+    pub fn remove_sorted<Q>(&mut self, keys: &[Q]) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let mut kept = Vec::with_capacity(self.len);
+        let mut removed = vec![];
+        let mut keys = keys.iter().peekable();
+        let mut current = self.root.map(Node::min_child);
+        while let Some(node) = current {
+            while keys.next_if(|key| *key < node.key()).is_some() {}
+            if keys.next_if(|key| *key == node.key()).is_some() {
+                removed.push(node);
+            } else {
+                kept.push(node);
+            }
+            current = node.successor();
+        }
+        let count = removed.len();
+        // the links are used by `successor` until the walk ends, and the tree must not refer the removed nodes if dropping an entry panics, so they are deallocated after re-linking.
+        self.relink_sorted(kept.into_iter());
+        for node in removed {
+            // Safety: The node was unlinked from the tree by re-linking the kept nodes above.
+            drop(unsafe { node.deallocate() });
+        }
+        count
+    }
 
+    /// Splits off the nodes whose entries satisfy `pred` into a new tree, by walking the tree once in ascending order. The both trees are re-linked as [`rebuild`](Self::rebuild) does, so it takes `O(n)` time. The tree is left unchanged if `pred` panics.
+    pub fn partition<F>(&mut self, mut pred: F) -> Self
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut kept = Vec::with_capacity(self.len);
+        let mut taken = vec![];
+        let mut current = self.root.map(Node::min_child);
+        while let Some(node) = current {
+            // Safety: The mutable reference will not live longer than the call of `pred`.
+            let (key, value) = unsafe { node.key_value_mut() };
+            if pred(key, value) {
+                taken.push(node);
+            } else {
+                kept.push(node);
+            }
+            current = node.successor();
+        }
+        self.relink_sorted(kept.into_iter());
+        Self::from_sorted_nodes(taken.into_iter())
+    }
+
+    /// Overwrites the entries of the tree with the clones of the entries of `source`, reusing the nodes of the tree by cloning into them in ascending order. The lacking nodes are allocated and the surplus nodes are deallocated, and then the nodes are re-linked as [`rebuild`](Self::rebuild) does. It takes `O(n + m)` time.
+    pub fn clone_from_root(&mut self, source: &Self)
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut nodes = Vec::with_capacity(source.len);
+        let mut target = self.root.map(Node::min_child);
+        let mut from = source.root.map(Node::min_child);
+        while let Some(src) = from {
+            // Safety: The references are temporary, and `src` is in the other tree.
+            let (key, value) = unsafe { src.key_value() };
+            if let Some(node) = target {
+                // Safety: The links are not changed, and the whole keys are overwritten in order of `source`.
                 unsafe {
-                    target.set_child(idx, new_node);
+                    node.key_mut().clone_from(key);
+                    node.value_mut().clone_from(value);
                 }
+                nodes.push(node);
+                target = node.successor();
+            } else {
+                nodes.push(Node::new(key.clone(), value.clone()));
+            }
+            from = src.successor();
+        }
+        let mut surplus = vec![];
+        while let Some(node) = target {
+            surplus.push(node);
+            target = node.successor();
+        }
+        self.relink_sorted(nodes.into_iter());
+        for node in surplus {
+            // Safety: The node is no longer linked from the tree re-linked above.
+            drop(unsafe { node.deallocate() });
+        }
+    }
+
+    /// Counts the red nodes and the black nodes in one in-order walk.
+    pub fn color_histogram(&self) -> (usize, usize) {
+        let (mut red, mut black) = (0, 0);
+        let mut current = self.root.map(Node::min_child);
+        while let Some(node) = current {
+            if node.is_red() {
+                red += 1;
+            } else {
+                black += 1;
+            }
+            current = node.successor();
+        }
+        debug_assert_eq!(
+            red + black,
+            self.len,
+            "the length must equal the walked nodes"
+        );
+        (red, black)
+    }
+
+    /// Joins `self`, a new node of `key` and `value`, and `right` into one tree. All of the keys in `self` must be less than `key`, and all of the keys in `right` must be greater than `key`.
+    ///
+    /// The new node is attached at the spine of the taller tree where the black height matches the shorter one, so it takes `O(log n)` time.
+    pub fn join(mut self, key: K, value: V, mut right: Self) -> Self {
+        let len = self.len + 1 + right.len;
+        let root = join_nodes(self.root.take(), Node::new(key, value), right.root.take());
+        Root {
+            root,
+            len,
+            modifications: ModCount::new(),
+            _phantom: PhantomData,
+        }
+    }
 
-                new_node.balance_after_insert(&mut self.root);
-                self.len += 1;
-                Ok(new_node)
+    /// Splits the tree into the entries less than `key`, the entry equal to `key` if exists, and the entries greater than `key`.
+    ///
+    /// The nodes on the path to `key` are re-linked by [`Root::join`] so it takes `O(log n)` time, but counting the lengths of the new trees takes `O(min(k, n - k))` time for `k` entries less than `key`.
+    pub fn split<Q>(mut self, key: &Q) -> (Self, Option<(K, V)>, Self)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        /// Detaches `node` from its parent and children, and returns the children.
+        ///
+        /// # Safety
+        ///
+        /// The parent of `node` must be detached already.
+        #[allow(clippy::type_complexity)]
+        unsafe fn detach<K, V>(node: Node<K, V>) -> (Option<Node<K, V>>, Option<Node<K, V>>) {
+            let left = node.left().map(|_| node.clear_child(ChildIndex::Left));
+            let right = node.right().map(|_| node.clear_child(ChildIndex::Right));
+            node.set_parent(None);
+            node.set_color(Color::Red);
+            (left, right)
+        }
+
+        let total = self.len;
+        let mut current = self.root.take();
+        // the nodes on the path with the opposite subtree, from the root to the bottom.
+        let mut path = vec![];
+        let mut found = None;
+        while let Some(node) = current {
+            // Safety: `node` is the root or the parent was detached on the previous step.
+            let (left, right) = unsafe { detach(node) };
+            match key.cmp(node.key()) {
+                Ordering::Less => {
+                    path.push((node, ChildIndex::Left, right));
+                    current = left;
+                }
+                Ordering::Greater => {
+                    path.push((node, ChildIndex::Right, left));
+                    current = right;
+                }
+                Ordering::Equal => {
+                    found = Some((node, left, right));
+                    break;
+                }
+            }
+        }
+        let (mut left, found, mut right) = match found {
+            // Safety: `node` was detached from the tree.
+            Some((node, left, right)) => (left, Some(unsafe { node.deallocate() }), right),
+            None => (None, None, None),
+        };
+        while let Some((node, went, opposite)) = path.pop() {
+            if went.is_left() {
+                right = join_nodes(right, node, opposite);
+            } else {
+                left = join_nodes(opposite, node, left);
             }
         }
+
+        // count the smaller tree only, by walking the both trees in lockstep.
+        let rest = total - found.is_some() as usize;
+        let mut left_walker = left.map(Node::min_child);
+        let mut right_walker = right.map(Node::min_child);
+        let mut count = 0;
+        let (left_len, right_len) = loop {
+            match (left_walker, right_walker) {
+                (None, _) => break (count, rest - count),
+                (_, None) => break (rest - count, count),
+                (Some(l), Some(r)) => {
+                    left_walker = l.successor();
+                    right_walker = r.successor();
+                    count += 1;
+                }
+            }
+        };
+        (
+            Root {
+                root: left,
+                len: left_len,
+                modifications: ModCount::new(),
+                _phantom: PhantomData,
+            },
+            found,
+            Root {
+                root: right,
+                len: right_len,
+                modifications: ModCount::new(),
+                _phantom: PhantomData,
+            },
+        )
     }
 
     pub fn remove_min(&mut self) -> Option<(K, V)> {
@@ -153,8 +688,9 @@ impl<K, V> Root<K, V> {
         self.delete_node(to_remove)
     }
 
-    fn delete_node(&mut self, to_remove: Node<K, V>) -> Option<(K, V)> {
+    pub fn delete_node(&mut self, to_remove: Node<K, V>) -> Option<(K, V)> {
         self.len -= 1;
+        self.modifications.bump();
 
         if Some(to_remove) == self.root && to_remove.children() == (None, None) {
             // Safety: There is only `to_remove` in the tree, so just deallocate it.
@@ -163,9 +699,8 @@ impl<K, V> Root<K, V> {
                 return Some(to_remove.deallocate());
             }
         }
-        // `to_remove` is not the root, has its parent.
         if let (Some(left), Some(right)) = to_remove.children() {
-            // `to_remove` is needed to replace with the maximum node in the left.
+            // `to_remove` is needed to swap with the maximum node in the left, both of positions and colors. Then `to_remove` has one child at most, so it can be removed as below.
             let max_in_left = left.max_child();
             let redundant = max_in_left.left();
             //  parent
@@ -188,25 +723,30 @@ impl<K, V> Root<K, V> {
             //  /  \
             //     ...
             //       \
-            //     redundant
+            //    to_remove
+            //      /
+            // redundant
             unsafe {
                 let to_remove_color = to_remove.color();
                 to_remove.set_color(max_in_left.color());
                 max_in_left.set_color(to_remove_color);
 
-                let (idx, parent) = max_in_left.index_and_parent().unwrap();
-                parent.set_child(idx, redundant);
-                if let Some((idx, parent)) = to_remove.index_and_parent() {
-                    parent.set_child(idx, max_in_left);
+                let parent = to_remove.index_and_parent();
+                if max_in_left == left {
+                    max_in_left.set_child(ChildIndex::Left, to_remove);
                 } else {
-                    self.root = Some(max_in_left);
-                }
-                if max_in_left != left {
+                    let (idx, max_parent) = max_in_left.index_and_parent().unwrap();
+                    max_parent.set_child(idx, to_remove);
                     max_in_left.set_child(ChildIndex::Left, left);
                 }
+                to_remove.clear_child(ChildIndex::Right);
+                to_remove.set_child(ChildIndex::Left, redundant);
                 max_in_left.set_child(ChildIndex::Right, right);
-
-                return Some(to_remove.deallocate());
+                if let Some((idx, parent)) = parent {
+                    parent.set_child(idx, max_in_left);
+                } else {
+                    self.root = max_in_left.make_root();
+                }
             }
         }
 
@@ -254,6 +794,77 @@ impl<K, V> Root<K, V> {
     }
 }
 
+/// Joins `left`, `mid` and `right` into one tree and returns its root. `mid` must be detached from any tree, and the keys must be ascending in this order.
+fn join_nodes<K, V>(
+    left: Option<Node<K, V>>,
+    mid: Node<K, V>,
+    right: Option<Node<K, V>>,
+) -> Option<Node<K, V>> {
+    // the roots are colored black, so that the black heights are measured in the same way.
+    for root in [left, right].into_iter().flatten() {
+        root.set_color(Color::Black);
+    }
+    let left_height = black_height(left);
+    let right_height = black_height(right);
+
+    if left_height == right_height {
+        // Safety: `mid` is detached, and the roots are detached from the trees.
+        unsafe {
+            mid.set_child(ChildIndex::Left, left);
+            mid.set_child(ChildIndex::Right, right);
+            mid.set_color(Color::Black);
+            return mid.make_root();
+        }
+    }
+    // descend the spine of the taller tree toward the shorter one.
+    let (taller, taller_height, shorter, shorter_height, dir) = if left_height > right_height {
+        (left, left_height, right, right_height, ChildIndex::Right)
+    } else {
+        (right, right_height, left, left_height, ChildIndex::Left)
+    };
+    let mut root = taller;
+    let mut parent = taller.unwrap();
+    let mut height = taller_height - 1;
+    let mut current = parent.child(dir);
+    while let Some(curr) = current {
+        if curr.is_black() && height == shorter_height {
+            break;
+        }
+        height -= curr.is_black() as usize;
+        parent = curr;
+        current = curr.child(dir);
+    }
+    //   parent
+    //      \
+    //    current  [shorter]
+    // ↓
+    //   parent
+    //      \
+    //     (mid)
+    //     /   \
+    // current [shorter]
+    // Safety: `mid` is detached, and `current` is re-linked to it.
+    unsafe {
+        parent.set_child(dir, mid);
+        mid.set_child(!dir, current);
+        mid.set_child(dir, shorter);
+    }
+    mid.set_color(Color::Red);
+    mid.balance_after_insert(&mut root);
+    root
+}
+
+/// Returns the number of black nodes on a path from `node` to a leaf.
+fn black_height<K, V>(node: Option<Node<K, V>>) -> usize {
+    let mut height = 0;
+    let mut current = node;
+    while let Some(curr) = current {
+        height += curr.is_black() as usize;
+        current = curr.left();
+    }
+    height
+}
+
 pub struct Node<K, V>(NonNull<InnerNode<K, V>>);
 
 impl<K, V> fmt::Debug for Node<K, V> {
@@ -264,7 +875,7 @@ impl<K, V> fmt::Debug for Node<K, V> {
 
 impl<K, V> Clone for Node<K, V> {
     fn clone(&self) -> Self {
-        Self(self.0)
+        *self
     }
 }
 
@@ -286,9 +897,8 @@ impl<K, V> Node<K, V> {
     pub fn new(key: K, value: V) -> Self {
         let leaked = Box::leak(
             InnerNode {
-                parent: None,
+                parent_and_color: std::ptr::null_mut(),
                 children: (None, None),
-                color: Color::Red,
                 key,
                 value,
             }
@@ -297,15 +907,36 @@ impl<K, V> Node<K, V> {
         Node(leaked.into())
     }
 
+    /// Constructs a new node as [`new`](Self::new) does, but returns `Err(AllocError)` instead of aborting if the global allocator fails.
+    pub fn try_new(key: K, value: V) -> Result<Self, AllocError> {
+        let layout = Layout::new::<InnerNode<K, V>>();
+        // Safety: `InnerNode` is never zero-sized because it holds the pointers.
+        let ptr = unsafe { alloc(layout) }.cast::<InnerNode<K, V>>();
+        let ptr = if let Some(ptr) = NonNull::new(ptr) {
+            ptr
+        } else {
+            return Err(AllocError);
+        };
+        // Safety: `ptr` was allocated with the layout of `InnerNode`, which is the same as `Box` uses, so it can be freed with [`deallocate`].
+        unsafe {
+            ptr.as_ptr().write(InnerNode {
+                parent_and_color: std::ptr::null_mut(),
+                children: (None, None),
+                key,
+                value,
+            });
+        }
+        Ok(Node(ptr))
+    }
+
     /// Deallocates the node and extract its key-value pair. You must not use the `NodeRef` after calling this method.
     ///
     /// # Safety
     ///
     /// This method must be called only once.
     pub unsafe fn deallocate(mut self) -> (K, V) {
-        let this = self.0.as_mut();
-        this.parent = None;
-        this.children = (None, None);
+        self.set_parent(None);
+        self.0.as_mut().children = (None, None);
         let this = Box::from_raw(self.0.as_ptr());
         (this.key, this.value)
     }
@@ -315,8 +946,8 @@ impl<K, V> Node<K, V> {
     /// # Safety
     ///
     /// You must set the node into `root` of the tree.
-    pub unsafe fn make_root(mut self) -> Option<Self> {
-        self.0.as_mut().parent = None;
+    pub unsafe fn make_root(self) -> Option<Self> {
+        self.set_parent(None);
         Some(self)
     }
 
@@ -376,6 +1007,18 @@ impl<K, V> Node<K, V> {
         &self.0.as_ref().value
     }
 
+    /// Returns the mutable reference of key from the node.
+    ///
+    /// # Safety
+    ///
+    /// The other reference of its key must not exist, and the order of the keys in the tree must be restored before searching the tree.
+    pub unsafe fn key_mut<'a>(mut self) -> &'a mut K
+    where
+        V: 'a,
+    {
+        &mut self.0.as_mut().key
+    }
+
     /// Returns the mutable reference of value pair from the node.
     ///
     /// # Safety
@@ -390,8 +1033,7 @@ impl<K, V> Node<K, V> {
 
     /// Returns whether the node colored as red.
     pub fn is_red(self) -> bool {
-        // Safety: Only reading the color.
-        unsafe { self.0.as_ref() }.color == Color::Red
+        self.color() == Color::Red
     }
 
     /// Returns whether the node colored as black.
@@ -401,20 +1043,42 @@ impl<K, V> Node<K, V> {
 
     /// Returns the color of the node.
     pub fn color(self) -> Color {
-        // Safety: Only reading the color.
-        unsafe { self.0.as_ref() }.color
+        // Safety: Only reading the tagged pointer.
+        let tagged = unsafe { self.0.as_ref() }.parent_and_color;
+        if tagged.addr() & BLACK_BIT == 0 {
+            Color::Red
+        } else {
+            Color::Black
+        }
     }
 
     /// Colors the node with [`Color`].
     pub fn set_color(mut self, color: Color) {
-        // Safety: Only writing the color.
-        unsafe { self.0.as_mut() }.color = color;
+        // Safety: Only writing the tag of the pointer.
+        let tagged = &mut unsafe { self.0.as_mut() }.parent_and_color;
+        *tagged = tagged.map_addr(|addr| match color {
+            Color::Red => addr & !BLACK_BIT,
+            Color::Black => addr | BLACK_BIT,
+        });
     }
 
     /// Returns the parent node of the node.
     pub fn parent(self) -> Option<Self> {
         // Safety: Using the parent node will be guaranteed on caller.
-        unsafe { self.0.as_ref() }.parent
+        let tagged = unsafe { self.0.as_ref() }.parent_and_color;
+        NonNull::new(tagged.map_addr(|addr| addr & !BLACK_BIT)).map(Node)
+    }
+
+    /// Links the node to `parent` keeping its color.
+    ///
+    /// # Safety
+    ///
+    /// The caller must keep the link from `parent` to the node consistent, or make the node root if `parent` is `None`.
+    unsafe fn set_parent(mut self, parent: Option<Self>) {
+        let tagged = &mut self.0.as_mut().parent_and_color;
+        let black = tagged.addr() & BLACK_BIT;
+        let parent = parent.map_or(std::ptr::null_mut(), |parent| parent.0.as_ptr());
+        *tagged = parent.map_addr(|addr| addr | black);
     }
 
     /// Returns the grandparent node of the node.
@@ -482,8 +1146,8 @@ impl<K, V> Node<K, V> {
     /// The child edge on `idx` must be occupied.
     pub unsafe fn clear_child(mut self, idx: ChildIndex) -> Self {
         let this = self.0.as_mut();
-        if let Some(mut child) = self.child(idx) {
-            child.0.as_mut().parent = None;
+        if let Some(child) = self.child(idx) {
+            child.set_parent(None);
         }
         let child = match idx {
             ChildIndex::Left => &mut this.children.0,
@@ -502,8 +1166,8 @@ impl<K, V> Node<K, V> {
         let new_child = new_child.into();
         debug_assert_ne!(Some(self), new_child);
         let this = self.0.as_mut();
-        if let Some(mut new_child) = new_child {
-            new_child.0.as_mut().parent = Some(self);
+        if let Some(new_child) = new_child {
+            new_child.set_parent(Some(self));
         }
         match idx {
             ChildIndex::Left => std::mem::replace(&mut this.children.0, new_child),
@@ -527,16 +1191,54 @@ impl<K, V> Node<K, V> {
         self.index_on_parent().zip(self.parent())
     }
 
-    pub fn search<Q>(mut self, key: &Q) -> Result<Self, (Self, ChildIndex)>
+    pub fn search<Q>(self, key: &Q) -> Result<Self, (Self, ChildIndex)>
     where
         K: Ord + Borrow<Q>,
         Q: Ord + ?Sized,
+    {
+        let mut current = self;
+        // the nodes which bound the rest of descent, to detect an inconsistent `Ord` in debug builds.
+        #[cfg(debug_assertions)]
+        let (mut lower, mut upper): (Option<Self>, Option<Self>) = (None, None);
+        loop {
+            let ord = current.key::<Q>().cmp(key);
+            #[cfg(debug_assertions)]
+            {
+                assert_eq!(
+                    ord,
+                    key.cmp(current.key()).reverse(),
+                    "inconsistent Ord implementation: the comparison is not antisymmetric"
+                );
+                assert!(
+                    lower.is_none_or(|lower| lower.key::<Q>() < current.key())
+                        && upper.is_none_or(|upper| current.key::<Q>() < upper.key()),
+                    "inconsistent Ord implementation: the keys are out of order in the tree"
+                );
+                match ord {
+                    Ordering::Less => lower = Some(current),
+                    Ordering::Greater => upper = Some(current),
+                    Ordering::Equal => {}
+                }
+            }
+            let idx = match ord {
+                Ordering::Greater => ChildIndex::Left,
+                Ordering::Equal => return Ok(current),
+                Ordering::Less => ChildIndex::Right,
+            };
+            current = current.child(idx).ok_or((current, idx))?;
+        }
+    }
+
+    /// Searches the node by `cmp`, which returns the ordering of the key against the target like [`slice::binary_search_by`].
+    pub fn search_by<F>(mut self, mut cmp: F) -> Result<Self, (Self, ChildIndex)>
+    where
+        F: FnMut(&K) -> std::cmp::Ordering,
     {
         loop {
-            let idx = match key.cmp(self.key()) {
-                std::cmp::Ordering::Less => ChildIndex::Left,
+            let idx = match cmp(self.key()) {
+                std::cmp::Ordering::Greater => ChildIndex::Left,
                 std::cmp::Ordering::Equal => return Ok(self),
-                std::cmp::Ordering::Greater => ChildIndex::Right,
+                std::cmp::Ordering::Less => ChildIndex::Right,
             };
             self = self.child(idx).ok_or((self, idx))?;
         }
@@ -557,4 +1259,34 @@ impl<K, V> Node<K, V> {
         }
         current
     }
+
+    /// Returns the next node of the node in ascending order.
+    pub fn successor(self) -> Option<Self> {
+        if let Some(right) = self.right() {
+            return Some(right.min_child());
+        }
+        let mut current = self;
+        loop {
+            let (idx, parent) = current.index_and_parent()?;
+            if idx.is_left() {
+                return Some(parent);
+            }
+            current = parent;
+        }
+    }
+
+    /// Returns the previous node of the node in ascending order.
+    pub fn predecessor(self) -> Option<Self> {
+        if let Some(left) = self.left() {
+            return Some(left.max_child());
+        }
+        let mut current = self;
+        loop {
+            let (idx, parent) = current.index_and_parent()?;
+            if idx.is_right() {
+                return Some(parent);
+            }
+            current = parent;
+        }
+    }
 }
@@ -0,0 +1,195 @@
+use super::{ChildIndex, Color, InnerNode, Node, Root};
+use crate::{tests::rng, RbTreeMap};
+
+#[test]
+fn color_is_packed_into_parent() {
+    assert_eq!(
+        std::mem::size_of::<InnerNode<u64, u64>>(),
+        5 * std::mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn color_and_parent_are_independent() {
+    let parent = Node::new(2, ());
+    let child = Node::new(1, ());
+    assert!(parent.is_red());
+    assert!(child.parent().is_none());
+
+    // Safety: The nodes are linked consistently, and deallocated at last.
+    unsafe {
+        parent.set_child(ChildIndex::Left, child);
+        assert_eq!(child.parent(), Some(parent));
+        assert!(child.is_red());
+
+        child.set_color(Color::Black);
+        assert_eq!(child.parent(), Some(parent));
+        assert!(child.is_black());
+
+        parent.set_color(Color::Black);
+        parent.clear_child(ChildIndex::Left);
+        assert!(child.parent().is_none());
+        assert!(child.is_black());
+
+        parent.set_child(ChildIndex::Right, child);
+        assert_eq!(child.parent(), Some(parent));
+        assert!(child.is_black());
+        child.set_color(Color::Red);
+        assert_eq!(child.parent(), Some(parent));
+        assert!(child.is_red());
+
+        parent.clear_child(ChildIndex::Right);
+        child.deallocate();
+        parent.deallocate();
+    }
+}
+
+#[test]
+fn colors_survive_rebalancing() {
+    fn check(map: &RbTreeMap<u32, ()>) {
+        let mut stack: Vec<_> = map.root.inner().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            for child in [node.left(), node.right()].into_iter().flatten() {
+                assert_eq!(child.parent(), Some(node));
+                assert!(!(node.is_red() && child.is_red()));
+                stack.push(child);
+            }
+        }
+    }
+
+    let mut map = RbTreeMap::new();
+    for i in 0..256 {
+        map.insert(i * 7 % 256, ());
+        check(&map);
+    }
+    for i in 0..128 {
+        map.remove(&(i * 11 % 256));
+        check(&map);
+    }
+    assert_eq!(map.len(), 128);
+}
+
+#[test]
+fn from_sorted_nodes_is_balanced() {
+    for len in 0..300 {
+        let mut root = Root::new();
+        root.extend_sorted((0..len).map(|k| (k, k * 2)));
+        assert_eq!(root.len(), len);
+        let map = RbTreeMap { root };
+        assert!(map
+            .iter()
+            .map(|(&k, &v)| (k, v))
+            .eq((0..len).map(|k| (k, k * 2))));
+        if let Some(root) = map.root.inner() {
+            assert!(root.parent().is_none());
+            root.assert_tree(&Some(root));
+        }
+    }
+}
+
+/// Runs `f` on a thread with a small stack, so any recursion as deep as the tree overflows.
+fn on_small_stack(f: impl FnOnce() + Send + 'static) {
+    std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+/// Links the nodes of `0..len` into a chain leaning to `idx`, which is a valid search tree but not balanced at all.
+fn chain(len: u32, idx: ChildIndex) -> RbTreeMap<u32, u32> {
+    let mut keys: Vec<u32> = (0..len).collect();
+    if idx.is_right() {
+        keys.reverse();
+    }
+    let mut top: Option<Node<u32, u32>> = None;
+    for key in keys {
+        let node = Node::new(key, key);
+        if let Some(top) = top {
+            // Safety: `top` is the root of the chain so far and has no parent.
+            unsafe { node.set_child(idx, top) };
+        }
+        top = Some(node);
+    }
+    RbTreeMap {
+        root: Root {
+            root: top,
+            len: len as usize,
+            modifications: super::ModCount::new(),
+            _phantom: std::marker::PhantomData,
+        },
+    }
+}
+
+#[test]
+fn drop_large_tree_without_recursion() {
+    const LEN: u32 = 1_000_000;
+
+    on_small_stack(|| {
+        let mut map = RbTreeMap::new();
+        map.extend_sorted((0..LEN).map(|k| (k, k)));
+        assert_eq!(map.len(), LEN as usize);
+        drop(map);
+    });
+    for idx in [ChildIndex::Left, ChildIndex::Right] {
+        on_small_stack(move || {
+            let map = chain(LEN, idx);
+            assert_eq!(map.first(), Some((&0, &0)));
+            assert_eq!(map.last(), Some((&(LEN - 1), &(LEN - 1))));
+            drop(map);
+        });
+        on_small_stack(move || {
+            // drops the rest after cutting from the both sides
+            let mut iter = chain(LEN, idx).into_iter();
+            assert_eq!(iter.next(), Some((0, 0)));
+            assert_eq!(iter.next_back(), Some((LEN - 1, LEN - 1)));
+            drop(iter);
+        });
+    }
+}
+
+#[test]
+fn rebuild_minimizes_height() {
+    fn height<K, V>(root: Option<Node<K, V>>) -> usize {
+        let mut max = 0;
+        let mut stack: Vec<_> = root.into_iter().map(|r| (1, r)).collect();
+        while let Some((depth, node)) = stack.pop() {
+            max = max.max(depth);
+            stack.extend(
+                [node.left(), node.right()]
+                    .into_iter()
+                    .flatten()
+                    .map(|c| (depth + 1, c)),
+            );
+        }
+        max
+    }
+
+    let mut map = RbTreeMap::new();
+    let mut rand = rng(7);
+    for _ in 0..2000 {
+        let seed = rand();
+        map.insert((seed >> 8) % 4096, seed);
+    }
+    map.retain(|&k, _| k % 5 == 0);
+    let expected: Vec<(u32, u32)> = map.iter().map(|(&k, &v)| (k, v)).collect();
+
+    map.rebuild();
+    assert_eq!(map.len(), expected.len());
+    assert!(map
+        .iter()
+        .map(|(&k, &v)| (k, v))
+        .eq(expected.iter().copied()));
+
+    let root = map.root.inner().unwrap();
+    root.assert_tree(&Some(root));
+    let height = height(Some(root));
+    let black_height = super::black_height(Some(root));
+    assert_eq!(height, (map.len() + 1).next_power_of_two().ilog2() as usize);
+    assert!(height <= black_height * 2 + 1);
+
+    let mut empty = RbTreeMap::<u32, u32>::new();
+    empty.rebuild();
+    assert!(empty.is_empty());
+}
@@ -0,0 +1,149 @@
+//! Wrappers to make the types ordered totally, so that they can be used as keys of the trees, and the traits of the ordered keys.
+
+#[cfg(test)]
+mod tests;
+
+use std::{cmp::Ordering, fmt, hash};
+
+macro_rules! total_float {
+    ($name:ident, $float:ty) => {
+        #[doc = concat!("A wrapper of [`", stringify!($float), "`] ordered by [`", stringify!($float), "::total_cmp`], so that `NaN`s are also ordered consistently.")]
+        ///
+        /// Negative `NaN`s are less than all of the other values, and positive `NaN`s are greater than them. `-0.0` is less than `0.0`.
+        #[derive(Clone, Copy, Default)]
+        #[repr(transparent)]
+        pub struct $name(pub $float);
+
+        impl $name {
+            #[doc = concat!("Converts a reference of [`", stringify!($float), "`] into the wrapper's one, to look up a key by the inner value.")]
+            ///
+            /// # Examples
+            ///
+            /// ```
+            #[doc = concat!("use rb_tree::{RbTreeMap, ord::", stringify!($name), "};")]
+            ///
+            /// let mut map = RbTreeMap::new();
+            #[doc = concat!("map.insert(", stringify!($name), "(1.5), \"a\");")]
+            ///
+            #[doc = concat!("let key: &", stringify!($float), " = &1.5;")]
+            #[doc = concat!("assert_eq!(map.get(", stringify!($name), "::from_ref(key)), Some(&\"a\"));")]
+            /// ```
+            pub fn from_ref(value: &$float) -> &Self {
+                // Safety: `Self` is `repr(transparent)` over the float.
+                unsafe { &*(value as *const $float as *const Self) }
+            }
+
+            /// Returns the inner value.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            #[doc = concat!("use rb_tree::ord::", stringify!($name), ";")]
+            ///
+            #[doc = concat!("assert_eq!(", stringify!($name), "(2.0).get(), 2.0);")]
+            /// ```
+            pub const fn get(self) -> $float {
+                self.0
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other).is_eq()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        /// The bits are hashed, because they are equal if and only if the values are equal in the total order.
+        impl hash::Hash for $name {
+            fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state);
+            }
+        }
+
+        impl From<$float> for $name {
+            fn from(value: $float) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $float {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl AsRef<$float> for $name {
+            fn as_ref(&self) -> &$float {
+                &self.0
+            }
+        }
+    };
+}
+
+total_float!(TotalF64, f64);
+total_float!(TotalF32, f32);
+
+/// A type whose values are discrete, so that each value has the next one in the order. It is a stable alternative of the unstable [`std::iter::Step`], used to find consecutive keys.
+pub trait Step: Ord + Sized {
+    /// Returns the least value greater than `self`, or `None` if `self` is the maximum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::ord::Step;
+    ///
+    /// assert_eq!(3u8.forward(), Some(4));
+    /// assert_eq!(u8::MAX.forward(), None);
+    /// ```
+    fn forward(&self) -> Option<Self>;
+}
+
+macro_rules! step_int {
+    ($($int:ty),*) => {
+        $(
+            impl Step for $int {
+                fn forward(&self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+            }
+        )*
+    };
+}
+
+step_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// The surrogate code points are skipped, so `'\u{d7ff}'` is followed by `'\u{e000}'`.
+impl Step for char {
+    fn forward(&self) -> Option<Self> {
+        match *self {
+            '\u{d7ff}' => Some('\u{e000}'),
+            c => char::from_u32(c as u32 + 1),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use super::{Step, TotalF32, TotalF64};
+use crate::RbTreeMap;
+
+#[test]
+fn nan_is_ordered() {
+    let values = [
+        -f64::NAN,
+        f64::NEG_INFINITY,
+        -1.0,
+        -0.0,
+        0.0,
+        1.0,
+        f64::INFINITY,
+        f64::NAN,
+    ];
+    let map: RbTreeMap<TotalF64, usize> = values
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, &v)| (TotalF64(v), i))
+        .collect();
+    assert_eq!(map.len(), values.len());
+    assert!(map.values().copied().eq(0..values.len()));
+
+    assert_eq!(TotalF64(f64::NAN), TotalF64(f64::NAN));
+    assert_ne!(TotalF64(0.0), TotalF64(-0.0));
+    assert!(TotalF32(f32::NAN) > TotalF32(f32::INFINITY));
+}
+
+#[test]
+fn lookup_by_inner_float() {
+    let mut map = RbTreeMap::new();
+    map.insert(TotalF64::from(f64::NAN), "nan");
+    map.insert(TotalF64::from(0.5), "half");
+
+    let nan = f64::NAN;
+    assert_eq!(map.get(TotalF64::from_ref(&nan)), Some(&"nan"));
+    assert_eq!(map.get(TotalF64::from_ref(&0.5)), Some(&"half"));
+    assert_eq!(map.get(TotalF64::from_ref(&-0.5)), None);
+    let first: f64 = map.first().map(|(&k, _)| k.into()).unwrap();
+    assert_eq!(first, 0.5);
+}
+
+#[test]
+fn step_forward_at_bounds() {
+    assert_eq!(0i8.forward(), Some(1));
+    assert_eq!((-1i64).forward(), Some(0));
+    assert_eq!(i32::MAX.forward(), None);
+    assert_eq!(u128::MAX.forward(), None);
+
+    assert_eq!('a'.forward(), Some('b'));
+    assert_eq!('\u{d7ff}'.forward(), Some('\u{e000}'));
+    assert_eq!(char::MAX.forward(), None);
+}
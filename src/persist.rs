@@ -0,0 +1,149 @@
+//! A versioned format to persist [`RbTreeMap`] with any serde data format, and restore it in `O(n)` time.
+//!
+//! The map is written as a tuple of the format version `u8`, the length `u64`, and the sequence of the entries in ascending order of key.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{node::Root, RbTreeMap};
+
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, DeserializeSeed, SeqAccess, Visitor},
+    ser::{SerializeSeq, SerializeTuple},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The version of the persisted format. It must be changed when the format changes.
+pub const FORMAT_VERSION: u8 = 1;
+
+impl<K: Serialize, V: Serialize> RbTreeMap<K, V> {
+    /// Writes the map into `serializer` in the versioned format, which can be read by [`RbTreeMap::restore`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map: RbTreeMap<_, _> = (0..3).map(|i| (i, i * 10)).collect();
+    ///
+    /// let mut bytes = vec![];
+    /// map.persist(&mut serde_json::Serializer::new(&mut bytes)).unwrap();
+    /// assert_eq!(bytes, b"[1,3,[[0,0],[1,10],[2,20]]]");
+    /// ```
+    pub fn persist<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&FORMAT_VERSION)?;
+        tuple.serialize_element(&(self.len() as u64))?;
+        tuple.serialize_element(&Entries(self))?;
+        tuple.end()
+    }
+}
+
+impl<'de, K: Ord + Deserialize<'de>, V: Deserialize<'de>> RbTreeMap<K, V> {
+    /// Reads the map written by [`RbTreeMap::persist`] from `deserializer`. The tree is built from the sorted entries at once, so it takes `O(n)` time.
+    ///
+    /// It returns an error if the format version does not match [`FORMAT_VERSION`], the number of the entries does not match the length, or the keys are not in strictly ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeMap;
+    ///
+    /// let map = RbTreeMap::<i32, i32>::restore(&mut serde_json::Deserializer::from_str(
+    ///     "[1,2,[[0,0],[1,10]]]",
+    /// ))
+    /// .unwrap();
+    /// assert_eq!(map[&1], 10);
+    ///
+    /// let unknown_version = RbTreeMap::<i32, i32>::restore(&mut serde_json::Deserializer::from_str(
+    ///     "[0,2,[[0,0],[1,10]]]",
+    /// ));
+    /// assert!(unknown_version.is_err());
+    /// ```
+    pub fn restore<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(3, PersistedVisitor(PhantomData))
+    }
+}
+
+struct Entries<'a, K, V>(&'a RbTreeMap<K, V>);
+
+impl<K: Serialize, V: Serialize> Serialize for Entries<'_, K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for entry in self.0.iter() {
+            seq.serialize_element(&entry)?;
+        }
+        seq.end()
+    }
+}
+
+struct PersistedVisitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K: Ord + Deserialize<'de>, V: Deserialize<'de>> Visitor<'de> for PersistedVisitor<K, V> {
+    type Value = RbTreeMap<K, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a persisted RbTreeMap")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let version: u8 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        if version != FORMAT_VERSION {
+            return Err(de::Error::custom(format_args!(
+                "unsupported format version {}, expected {}",
+                version, FORMAT_VERSION
+            )));
+        }
+        let len: u64 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let entries = seq
+            .next_element_seed(EntriesSeed(len, PhantomData))?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        Ok(RbTreeMap {
+            root: Root::from_sorted_iter(entries.into_iter()),
+        })
+    }
+}
+
+/// Reads the `.0` entries in strictly ascending order of key.
+struct EntriesSeed<K, V>(u64, PhantomData<(K, V)>);
+
+impl<'de, K: Ord + Deserialize<'de>, V: Deserialize<'de>> DeserializeSeed<'de>
+    for EntriesSeed<K, V>
+{
+    type Value = Vec<(K, V)>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, K: Ord + Deserialize<'de>, V: Deserialize<'de>> Visitor<'de> for EntriesSeed<K, V> {
+    type Value = Vec<(K, V)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{} entries in ascending order of key", self.0)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        // the capacity is limited, not to trust the length of broken data too much.
+        let mut entries: Vec<(K, V)> = Vec::with_capacity(self.0.min(4096) as usize);
+        while let Some((key, value)) = seq.next_element::<(K, V)>()? {
+            if entries.last().is_some_and(|(last, _)| last >= &key) {
+                return Err(de::Error::custom(
+                    "the keys are not in strictly ascending order",
+                ));
+            }
+            entries.push((key, value));
+        }
+        if entries.len() as u64 != self.0 {
+            return Err(de::Error::invalid_length(entries.len(), &self));
+        }
+        Ok(entries)
+    }
+}
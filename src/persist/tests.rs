@@ -0,0 +1,44 @@
+use crate::RbTreeMap;
+
+fn persist<K: serde::Serialize, V: serde::Serialize>(map: &RbTreeMap<K, V>) -> Vec<u8> {
+    let mut bytes = vec![];
+    map.persist(&mut serde_json::Serializer::new(&mut bytes))
+        .unwrap();
+    bytes
+}
+
+fn restore(json: &[u8]) -> Result<RbTreeMap<u32, String>, serde_json::Error> {
+    RbTreeMap::restore(&mut serde_json::Deserializer::from_slice(json))
+}
+
+#[test]
+fn round_trip() {
+    // the map is built at once, because checking the tree on every insertion takes too long.
+    let entries: std::collections::BTreeMap<u32, String> = (0..100_000u32)
+        .map(|k| (k.wrapping_mul(2_654_435_761) % 1_000_000, k.to_string()))
+        .collect();
+    let mut map = RbTreeMap::new();
+    map.extend_sorted(entries);
+    let restored = restore(&persist(&map)).unwrap();
+    assert_eq!(restored.len(), map.len());
+    assert_eq!(restored, map);
+    let root = restored.root.inner().unwrap();
+    assert!(root.parent().is_none());
+    root.assert_tree(&Some(root));
+
+    let empty = restore(&persist(&RbTreeMap::<u32, String>::new())).unwrap();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn rejects_broken_data() {
+    // unknown version
+    assert!(restore(br#"[2,1,[[0,"a"]]]"#).is_err());
+    // length mismatch
+    assert!(restore(br#"[1,2,[[0,"a"]]]"#).is_err());
+    assert!(restore(br#"[1,0,[[0,"a"]]]"#).is_err());
+    // not ascending
+    assert!(restore(br#"[1,2,[[1,"a"],[0,"b"]]]"#).is_err());
+    assert!(restore(br#"[1,2,[[0,"a"],[0,"b"]]]"#).is_err());
+    assert!(restore(br#"[1,2,[[0,"a"],[1,"b"]]]"#).is_ok());
+}
@@ -0,0 +1,476 @@
+//! A persistent map whose nodes are shared by [`Arc`] between its clones.
+//!
+//! Cloning [`PersistentRbTreeMap`] takes `O(1)` time, so a clone works as a snapshot which is never affected by later mutations of the original. A mutation copies only the nodes on the path from the root to the touched node, and the other subtrees stay shared.
+
+#[cfg(test)]
+mod tests;
+
+use crate::node::Color;
+
+use std::{borrow::Borrow, cmp::Ordering, fmt, iter::FusedIterator, sync::Arc};
+
+type Link<K, V> = Option<Arc<PersistentNode<K, V>>>;
+
+struct PersistentNode<K, V> {
+    color: Color,
+    key: K,
+    value: V,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K, V> PersistentNode<K, V> {
+    fn new(color: Color, left: Link<K, V>, key: K, value: V, right: Link<K, V>) -> Arc<Self> {
+        Arc::new(Self {
+            color,
+            key,
+            value,
+            left,
+            right,
+        })
+    }
+
+    fn is_red(&self) -> bool {
+        self.color == Color::Red
+    }
+}
+
+/// Builds a node of `color`, resolving a red child which has a red child under a black node by restructuring them into a red node with two black children.
+fn balance<K: Clone, V: Clone>(
+    color: Color,
+    left: Link<K, V>,
+    key: K,
+    value: V,
+    right: Link<K, V>,
+) -> Arc<PersistentNode<K, V>> {
+    use Color::*;
+
+    if color == Black {
+        if let Some(l) = left.as_ref().filter(|l| l.is_red()) {
+            if let Some(ll) = l.left.as_ref().filter(|ll| ll.is_red()) {
+                return PersistentNode::new(
+                    Red,
+                    Some(PersistentNode::new(
+                        Black,
+                        ll.left.clone(),
+                        ll.key.clone(),
+                        ll.value.clone(),
+                        ll.right.clone(),
+                    )),
+                    l.key.clone(),
+                    l.value.clone(),
+                    Some(PersistentNode::new(
+                        Black,
+                        l.right.clone(),
+                        key,
+                        value,
+                        right,
+                    )),
+                );
+            }
+            if let Some(lr) = l.right.as_ref().filter(|lr| lr.is_red()) {
+                return PersistentNode::new(
+                    Red,
+                    Some(PersistentNode::new(
+                        Black,
+                        l.left.clone(),
+                        l.key.clone(),
+                        l.value.clone(),
+                        lr.left.clone(),
+                    )),
+                    lr.key.clone(),
+                    lr.value.clone(),
+                    Some(PersistentNode::new(
+                        Black,
+                        lr.right.clone(),
+                        key,
+                        value,
+                        right,
+                    )),
+                );
+            }
+        }
+        if let Some(r) = right.as_ref().filter(|r| r.is_red()) {
+            if let Some(rl) = r.left.as_ref().filter(|rl| rl.is_red()) {
+                return PersistentNode::new(
+                    Red,
+                    Some(PersistentNode::new(
+                        Black,
+                        left,
+                        key,
+                        value,
+                        rl.left.clone(),
+                    )),
+                    rl.key.clone(),
+                    rl.value.clone(),
+                    Some(PersistentNode::new(
+                        Black,
+                        rl.right.clone(),
+                        r.key.clone(),
+                        r.value.clone(),
+                        r.right.clone(),
+                    )),
+                );
+            }
+            if let Some(rr) = r.right.as_ref().filter(|rr| rr.is_red()) {
+                return PersistentNode::new(
+                    Red,
+                    Some(PersistentNode::new(Black, left, key, value, r.left.clone())),
+                    r.key.clone(),
+                    r.value.clone(),
+                    Some(PersistentNode::new(
+                        Black,
+                        rr.left.clone(),
+                        rr.key.clone(),
+                        rr.value.clone(),
+                        rr.right.clone(),
+                    )),
+                );
+            }
+        }
+    }
+    PersistentNode::new(color, left, key, value, right)
+}
+
+/// Inserts the pair under `link` by copying the nodes on the path, and returns the new subtree and the old value.
+fn insert<K: Ord + Clone, V: Clone>(
+    link: &Link<K, V>,
+    key: K,
+    value: V,
+) -> (Arc<PersistentNode<K, V>>, Option<V>) {
+    let node = if let Some(node) = link {
+        node
+    } else {
+        return (
+            PersistentNode::new(Color::Red, None, key, value, None),
+            None,
+        );
+    };
+    match key.cmp(&node.key) {
+        Ordering::Less => {
+            let (left, old) = insert(&node.left, key, value);
+            let new_node = balance(
+                node.color,
+                Some(left),
+                node.key.clone(),
+                node.value.clone(),
+                node.right.clone(),
+            );
+            (new_node, old)
+        }
+        Ordering::Equal => {
+            let new_node = PersistentNode::new(
+                node.color,
+                node.left.clone(),
+                key,
+                value,
+                node.right.clone(),
+            );
+            (new_node, Some(node.value.clone()))
+        }
+        Ordering::Greater => {
+            let (right, old) = insert(&node.right, key, value);
+            let new_node = balance(
+                node.color,
+                node.left.clone(),
+                node.key.clone(),
+                node.value.clone(),
+                Some(right),
+            );
+            (new_node, old)
+        }
+    }
+}
+
+/// A persistent map based on a red-black tree, whose clones share the nodes by [`Arc`].
+///
+/// # Examples
+///
+/// ```
+/// use rb_tree::persistent::PersistentRbTreeMap;
+///
+/// let mut map = PersistentRbTreeMap::new();
+/// map.insert(1, "a");
+/// map.insert(2, "b");
+///
+/// let snapshot = map.clone();
+/// map.insert(1, "c");
+/// map.insert(3, "d");
+///
+/// assert_eq!(snapshot.get(&1), Some(&"a"));
+/// assert_eq!(snapshot.len(), 2);
+/// assert_eq!(map.get(&1), Some(&"c"));
+/// assert_eq!(map.len(), 3);
+/// ```
+pub struct PersistentRbTreeMap<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+/// Takes `O(1)` time by sharing the nodes.
+impl<K, V> Clone for PersistentRbTreeMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K, V> Default for PersistentRbTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for PersistentRbTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> PersistentRbTreeMap<K, V> {
+    /// Creates an empty `PersistentRbTreeMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::persistent::PersistentRbTreeMap;
+    ///
+    /// let map = PersistentRbTreeMap::<i32, ()>::new();
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    /// Returns the number of elements in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::persistent::PersistentRbTreeMap;
+    ///
+    /// let mut map = PersistentRbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the map contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::persistent::PersistentRbTreeMap;
+    ///
+    /// let mut map = PersistentRbTreeMap::new();
+    /// assert!(map.is_empty());
+    /// map.insert(1, "a");
+    /// assert!(!map.is_empty());
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::persistent::PersistentRbTreeMap;
+    ///
+    /// let map: PersistentRbTreeMap<_, _> = [(2, "b"), (1, "a")].into_iter().collect();
+    /// assert!(map.iter().eq([(&1, &"a"), (&2, &"b")]));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            length: self.len,
+        };
+        iter.push_left_spine(self.root.as_deref());
+        iter
+    }
+
+    /// Returns `true` if `self` and `other` share the same root node, that is one is a clone of the other without mutations after cloning. It takes `O(1)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::persistent::PersistentRbTreeMap;
+    ///
+    /// let mut map: PersistentRbTreeMap<_, _> = [(1, "a")].into_iter().collect();
+    /// let snapshot = map.clone();
+    /// assert!(map.ptr_eq(&snapshot));
+    ///
+    /// map.insert(2, "b");
+    /// assert!(!map.ptr_eq(&snapshot));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<K: Ord, V> PersistentRbTreeMap<K, V> {
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::persistent::PersistentRbTreeMap;
+    ///
+    /// let mut map = PersistentRbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get(&1), Some(&"a"));
+    /// assert_eq!(map.get(&2), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            current = match key.cmp(node.key.borrow()) {
+                Ordering::Less => node.left.as_deref(),
+                Ordering::Equal => return Some(&node.value),
+                Ordering::Greater => node.right.as_deref(),
+            };
+        }
+        None
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::persistent::PersistentRbTreeMap;
+    ///
+    /// let mut map = PersistentRbTreeMap::new();
+    /// map.insert(1, "a");
+    /// assert!(map.contains_key(&1));
+    /// assert!(!map.contains_key(&2));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> PersistentRbTreeMap<K, V> {
+    /// Inserts a key-value pair into the map, and returns the old value if the key was in. The nodes on the path to the key are copied, so the clones of the map are not affected.
+    ///
+    /// It takes `O(log n)` time and allocates `O(log n)` nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::persistent::PersistentRbTreeMap;
+    ///
+    /// let mut map = PersistentRbTreeMap::new();
+    /// assert_eq!(map.insert(37, "a"), None);
+    /// assert_eq!(map.insert(37, "b"), Some("a"));
+    /// assert_eq!(map.get(&37), Some(&"b"));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (root, old) = insert(&self.root, key, value);
+        // the root is always black, so a red root made by the balancing is repainted.
+        let root = if root.is_red() {
+            PersistentNode::new(
+                Color::Black,
+                root.left.clone(),
+                root.key.clone(),
+                root.value.clone(),
+                root.right.clone(),
+            )
+        } else {
+            root
+        };
+        self.root = Some(root);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> FromIterator<(K, V)> for PersistentRbTreeMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for (k, v) in iter {
+            map.insert(k, v);
+        }
+        map
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a PersistentRbTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of [`PersistentRbTreeMap`], created by [`PersistentRbTreeMap::iter`].
+pub struct Iter<'a, K, V> {
+    /// The nodes whose left subtrees were visited, with the next one on the top.
+    stack: Vec<&'a PersistentNode<K, V>>,
+    length: usize,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left_spine(&mut self, mut current: Option<&'a PersistentNode<K, V>>) {
+        while let Some(node) = current {
+            self.stack.push(node);
+            current = node.left.as_deref();
+        }
+    }
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            stack: self.stack.clone(),
+            length: self.length,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.length -= 1;
+        self.push_left_spine(node.right.as_deref());
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    fn len(&self) -> usize {
+        self.length
+    }
+}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
@@ -0,0 +1,80 @@
+use super::{Link, PersistentRbTreeMap};
+use crate::{node::Color, tests::rng};
+
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Checks that no red node has a red child and every path has the same number of black nodes, and returns the black height.
+fn assert_balanced<K: Ord, V>(link: &Link<K, V>) -> usize {
+    let node = if let Some(node) = link {
+        node
+    } else {
+        return 0;
+    };
+    if node.is_red() {
+        assert!(node.left.as_ref().is_none_or(|n| !n.is_red()));
+        assert!(node.right.as_ref().is_none_or(|n| !n.is_red()));
+    }
+    assert!(node.left.as_ref().is_none_or(|n| n.key < node.key));
+    assert!(node.right.as_ref().is_none_or(|n| node.key < n.key));
+    let left = assert_balanced(&node.left);
+    let right = assert_balanced(&node.right);
+    assert_eq!(left, right);
+    left + (node.color == Color::Black) as usize
+}
+
+#[test]
+fn insert_and_get() {
+    let mut map = PersistentRbTreeMap::new();
+    let mut expected = BTreeMap::new();
+    let mut rand = rng(99);
+    for _ in 0..2000 {
+        let seed = rand();
+        let key = (seed >> 16) % 500;
+        assert_eq!(map.insert(key, seed), expected.insert(key, seed));
+    }
+    assert_balanced(&map.root);
+    assert!(map.root.as_ref().is_some_and(|r| !r.is_red()));
+    assert_eq!(map.len(), expected.len());
+    assert!(map.iter().eq(expected.iter()));
+    for key in 0..500 {
+        assert_eq!(map.get(&key), expected.get(&key));
+    }
+}
+
+#[test]
+fn snapshot_is_unaffected() {
+    let mut map: PersistentRbTreeMap<u32, String> = (0..100).map(|k| (k, k.to_string())).collect();
+    let snapshot = map.clone();
+    assert!(map.ptr_eq(&snapshot));
+
+    for k in 50..150 {
+        map.insert(k, format!("new {k}"));
+    }
+    assert_eq!(snapshot.len(), 100);
+    assert!(snapshot
+        .iter()
+        .map(|(&k, v)| (k, v.clone()))
+        .eq((0..100).map(|k| (k, k.to_string()))));
+    assert_eq!(map.len(), 150);
+    assert_eq!(map.get(&70).map(String::as_str), Some("new 70"));
+    assert_eq!(map.get(&30).map(String::as_str), Some("30"));
+    assert_balanced(&map.root);
+    assert_balanced(&snapshot.root);
+}
+
+#[test]
+fn insert_copies_only_the_path() {
+    let map: PersistentRbTreeMap<u32, u32> = (0..1000).map(|k| (k, k)).collect();
+    let mut updated = map.clone();
+    updated.insert(0, 1);
+
+    // the subtree which does not contain the key is shared
+    let (old_root, new_root) = (map.root.as_ref().unwrap(), updated.root.as_ref().unwrap());
+    assert!(!Arc::ptr_eq(old_root, new_root));
+    assert!(Arc::ptr_eq(
+        old_root.right.as_ref().unwrap(),
+        new_root.right.as_ref().unwrap()
+    ));
+    assert_eq!(map.get(&0), Some(&0));
+    assert_eq!(updated.get(&0), Some(&1));
+}
@@ -0,0 +1,202 @@
+#[cfg(test)]
+mod tests;
+
+use crate::RbTreeSet;
+
+use std::fmt;
+
+/// A double-ended priority queue based on a red-black tree.
+///
+/// Unlike [`std::collections::BinaryHeap`], both of the minimum and the maximum can be popped in `O(log n)`. Among equal elements, [`pop_min`](Self::pop_min) pops the earliest pushed one first and [`pop_max`](Self::pop_max) pops the latest pushed one first. The order is kept until the push counter wraps around after `usize::MAX` pushes.
+pub struct RbPriorityQueue<T> {
+    set: RbTreeSet<(T, usize)>,
+    pushed: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for RbPriorityQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.set.iter().map(|(item, _)| item))
+            .finish()
+    }
+}
+
+impl<T> Default for RbPriorityQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for RbPriorityQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = Self::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T: Ord> Extend<T> for RbPriorityQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> RbPriorityQueue<T> {
+    /// Creates an empty `RbPriorityQueue`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue = RbPriorityQueue::new();
+    /// queue.push(5);
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            set: RbTreeSet::new(),
+            pushed: 0,
+        }
+    }
+
+    /// Returns the number of elements in the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue = RbPriorityQueue::new();
+    /// queue.push(1);
+    /// queue.push(1);
+    /// assert_eq!(queue.len(), 2);
+    /// ```
+    pub const fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue = RbPriorityQueue::new();
+    /// assert!(queue.is_empty());
+    /// queue.push(1);
+    /// assert!(!queue.is_empty());
+    /// ```
+    pub const fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Clears the queue, removing all elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue = RbPriorityQueue::new();
+    /// queue.push(1);
+    /// queue.clear();
+    /// assert!(queue.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+}
+
+impl<T: Ord> RbPriorityQueue<T> {
+    /// Pushes an element into the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue = RbPriorityQueue::new();
+    /// queue.push(3);
+    /// queue.push(5);
+    /// queue.push(1);
+    ///
+    /// assert_eq!(queue.len(), 3);
+    /// assert_eq!(queue.peek_min(), Some(&1));
+    /// assert_eq!(queue.peek_max(), Some(&5));
+    /// ```
+    pub fn push(&mut self, item: T) {
+        self.set.insert((item, self.pushed));
+        self.pushed = self.pushed.wrapping_add(1);
+    }
+
+    /// Returns a reference to the minimum element in the queue, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue = RbPriorityQueue::new();
+    /// assert_eq!(queue.peek_min(), None);
+    /// queue.push(2);
+    /// queue.push(1);
+    /// assert_eq!(queue.peek_min(), Some(&1));
+    /// ```
+    pub fn peek_min(&self) -> Option<&T> {
+        self.set.first::<(T, usize)>().map(|(item, _)| item)
+    }
+
+    /// Returns a reference to the maximum element in the queue, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue = RbPriorityQueue::new();
+    /// assert_eq!(queue.peek_max(), None);
+    /// queue.push(1);
+    /// queue.push(2);
+    /// assert_eq!(queue.peek_max(), Some(&2));
+    /// ```
+    pub fn peek_max(&self) -> Option<&T> {
+        self.set.last::<(T, usize)>().map(|(item, _)| item)
+    }
+
+    /// Removes the minimum element from the queue and returns it, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue: RbPriorityQueue<_> = [2, 1, 3].into_iter().collect();
+    /// assert_eq!(queue.pop_min(), Some(1));
+    /// assert_eq!(queue.pop_min(), Some(2));
+    /// assert_eq!(queue.pop_min(), Some(3));
+    /// assert_eq!(queue.pop_min(), None);
+    /// ```
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.set.pop_first().map(|(item, _)| item)
+    }
+
+    /// Removes the maximum element from the queue and returns it, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbPriorityQueue;
+    ///
+    /// let mut queue: RbPriorityQueue<_> = [2, 1, 3].into_iter().collect();
+    /// assert_eq!(queue.pop_max(), Some(3));
+    /// assert_eq!(queue.pop_max(), Some(2));
+    /// assert_eq!(queue.pop_max(), Some(1));
+    /// assert_eq!(queue.pop_max(), None);
+    /// ```
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.set.pop_last().map(|(item, _)| item)
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{tests::Tagged, RbPriorityQueue};
+
+#[test]
+fn alternate_pop() {
+    let mut queue = RbPriorityQueue::new();
+    for x in [7, 3, 9, 0, 5, 2, 8, 4, 1, 6, 5] {
+        queue.push(x);
+    }
+    assert_eq!(queue.len(), 11);
+
+    let mut popped = vec![];
+    while let Some(min) = queue.pop_min() {
+        popped.push(min);
+        if let Some(max) = queue.pop_max() {
+            popped.push(max);
+        }
+    }
+    assert_eq!(popped, vec![0, 9, 1, 8, 2, 7, 3, 6, 4, 5, 5]);
+    assert!(queue.is_empty());
+    assert_eq!(queue.peek_min(), None);
+    assert_eq!(queue.peek_max(), None);
+}
+
+#[test]
+fn ties_pop_in_push_order() {
+    let tasks = [(1, "a"), (2, "b"), (1, "c"), (2, "d"), (1, "e"), (2, "f")];
+    let mut queue: RbPriorityQueue<_> = tasks.into_iter().map(|(p, n)| Tagged(p, n)).collect();
+
+    let mut mins = vec![];
+    let mut maxes = vec![];
+    for _ in 0..3 {
+        mins.push(queue.pop_min().unwrap().1);
+        maxes.push(queue.pop_max().unwrap().1);
+    }
+    assert_eq!(mins, ["a", "c", "e"]);
+    assert_eq!(maxes, ["f", "d", "b"]);
+    assert!(queue.is_empty());
+}
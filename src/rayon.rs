@@ -0,0 +1,74 @@
+//! The implementation of [`FromParallelIterator`]. The items are collected into sorted runs in parallel, merged into one run, and then built into a tree at once.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{node::Root, RbTreeMap};
+
+use ::rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelIterator};
+
+use std::cmp::Ordering;
+
+/// Sorts `run` by key stably, and drops the earlier pairs of the equal keys as [`RbTreeMap::insert`] replaces them.
+fn sort_run<K: Ord, V>(mut run: Vec<(K, V)>) -> Vec<(K, V)> {
+    run.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut sorted: Vec<(K, V)> = Vec::with_capacity(run.len());
+    for (key, value) in run {
+        if let Some(last) = sorted.last_mut().filter(|last| last.0 == key) {
+            *last = (key, value);
+        } else {
+            sorted.push((key, value));
+        }
+    }
+    sorted
+}
+
+/// Merges the sorted runs into one, where the pairs in `right` win on the equal keys because they came later.
+fn merge_runs<K: Ord, V>(left: Vec<(K, V)>, right: Vec<(K, V)>) -> Vec<(K, V)> {
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    loop {
+        let order = match (left.peek(), right.peek()) {
+            (Some(l), Some(r)) => l.0.cmp(&r.0),
+            (Some(_), None) => {
+                merged.extend(left);
+                return merged;
+            }
+            (None, _) => {
+                merged.extend(right);
+                return merged;
+            }
+        };
+        match order {
+            Ordering::Less => merged.extend(left.next()),
+            Ordering::Equal => {
+                left.next();
+                merged.extend(right.next());
+            }
+            Ordering::Greater => merged.extend(right.next()),
+        }
+    }
+}
+
+/// Collects the pairs in parallel. The later duplicates of a key in the order of `par_iter` overwrite the earlier ones, as a sequential `collect` does.
+impl<K: Ord + Send, V: Send> FromParallelIterator<(K, V)> for RbTreeMap<K, V> {
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = (K, V)>,
+    {
+        // the runs are in the order of `par_iter`, so the reduction keeps it too.
+        let runs: Vec<Vec<(K, V)>> = par_iter
+            .into_par_iter()
+            .collect_vec_list()
+            .into_iter()
+            .collect();
+        let sorted = runs
+            .into_par_iter()
+            .map(sort_run)
+            .reduce(Vec::new, merge_runs);
+        Self {
+            root: Root::from_sorted_iter(sorted.into_iter()),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::RbTreeMap;
+
+use ::rayon::prelude::*;
+
+#[test]
+fn parallel_collect_equals_sequential() {
+    // many duplicated keys over the runs
+    let items: Vec<(u32, usize)> = (0..20_000usize)
+        .map(|i| ((i as u32).wrapping_mul(2_654_435_761) % 2_000, i))
+        .collect();
+
+    let parallel: RbTreeMap<u32, usize> = items.par_iter().copied().collect();
+    let sequential: RbTreeMap<u32, usize> = items.iter().copied().collect();
+
+    assert_eq!(parallel.len(), sequential.len());
+    assert!(parallel.iter().eq(sequential.iter()));
+    let root = parallel.root.inner().unwrap();
+    root.assert_tree(&Some(root));
+
+    let empty: RbTreeMap<u32, u32> = Vec::new().into_par_iter().collect();
+    assert!(empty.is_empty());
+}
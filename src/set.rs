@@ -1,14 +1,26 @@
 pub mod iter;
 mod ops;
 
-use crate::RbTreeMap;
+use crate::{node::Root, RbTreeMap};
 
-use std::{borrow::Borrow, fmt};
+use std::{borrow::Borrow, collections::BTreeSet, fmt};
 
 /// A set based on a red-black tree.
 #[derive(Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RbTreeSet<T> {
-    map: RbTreeMap<T, ()>,
+    pub(crate) map: RbTreeMap<T, ()>,
+}
+
+impl<T: PartialEq> PartialEq<BTreeSet<T>> for RbTreeSet<T> {
+    fn eq(&self, other: &BTreeSet<T>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq> PartialEq<RbTreeSet<T>> for BTreeSet<T> {
+    fn eq(&self, other: &RbTreeSet<T>) -> bool {
+        other == self
+    }
 }
 
 impl<T> Default for RbTreeSet<T> {
@@ -92,6 +104,54 @@ impl<T> RbTreeSet<T> {
         self.map.contains_key(value)
     }
 
+    /// Returns `true` if the set contains all of `queries`, which must be sorted in ascending order. It walks the set and the queries in tandem, so it takes `O(n + m)` time for `m` queries instead of `m` independent lookups.
+    ///
+    /// The order of `queries` is checked only in debug builds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let set: RbTreeSet<_> = [1, 2, 3, 5, 8].into_iter().collect();
+    /// assert!(set.contains_all_sorted([2, 3, 8]));
+    /// assert!(set.contains_all_sorted(Vec::<i32>::new()));
+    /// assert!(!set.contains_all_sorted([1, 4, 5]));
+    /// ```
+    pub fn contains_all_sorted<Q, I>(&self, queries: I) -> bool
+    where
+        T: Ord + Borrow<Q>,
+        Q: Ord,
+        I: IntoIterator<Item = Q>,
+    {
+        use std::cmp::Ordering::*;
+
+        let mut elems = self.iter().peekable();
+        let mut prev: Option<Q> = None;
+        for query in queries {
+            debug_assert!(
+                prev.as_ref().is_none_or(|prev| prev <= &query),
+                "the queries must be sorted in ascending order"
+            );
+            loop {
+                let elem = if let Some(elem) = elems.peek() {
+                    elem
+                } else {
+                    return false;
+                };
+                match (*elem).borrow().cmp(&query) {
+                    Less => {
+                        elems.next();
+                    }
+                    Equal => break,
+                    Greater => return false,
+                }
+            }
+            prev = Some(query);
+        }
+        true
+    }
+
     /// Returns a reference to the value in the set, if any, that is equal to the given value.
     ///
     /// The value may be any borrowed form of the set's value type,
@@ -115,6 +175,37 @@ impl<T> RbTreeSet<T> {
         self.map.get_key_value(value).map(|(k, _)| k)
     }
 
+    /// Returns references to the `N` values in the set equal to the given values, in the same order. It returns `None` if any of them is absent, or two of them refer to the same stored value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let set: RbTreeSet<_> = [1, 2, 3].iter().cloned().collect();
+    /// assert_eq!(set.get_disjoint([&3, &1]), Some([&3, &1]));
+    /// assert_eq!(set.get_disjoint([&1, &4]), None);
+    /// assert_eq!(set.get_disjoint([&2, &2]), None);
+    /// ```
+    pub fn get_disjoint<Q, const N: usize>(&self, values: [&Q; N]) -> Option<[&T; N]>
+    where
+        T: Ord + Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut found: [Option<&T>; N] = [None; N];
+        for (i, value) in values.into_iter().enumerate() {
+            let stored = self.get(value)?;
+            if found[..i]
+                .iter()
+                .any(|&prev| prev.is_some_and(|prev| std::ptr::eq(prev, stored)))
+            {
+                return None;
+            }
+            found[i] = Some(stored);
+        }
+        Some(found.map(Option::unwrap))
+    }
+
     /// Adds a value to the set.
     ///
     /// If the set did not have this value present, true is returned.
@@ -227,6 +318,25 @@ impl<T> RbTreeSet<T> {
         self.drain_filter(|item| !f(item));
     }
 
+    /// Retains only the elements specified by the predicate like [`retain`](Self::retain), and returns the number of removed elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let mut set: RbTreeSet<i32> = (0..10).collect();
+    /// assert_eq!(set.retain_count(|&k| k % 2 == 0), 5);
+    /// assert!(set.iter().eq([0, 2, 4, 6, 8].iter()));
+    /// ```
+    pub fn retain_count<F>(&mut self, mut f: F) -> usize
+    where
+        T: Ord,
+        F: FnMut(&T) -> bool,
+    {
+        self.drain_filter(|item| !f(item)).count()
+    }
+
     /// Moves all elements from other into Self, leaving other empty.
     ///
     /// # Examples
@@ -262,6 +372,65 @@ impl<T> RbTreeSet<T> {
         self.map.append(&mut other.map);
     }
 
+    /// Splits the set into two at the given value. Returns everything after the given value, including the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let mut a = RbTreeSet::new();
+    /// a.insert(1);
+    /// a.insert(2);
+    /// a.insert(3);
+    /// a.insert(17);
+    /// a.insert(41);
+    ///
+    /// let b = a.split_off(&3);
+    ///
+    /// assert_eq!(a.len(), 2);
+    /// assert_eq!(b.len(), 3);
+    ///
+    /// assert!(a.contains(&1));
+    /// assert!(a.contains(&2));
+    ///
+    /// assert!(b.contains(&3));
+    /// assert!(b.contains(&17));
+    /// assert!(b.contains(&41));
+    /// ```
+    pub fn split_off<Q>(&mut self, value: &Q) -> Self
+    where
+        T: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+    {
+        Self {
+            map: self.map.split_off(value),
+        }
+    }
+
+    /// Consumes the set and partitions the values into two sets by `f`, the first of which has the values `f` returned `true` for. The values are visited once in ascending order, and each result is built at once without comparing the values, so it takes `O(n)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let set: RbTreeSet<_> = (0..8).collect();
+    /// let (evens, odds) = set.partition(|&x| x % 2 == 0);
+    ///
+    /// assert!(evens.into_iter().eq([0, 2, 4, 6]));
+    /// assert!(odds.into_iter().eq([1, 3, 5, 7]));
+    /// ```
+    pub fn partition<F: FnMut(&T) -> bool>(self, mut f: F) -> (Self, Self) {
+        let (matched, unmatched): (Vec<T>, Vec<T>) = self.into_iter().partition(|value| f(value));
+        let build = |values: Vec<T>| Self {
+            map: RbTreeMap {
+                root: Root::from_sorted_iter(values.into_iter().map(|value| (value, ()))),
+            },
+        };
+        (build(matched), build(unmatched))
+    }
+
     /// Clears the set, removing all values.
     ///
     /// # Examples
@@ -322,6 +491,28 @@ impl<T> RbTreeSet<T> {
         self.map.last().map(|(k, _)| k.borrow())
     }
 
+    /// Returns a reference to the value at the ascending position `index`, or `None` if `index` is out of bounds.
+    ///
+    /// The subtree sizes are not tracked in the tree, so the value is found by walking from the nearer end of the set in `O(min(index, len - index))` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let set: RbTreeSet<_> = [5, 1, 3].into_iter().collect();
+    /// assert_eq!(set.nth_by_walk(0), Some(&1));
+    /// assert_eq!(set.nth_by_walk(1), Some(&3));
+    /// assert_eq!(set.nth_by_walk(2), Some(&5));
+    /// assert_eq!(set.nth_by_walk(3), None);
+    /// ```
+    pub fn nth_by_walk(&self, index: usize) -> Option<&T>
+    where
+        T: Ord,
+    {
+        self.map.entry_at(index).map(|(value, _)| value)
+    }
+
     /// Removes the first value from the set and returns it, if any. The first value is always the minimum value in the set.
     ///
     /// # Examples
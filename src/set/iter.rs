@@ -4,6 +4,7 @@ mod merge;
 use self::merge::MergeIter;
 
 use super::RbTreeSet;
+use crate::ord::Step;
 
 use std::{
     borrow::Borrow,
@@ -44,17 +45,60 @@ impl<T> RbTreeSet<T> {
     /// assert_eq!(set_iter.next(), Some(&3));
     /// assert_eq!(set_iter.next(), None);
     /// ```
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter(self.map.keys())
     }
 
+    /// Collects the references to the values into a `Vec` in ascending order, which is sized exactly by the length of the set.
+    ///
+    /// The `Vec` is a sorted slice over a stable view of the set, so algorithms on sorted slices such as [`slice::binary_search`] and [`slice::partition_point`] work on it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let set: RbTreeSet<_> = [5, 1, 3].into_iter().collect();
+    ///
+    /// let values = set.to_sorted_vec();
+    /// assert_eq!(values, [&1, &3, &5]);
+    /// assert_eq!(values.partition_point(|&&v| v < 4), 2);
+    /// ```
+    pub fn to_sorted_vec(&self) -> Vec<&T> {
+        let mut values = Vec::with_capacity(self.len());
+        values.extend(self.iter());
+        values
+    }
+
+    /// Gets an iterator over the runs of consecutive elements in the set, in ascending order. Each run is yielded as an inclusive range from its first element to its last element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let set: RbTreeSet<u32> = [1, 2, 3, 7, 8, 10].into_iter().collect();
+    ///
+    /// assert!(set.runs().eq([1..=3, 7..=8, 10..=10]));
+    /// ```
+    pub fn runs(&self) -> impl Iterator<Item = ops::RangeInclusive<T>> + '_
+    where
+        T: Step + Clone,
+    {
+        self.map.key_runs()
+    }
+
     /// Constructs a double-ended iterator over a sub-range of elements in the set.
     ///
+    /// The rest of the range can be counted by [`Range::count_remaining`], which walks it in `O(k)` time for `k` elements.
+    ///
+    /// # Examples
+    ///
     /// ```
-    /// use std::collections::BTreeSet;
+    /// use rb_tree::RbTreeSet;
     /// use std::ops::Bound::Included;
     ///
-    /// let mut set = BTreeSet::new();
+    /// let mut set = RbTreeSet::new();
     /// set.insert(3);
     /// set.insert(5);
     /// set.insert(8);
@@ -62,8 +106,20 @@ impl<T> RbTreeSet<T> {
     ///     println!("{}", elem);
     /// }
     /// assert_eq!(Some(&5), set.range(4..).next());
+    /// assert_eq!(set.range(4..).count_remaining(), 2);
+    /// ```
+    ///
+    /// The bounds may be owned values or any borrowed form of the element type:
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    /// use std::ops::Bound::{Excluded, Included};
+    ///
+    /// let set: RbTreeSet<String> = ["a", "b", "c", "d"].into_iter().map(String::from).collect();
+    /// assert!(set.range("b".to_string().."d".to_string()).eq(["b", "c"]));
+    /// assert!(set.range::<_, str>((Included("b"), Excluded("d"))).eq(["b", "c"]));
     /// ```
-    pub fn range<R, I>(&self, range: R) -> Range<T>
+    pub fn range<R, I>(&self, range: R) -> Range<'_, T>
     where
         T: Ord + Borrow<I>,
         R: ops::RangeBounds<I>,
@@ -72,6 +128,46 @@ impl<T> RbTreeSet<T> {
         Range(self.map.range(range))
     }
 
+    /// Constructs a double-ended iterator over the elements after `start`, which is a shorthand of [`range`](Self::range) bounded only at the start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    /// use std::ops::Bound::{Excluded, Included};
+    ///
+    /// let set: RbTreeSet<_> = (1..=5).collect();
+    /// assert!(set.range_from(Excluded(&3)).eq(&[4, 5]));
+    /// assert!(set.range_from(Included(&3)).eq(&[3, 4, 5]));
+    /// ```
+    pub fn range_from<Q>(&self, start: ops::Bound<&Q>) -> Range<'_, T>
+    where
+        T: Ord + Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.range((start, ops::Bound::Unbounded))
+    }
+
+    /// Constructs a double-ended iterator over the elements before `end`, which is a shorthand of [`range`](Self::range) bounded only at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    /// use std::ops::Bound::{Excluded, Included};
+    ///
+    /// let set: RbTreeSet<_> = (1..=5).collect();
+    /// assert!(set.range_to(Excluded(&3)).eq(&[1, 2]));
+    /// assert!(set.range_to(Included(&3)).rev().eq(&[3, 2, 1]));
+    /// ```
+    pub fn range_to<Q>(&self, end: ops::Bound<&Q>) -> Range<'_, T>
+    where
+        T: Ord + Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.range((ops::Bound::Unbounded, end))
+    }
+
     /// Visits the values representing the difference, i.e., the values that are in self but not in other, in ascending order.
     ///
     /// # Examples
@@ -90,7 +186,7 @@ impl<T> RbTreeSet<T> {
     /// let diff: Vec<_> = a.difference(&b).cloned().collect();
     /// assert_eq!(diff, [1]);
     /// ```
-    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<T>
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T>
     where
         T: Ord,
     {
@@ -240,6 +336,78 @@ impl<T> RbTreeSet<T> {
         Union(MergeIter::new(self.iter(), other.iter()))
     }
 
+    /// Counts the values in the intersection without collecting them. It chooses the strategy by the sizes as [`intersection`](Self::intersection) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let a: RbTreeSet<_> = [1, 2, 3].into_iter().collect();
+    /// let b: RbTreeSet<_> = [2, 3, 4].into_iter().collect();
+    /// assert_eq!(a.intersection_len(&b), 2);
+    /// ```
+    pub fn intersection_len(&self, other: &Self) -> usize
+    where
+        T: Ord,
+    {
+        self.intersection(other).count()
+    }
+
+    /// Counts the values in the union without collecting them, that is `self.len() + other.len() - self.intersection_len(other)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let a: RbTreeSet<_> = [1, 2, 3].into_iter().collect();
+    /// let b: RbTreeSet<_> = [2, 3, 4].into_iter().collect();
+    /// assert_eq!(a.union_len(&b), 4);
+    /// ```
+    pub fn union_len(&self, other: &Self) -> usize
+    where
+        T: Ord,
+    {
+        self.len() + other.len() - self.intersection_len(other)
+    }
+
+    /// Counts the values in the difference without collecting them, that is `self.len() - self.intersection_len(other)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let a: RbTreeSet<_> = [1, 2, 3].into_iter().collect();
+    /// let b: RbTreeSet<_> = [2, 3, 4].into_iter().collect();
+    /// assert_eq!(a.difference_len(&b), 1);
+    /// ```
+    pub fn difference_len(&self, other: &Self) -> usize
+    where
+        T: Ord,
+    {
+        self.len() - self.intersection_len(other)
+    }
+
+    /// Counts the values in the symmetric difference without collecting them, that is `self.len() + other.len() - 2 * self.intersection_len(other)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let a: RbTreeSet<_> = [1, 2, 3].into_iter().collect();
+    /// let b: RbTreeSet<_> = [2, 3, 4].into_iter().collect();
+    /// assert_eq!(a.symmetric_difference_len(&b), 2);
+    /// ```
+    pub fn symmetric_difference_len(&self, other: &Self) -> usize
+    where
+        T: Ord,
+    {
+        self.len() + other.len() - 2 * self.intersection_len(other)
+    }
+
     /// Returns `true` if `self` has no elements in common with `other`. This is equivalent to checking for an empty intersection.
     ///
     /// # Examples
@@ -386,6 +554,10 @@ impl<T> Iterator for IntoIter<T> {
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.0.size_hint()
     }
+
+    fn count(self) -> usize {
+        self.0.count()
+    }
 }
 
 impl<T> DoubleEndedIterator for IntoIter<T> {
@@ -402,6 +574,23 @@ impl<T> ExactSizeIterator for IntoIter<T> {
 
 impl<T> FusedIterator for IntoIter<T> {}
 
+/// Iterates over the elements of the set in ascending order, as [`RbTreeSet::iter`] does.
+///
+/// # Examples
+///
+/// ```
+/// use rb_tree::RbTreeSet;
+///
+/// let set: RbTreeSet<_> = [3, 1, 2].into_iter().collect();
+///
+/// let mut seen = vec![];
+/// for x in &set {
+///     seen.push(*x);
+/// }
+/// assert_eq!(seen, [1, 2, 3]);
+/// // the set is only borrowed
+/// assert_eq!(set.len(), 3);
+/// ```
 impl<'a, T> IntoIterator for &'a RbTreeSet<T> {
     type Item = &'a T;
 
@@ -431,6 +620,10 @@ impl<'a, T> Iterator for Iter<'a, T> {
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.0.size_hint()
     }
+
+    fn count(self) -> usize {
+        self.0.count()
+    }
 }
 
 impl<T> DoubleEndedIterator for Iter<'_, T> {
@@ -450,12 +643,22 @@ impl<T> FusedIterator for Iter<'_, T> {}
 #[derive(Debug)]
 pub struct Range<'a, T>(crate::map::iter::Range<'a, T, ()>);
 
+impl<T> Clone for Range<'_, T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
 impl<'a, T: 'a> Iterator for Range<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.0.next().map(|(k, _)| k)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
 }
 
 impl<'a, T: 'a> DoubleEndedIterator for Range<'a, T> {
@@ -464,8 +667,29 @@ impl<'a, T: 'a> DoubleEndedIterator for Range<'a, T> {
     }
 }
 
+impl<T> Range<'_, T> {
+    /// Counts the rest elements of the range without advancing the iterator. It walks the rest of the range, so it takes `O(k)` time for `k` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let set: RbTreeSet<_> = (0..10).collect();
+    ///
+    /// let mut range = set.range(4..);
+    /// assert_eq!(range.count_remaining(), 6);
+    /// range.next();
+    /// assert_eq!(range.count_remaining(), 5);
+    /// ```
+    pub fn count_remaining(&self) -> usize {
+        self.0.count_remaining()
+    }
+}
+
 impl<'a, T: 'a> FusedIterator for Range<'a, T> {}
 
+#[derive(Debug)]
 pub struct Difference<'a, T: 'a>(DifferenceInner<'a, T>);
 
 #[derive(Debug)]
@@ -566,6 +790,31 @@ impl<'a, T: Ord + 'a> Iterator for Difference<'a, T> {
 
 impl<'a, T: Ord + 'a> FusedIterator for Difference<'a, T> {}
 
+impl<'a, T: 'a> Difference<'a, T> {
+    /// Converts into the iterator which knows its length exactly, if the sets did not overlap so that the difference is a plain walk over `self`. Otherwise it returns back `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let a: RbTreeSet<_> = (0..10).collect();
+    /// let b: RbTreeSet<_> = (20..30).collect();
+    ///
+    /// let exact = a.difference(&b).into_exact().unwrap();
+    /// assert_eq!(exact.len(), 10);
+    ///
+    /// let overlapped: RbTreeSet<_> = (5..15).collect();
+    /// assert!(a.difference(&overlapped).into_exact().is_err());
+    /// ```
+    pub fn into_exact(self) -> Result<ExactSetIter<'a, T>, Self> {
+        match self.0 {
+            DifferenceInner::Through(iter) => Ok(ExactSetIter(ExactSetIterInner::Through(iter))),
+            inner => Err(Self(inner)),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SymmetricDifference<'a, T>(MergeIter<Iter<'a, T>>);
 
@@ -589,7 +838,8 @@ impl<'a, T: Ord + 'a> Iterator for SymmetricDifference<'a, T> {
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         let lens = self.0.lens();
-        (0, Some(lens.0 + lens.1))
+        // the surplus of the longer one cannot be matched
+        (lens.0.abs_diff(lens.1), Some(lens.0 + lens.1))
     }
 
     fn min(mut self) -> Option<Self::Item> {
@@ -679,6 +929,80 @@ impl<'a, T: Ord + 'a> Iterator for Intersection<'a, T> {
 
 impl<T: Ord> FusedIterator for Intersection<'_, T> {}
 
+impl<'a, T> Intersection<'a, T> {
+    /// Converts into the iterator which knows its length exactly, if the sets touch only at their ends or do not overlap, so that the intersection has at most one element. Otherwise it returns back `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::RbTreeSet;
+    ///
+    /// let a: RbTreeSet<_> = (0..=10).collect();
+    /// let b: RbTreeSet<_> = (10..20).collect();
+    ///
+    /// let exact = a.intersection(&b).into_exact().unwrap();
+    /// assert_eq!(exact.len(), 1);
+    /// assert!(exact.eq(&[10]));
+    ///
+    /// let overlapped: RbTreeSet<_> = (5..15).collect();
+    /// assert!(a.intersection(&overlapped).into_exact().is_err());
+    /// ```
+    pub fn into_exact(self) -> Result<ExactSetIter<'a, T>, Self> {
+        match self.0 {
+            IntersectionInner::AtLeast(opt) => Ok(ExactSetIter(ExactSetIterInner::AtLeast(opt))),
+            inner => Err(Self(inner)),
+        }
+    }
+}
+
+/// An iterator of a set operation whose length is known exactly, converted by [`Difference::into_exact`] or [`Intersection::into_exact`].
+#[derive(Debug)]
+pub struct ExactSetIter<'a, T>(ExactSetIterInner<'a, T>);
+
+#[derive(Debug)]
+enum ExactSetIterInner<'a, T> {
+    /// goes through the iterator
+    Through(Iter<'a, T>),
+    /// returns a specific value or emptiness
+    AtLeast(Option<&'a T>),
+}
+
+impl<T> Clone for ExactSetIter<'_, T> {
+    fn clone(&self) -> Self {
+        Self(match &self.0 {
+            ExactSetIterInner::Through(iter) => ExactSetIterInner::Through(iter.clone()),
+            ExactSetIterInner::AtLeast(opt) => ExactSetIterInner::AtLeast(*opt),
+        })
+    }
+}
+
+impl<'a, T: 'a> Iterator for ExactSetIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            ExactSetIterInner::Through(iter) => iter.next(),
+            ExactSetIterInner::AtLeast(opt) => opt.take(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for ExactSetIter<'a, T> {
+    fn len(&self) -> usize {
+        match &self.0 {
+            ExactSetIterInner::Through(iter) => iter.len(),
+            ExactSetIterInner::AtLeast(opt) => opt.is_some() as usize,
+        }
+    }
+}
+
+impl<'a, T: 'a> FusedIterator for ExactSetIter<'a, T> {}
+
 #[derive(Debug)]
 pub struct Union<'a, T>(MergeIter<Iter<'a, T>>);
 
@@ -0,0 +1,183 @@
+//! A map shared across threads behind a read-write lock.
+//!
+//! [`SyncRbTreeMap`] wraps [`RbTreeMap`] in an [`RwLock`], so any number of readers run at once and a writer runs exclusively. The `read_*` and `write_*` methods hold the lock only during the call, and [`scoped_read`](SyncRbTreeMap::scoped_read) and [`scoped_write`](SyncRbTreeMap::scoped_write) hand out the guards for a batch of operations under one lock. Put it behind an [`Arc`](std::sync::Arc) to share it.
+//!
+//! A panic while holding the write lock may leave the map half updated, so the lock is poisoned then and all of the later calls panic, as [`RwLock::read`] and [`RwLock::write`] report. Use [`FrozenRbTreeMap`](crate::frozen::FrozenRbTreeMap) instead if the map is never modified after sharing.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    borrow::Borrow,
+    fmt,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::RbTreeMap;
+
+/// A map based on a red-black tree, which is shared across threads behind a read-write lock.
+#[derive(Default)]
+pub struct SyncRbTreeMap<K, V>(RwLock<RbTreeMap<K, V>>);
+
+impl<K: fmt::Debug + Ord, V: fmt::Debug> fmt::Debug for SyncRbTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SyncRbTreeMap").field(&self.0).finish()
+    }
+}
+
+impl<K, V> From<RbTreeMap<K, V>> for SyncRbTreeMap<K, V> {
+    fn from(map: RbTreeMap<K, V>) -> Self {
+        Self(RwLock::new(map))
+    }
+}
+
+impl<K, V> SyncRbTreeMap<K, V> {
+    /// Creates an empty `SyncRbTreeMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::sync::SyncRbTreeMap;
+    ///
+    /// let map = SyncRbTreeMap::new();
+    /// map.write_insert(1, "a");
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self(RwLock::new(RbTreeMap::new()))
+    }
+
+    /// Consumes the lock and returns the inner map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::sync::SyncRbTreeMap;
+    ///
+    /// let map = SyncRbTreeMap::new();
+    /// map.write_insert(1, "a");
+    ///
+    /// let map = map.into_inner();
+    /// assert_eq!(map[&1], "a");
+    /// ```
+    pub fn into_inner(self) -> RbTreeMap<K, V> {
+        self.0.into_inner().expect("the lock is poisoned")
+    }
+
+    /// Locks the map for reading, and returns the guard which derefs to the map. The other readers can lock it at the same time, but the writers wait until the guard is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::sync::SyncRbTreeMap;
+    ///
+    /// let map = SyncRbTreeMap::from(rb_tree::RbTreeMap::from_iter([(1, "a"), (2, "b")]));
+    ///
+    /// let guard = map.scoped_read();
+    /// assert!(guard.keys().eq(&[1, 2]));
+    /// ```
+    pub fn scoped_read(&self) -> RwLockReadGuard<'_, RbTreeMap<K, V>> {
+        self.0.read().expect("the lock is poisoned")
+    }
+
+    /// Locks the map for writing, and returns the guard which derefs to the map mutably. The other readers and writers wait until the guard is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::sync::SyncRbTreeMap;
+    ///
+    /// let map = SyncRbTreeMap::new();
+    ///
+    /// let mut guard = map.scoped_write();
+    /// guard.insert(1, "a");
+    /// guard.insert(2, "b");
+    /// drop(guard);
+    /// assert_eq!(map.scoped_read().len(), 2);
+    /// ```
+    pub fn scoped_write(&self) -> RwLockWriteGuard<'_, RbTreeMap<K, V>> {
+        self.0.write().expect("the lock is poisoned")
+    }
+}
+
+impl<K: Ord, V> SyncRbTreeMap<K, V> {
+    /// Returns a clone of the value corresponding to the key, holding the read lock only during the call. Use [`scoped_read`](Self::scoped_read) to refer the value without cloning.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::sync::SyncRbTreeMap;
+    ///
+    /// let map = SyncRbTreeMap::new();
+    /// map.write_insert(1, "a");
+    /// assert_eq!(map.read_get(&1), Some("a"));
+    /// assert_eq!(map.read_get(&2), None);
+    /// ```
+    pub fn read_get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        V: Clone,
+    {
+        self.scoped_read().get(key).cloned()
+    }
+
+    /// Inserts a key-value pair into the map as [`RbTreeMap::insert`] does, holding the write lock only during the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::sync::SyncRbTreeMap;
+    ///
+    /// let map = SyncRbTreeMap::new();
+    /// assert_eq!(map.write_insert(37, "a"), None);
+    /// assert_eq!(map.write_insert(37, "b"), Some((37, "a")));
+    /// ```
+    pub fn write_insert(&self, key: K, value: V) -> Option<(K, V)> {
+        self.scoped_write().insert(key, value)
+    }
+
+    /// Removes a key from the map as [`RbTreeMap::remove`] does, holding the write lock only during the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rb_tree::sync::SyncRbTreeMap;
+    ///
+    /// let map = SyncRbTreeMap::new();
+    /// map.write_insert(1, "a");
+    /// assert_eq!(map.write_remove(&1), Some("a"));
+    /// assert_eq!(map.write_remove(&1), None);
+    /// ```
+    pub fn write_remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.scoped_write().remove(key)
+    }
+}
@@ -0,0 +1,62 @@
+use super::SyncRbTreeMap;
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+#[test]
+fn readers_and_writer_across_threads() {
+    let map = Arc::new(SyncRbTreeMap::new());
+    {
+        let mut guard = map.scoped_write();
+        guard.extend_sorted((0..1000u32).map(|k| (k * 2, k)));
+    }
+    let done = Arc::new(AtomicBool::new(false));
+
+    let readers: Vec<_> = (0..4u32)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                let mut rounds = 0;
+                while !done.load(Ordering::Acquire) || rounds == 0 {
+                    // the even keys are never touched by the writer
+                    for k in (t..1000).step_by(4) {
+                        assert_eq!(map.read_get(&(k * 2)), Some(k));
+                    }
+                    let guard = map.scoped_read();
+                    assert!(guard.keys().zip(guard.keys().skip(1)).all(|(a, b)| a < b));
+                    rounds += 1;
+                }
+            })
+        })
+        .collect();
+
+    let writer = {
+        let map = Arc::clone(&map);
+        thread::spawn(move || {
+            for k in 0..1000u32 {
+                assert_eq!(map.write_insert(k * 2 + 1, k), None);
+            }
+            for k in (0..1000u32).step_by(2) {
+                assert_eq!(map.write_remove(&(k * 2 + 1)), Some(k));
+            }
+        })
+    };
+    writer.join().unwrap();
+    done.store(true, Ordering::Release);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    let map = Arc::into_inner(map).unwrap().into_inner();
+    assert_eq!(map.len(), 1500);
+    assert!(map
+        .range(..100)
+        .map(|(&k, _)| k)
+        .eq((0..100).filter(|k| k % 4 != 1)));
+}
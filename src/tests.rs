@@ -1,5 +1,41 @@
 use crate::RbTreeMap;
 
+/// Returns a xorshift generator of pseudo-random numbers for the tests. The sequence is fixed by `seed`, which must be non-zero.
+pub(crate) fn rng(seed: u32) -> impl FnMut() -> u32 {
+    assert!(seed != 0, "the seed must be non-zero");
+    let mut state = seed;
+    move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    }
+}
+
+/// A key ordered only by the first field, so that equal keys can be told apart by the second field.
+#[derive(Debug)]
+pub(crate) struct Tagged<T>(pub(crate) u32, pub(crate) T);
+
+impl<T> PartialEq for Tagged<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for Tagged<T> {}
+
+impl<T> PartialOrd for Tagged<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Tagged<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
 #[test]
 fn simple() {
     let mut tree = RbTreeMap::new();
@@ -37,3 +73,2058 @@ fn retain() {
     assert_eq!(tree.remove(&4), Some(()));
     assert_eq!(tree.remove(&5), None);
 }
+
+#[test]
+fn map_values_keeps_shape() {
+    use crate::node::{Color, Node};
+
+    fn shape<K: Copy, V>(root: Option<Node<K, V>>) -> Vec<(K, Color)> {
+        let mut shape = vec![];
+        let mut stack: Vec<_> = root.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            shape.push((*node.key(), node.color()));
+            stack.extend(node.right());
+            stack.extend(node.left());
+        }
+        shape
+    }
+
+    let tree: RbTreeMap<i32, i32> = (0..100).map(|x| (x * 7 % 100, x)).collect();
+    let expected: RbTreeMap<i32, String> = tree.iter().map(|(&k, v)| (k, v.to_string())).collect();
+    let tree_shape = shape(tree.root.inner());
+
+    let mut visited = vec![];
+    let mapped = tree.map_values(|v| {
+        visited.push(v);
+        v.to_string()
+    });
+    assert_eq!(shape(mapped.root.inner()), tree_shape);
+    assert_eq!(mapped.len(), 100);
+    assert_eq!(mapped, expected);
+    assert_eq!(visited, (0..100).map(|k| k * 43 % 100).collect::<Vec<_>>());
+}
+
+#[test]
+fn range_bounds() {
+    use std::{collections::BTreeMap, ops::Bound};
+
+    for len in 0..40 {
+        let tree: RbTreeMap<i32, ()> = (0..len).map(|k| (k, ())).collect();
+        let expected: BTreeMap<i32, ()> = (0..len).map(|k| (k, ())).collect();
+        for start in -1..=len {
+            for end in start..=len + 1 {
+                let bounds = |b| [Bound::Included(b), Bound::Excluded(b), Bound::Unbounded];
+                for start_bound in bounds(start) {
+                    for end_bound in bounds(end) {
+                        if let (Bound::Excluded(s), Bound::Excluded(e)) = (start_bound, end_bound) {
+                            if s == e {
+                                // `BTreeMap::range` panics on it
+                                continue;
+                            }
+                        }
+                        let range = (start_bound, end_bound);
+                        assert!(
+                            tree.range(range)
+                                .map(|(k, _)| *k)
+                                .eq(expected.range(range).map(|(k, _)| *k)),
+                            "forward {:?} in 0..{}",
+                            range,
+                            len,
+                        );
+                        assert!(
+                            tree.range(range)
+                                .rev()
+                                .map(|(k, _)| *k)
+                                .eq(expected.range(range).rev().map(|(k, _)| *k)),
+                            "backward {:?} in 0..{}",
+                            range,
+                            len,
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut range = tree.range(..);
+        let mut alternated = vec![];
+        while let Some((&front, _)) = range.next() {
+            alternated.push(front);
+            if let Some((&back, _)) = range.next_back() {
+                alternated.push(back);
+            }
+        }
+        alternated.sort_unstable();
+        assert!(alternated.into_iter().eq(0..len));
+        assert!(tree.into_iter().rev().map(|(k, _)| k).eq((0..len).rev()));
+    }
+}
+
+#[test]
+fn entry_ref_owns_key_only_on_insert() {
+    use std::{borrow::Borrow, cell::Cell};
+
+    thread_local! {
+        static OWNED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Name(String);
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    #[repr(transparent)]
+    struct NameRef(str);
+
+    impl NameRef {
+        fn new(s: &str) -> &Self {
+            // Safety: `NameRef` is a transparent wrapper of `str`.
+            unsafe { &*(s as *const str as *const NameRef) }
+        }
+    }
+
+    impl Borrow<NameRef> for Name {
+        fn borrow(&self) -> &NameRef {
+            NameRef::new(&self.0)
+        }
+    }
+
+    impl ToOwned for NameRef {
+        type Owned = Name;
+
+        fn to_owned(&self) -> Name {
+            OWNED.with(|owned| owned.set(owned.get() + 1));
+            Name(self.0.to_owned())
+        }
+    }
+
+    let mut map = RbTreeMap::new();
+    for name in ["alice", "bob", "alice", "carol", "alice", "bob"] {
+        *map.entry_ref(NameRef::new(name)).or_insert(0) += 1;
+    }
+    assert_eq!(OWNED.with(Cell::get), 3);
+    assert_eq!(map.get(NameRef::new("alice")), Some(&3));
+    assert_eq!(map.get(NameRef::new("bob")), Some(&2));
+    assert_eq!(map.get(NameRef::new("carol")), Some(&1));
+
+    let mut strings: RbTreeMap<String, i32> = RbTreeMap::new();
+    strings.insert("one".to_owned(), 1);
+    *strings.entry_ref("one").or_insert(0) += 10;
+    *strings.entry_ref("two").or_insert(2) += 20;
+    assert_eq!(strings["one"], 11);
+    assert_eq!(strings["two"], 22);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "the owned key must be equal to the borrowed key")]
+fn entry_ref_rejects_inconsistent_to_owned() {
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Shifty(u32);
+
+    impl Clone for Shifty {
+        fn clone(&self) -> Self {
+            Shifty(self.0 + 100)
+        }
+    }
+
+    let mut map: RbTreeMap<Shifty, ()> = (0..10).map(|n| (Shifty(n * 2), ())).collect();
+    map.entry_ref(&Shifty(5)).or_insert(());
+}
+
+#[test]
+fn raw_entry_by_tuple_prefix() {
+    use crate::map::raw_entry::RawEntryMut;
+
+    let mut map: RbTreeMap<(u32, String), i32> = (0..20)
+        .map(|n| ((n * 2, format!("item{n}")), n as i32))
+        .collect();
+
+    let found = map.raw_entry().search_by(|(id, _)| id.cmp(&14));
+    assert_eq!(found, Some((&(14, "item7".to_owned()), &7)));
+    assert_eq!(map.raw_entry().search_by(|(id, _)| id.cmp(&15)), None);
+
+    match map.raw_entry_mut().from_key_by(|(id, _)| id.cmp(&14)) {
+        RawEntryMut::Occupied(mut o) => *o.get_mut() += 100,
+        RawEntryMut::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(map[&(14, "item7".to_owned())], 107);
+
+    let (key, value) = map
+        .raw_entry_mut()
+        .from_key_by(|(id, _)| id.cmp(&15))
+        .or_insert((15, "odd".to_owned()), -1);
+    assert_eq!((key, *value), (&(15, "odd".to_owned()), -1));
+    assert_eq!(map.len(), 21);
+    assert!(map.keys().map(|(id, _)| *id).is_sorted());
+
+    if let RawEntryMut::Occupied(o) = map.raw_entry_mut().from_key_by(|(id, _)| id.cmp(&0)) {
+        assert_eq!(o.remove_entry(), ((0, "item0".to_owned()), 0));
+    }
+    assert_eq!(map.len(), 20);
+}
+
+#[test]
+fn nth_matches_repeated_next() {
+    let mut map: RbTreeMap<i32, i32> = (0..50).map(|x| (x, x * 10)).collect();
+
+    for k in 0..55 {
+        let mut skipped = map.iter();
+        let mut stepped = map.iter();
+        let got = skipped.nth(k);
+        for _ in 0..k {
+            stepped.next();
+        }
+        assert_eq!(got, stepped.next());
+        assert_eq!(skipped.len(), stepped.len());
+        assert!(skipped.eq(stepped));
+
+        let mut skipped = map.iter();
+        let mut stepped = map.iter();
+        let got = skipped.nth_back(k);
+        for _ in 0..k {
+            stepped.next_back();
+        }
+        assert_eq!(got, stepped.next_back());
+        assert!(skipped.eq(stepped));
+
+        let mut skipped = map.range(10..40);
+        let mut stepped = map.range(10..40);
+        let got = skipped.nth(k);
+        for _ in 0..k {
+            stepped.next();
+        }
+        assert_eq!(got, stepped.next());
+        assert!(skipped.eq(stepped));
+
+        let mut skipped = map.range(10..40);
+        let mut stepped = map.range(10..40);
+        let got = skipped.nth_back(k);
+        for _ in 0..k {
+            stepped.next_back();
+        }
+        assert_eq!(got, stepped.next_back());
+        assert!(skipped.eq(stepped));
+    }
+
+    // paginate from both ends alternately
+    let mut iter = map.range(..);
+    assert_eq!(iter.nth(3), Some((&3, &30)));
+    assert_eq!(iter.nth_back(3), Some((&46, &460)));
+    assert_eq!(iter.nth(40), Some((&44, &440)));
+    assert_eq!(iter.nth(1), None);
+    assert_eq!(iter.next(), None);
+
+    let mut iter = map.iter_mut();
+    *iter.nth(5).unwrap().1 += 1;
+    *iter.nth_back(5).unwrap().1 += 1;
+    assert_eq!(iter.len(), 38);
+    *map.range_mut(20..).nth(2).unwrap().1 += 1;
+    *map.range_mut(..20).nth_back(2).unwrap().1 += 1;
+    assert_eq!(map[&5], 51);
+    assert_eq!(map[&44], 441);
+    assert_eq!(map[&22], 221);
+    assert_eq!(map[&17], 171);
+}
+
+#[test]
+fn count_uses_length() {
+    use std::{cell::Cell, rc::Rc};
+
+    let mut map: RbTreeMap<i32, i32> = (0..30).map(|x| (x, x)).collect();
+    let mut iter = map.iter();
+    iter.next();
+    iter.next_back();
+    assert_eq!(iter.len(), 28);
+    assert_eq!(iter.count(), 28);
+    assert_eq!(map.keys().count(), map.len());
+    assert_eq!(map.values().count(), map.len());
+    assert_eq!(map.values_mut().count(), 30);
+    assert_eq!(map.iter_mut().skip(5).count(), 25);
+    assert_eq!(map.range(3..8).count(), 5);
+
+    struct Tracked(Rc<Cell<usize>>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let dropped = Rc::new(Cell::new(0));
+    let map: RbTreeMap<_, _> = (0..10).map(|x| (x, Tracked(dropped.clone()))).collect();
+    let mut iter = map.into_iter();
+    drop(iter.next());
+    drop(iter.next_back());
+    assert_eq!(dropped.get(), 2);
+    assert_eq!(iter.len(), 8);
+    assert_eq!(iter.count(), 8);
+    assert_eq!(dropped.get(), 10);
+
+    let map: RbTreeMap<_, _> = (0..10).map(|x| (x, Tracked(dropped.clone()))).collect();
+    assert_eq!(map.into_values().count(), 10);
+    assert_eq!(dropped.get(), 20);
+}
+
+#[test]
+fn range_count_remaining() {
+    use crate::RbTreeSet;
+
+    let set: RbTreeSet<i32> = (0..20).collect();
+    assert_eq!(set.range(3..8).count_remaining(), set.range(3..8).count());
+    assert_eq!(set.range(3..8).count_remaining(), 5);
+    assert_eq!(set.range(30..).count_remaining(), 0);
+
+    let mut range = set.range(..=10);
+    assert_eq!(range.count_remaining(), 11);
+    range.next();
+    range.next_back();
+    assert_eq!(range.count_remaining(), 9);
+    assert_eq!(range.size_hint(), (2, None));
+    assert_eq!(range.collect::<Vec<_>>().len(), 9);
+
+    // the bounds are exact only for the empty and single ranges
+    assert_eq!(set.range(30..).size_hint(), (0, Some(0)));
+    assert_eq!(set.range(4..5).size_hint(), (1, Some(1)));
+    let mut range = set.range(4..6);
+    range.next();
+    assert_eq!(range.size_hint(), (1, Some(1)));
+    range.next_back();
+    assert_eq!(range.size_hint(), (0, Some(0)));
+
+    let mut map: RbTreeMap<i32, ()> = (0..20).map(|x| (x, ())).collect();
+    assert_eq!(map.range(5..).count_remaining(), 15);
+    assert_eq!(map.range_mut(..5).count_remaining(), 5);
+}
+
+#[test]
+fn insert_replaces_key() {
+    let mut map = RbTreeMap::new();
+    map.insert(Tagged(1, 1), "old");
+    let (old_key, old_value) = map.insert(Tagged(1, 2), "new").unwrap();
+    assert_eq!((old_key.1, old_value), (1, "old"));
+
+    let (key, value) = map.first().unwrap();
+    assert_eq!((key.1, *value), (2, "new"));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn retain_count() {
+    use crate::RbTreeSet;
+
+    let mut map: RbTreeMap<i32, i32> = (0..10).map(|x| (x, x)).collect();
+    assert_eq!(map.retain_count(|&k, _| k % 2 == 0), 5);
+    assert!(map.keys().copied().eq((0..10).step_by(2)));
+    assert_eq!(map.retain_count(|_, _| true), 0);
+    assert_eq!(map.retain_count(|_, _| false), 5);
+    assert!(map.is_empty());
+
+    let mut set: RbTreeSet<i32> = (0..10).collect();
+    assert_eq!(set.retain_count(|&k| k % 2 == 0), 5);
+    assert!(set.iter().copied().eq((0..10).step_by(2)));
+}
+
+#[test]
+fn join() {
+    fn check(left: std::ops::Range<i32>, right: std::ops::Range<i32>) {
+        let mut map: RbTreeMap<i32, i32> = left.clone().map(|x| (x, x)).collect();
+        let other: RbTreeMap<i32, i32> = right.clone().map(|x| (x, x)).collect();
+        map.join(other);
+
+        assert_eq!(map.len(), left.len() + right.len());
+        assert!(map.keys().copied().eq(left.chain(right)));
+        if let Some(root) = map.root.inner() {
+            assert!(root.parent().is_none());
+            root.assert_tree(&Some(root));
+        }
+    }
+
+    check(0..1000, 1000..2000);
+    check(0..1000, 1000..1003);
+    check(0..3, 3..1000);
+    check(0..0, 0..100);
+    check(0..100, 100..100);
+    check(0..0, 0..0);
+    for split in 0..64 {
+        check(0..split, split..64);
+    }
+}
+
+#[test]
+fn split_and_join() {
+    fn check_tree(map: &RbTreeMap<i32, i32>) {
+        if let Some(root) = map.root.inner() {
+            assert!(root.parent().is_none());
+            root.assert_tree(&Some(root));
+        }
+    }
+
+    let keys: Vec<i32> = (0..200).map(|x| x * 2).collect();
+    let build = || -> RbTreeMap<i32, i32> { keys.iter().map(|&k| (k, -k)).collect() };
+    let original = build();
+    for at in -1..=400 {
+        let (less, found, greater) = build().split(&at);
+        check_tree(&less);
+        check_tree(&greater);
+        assert!(less
+            .keys()
+            .copied()
+            .eq(keys.iter().copied().filter(|&k| k < at)));
+        assert!(greater
+            .keys()
+            .copied()
+            .eq(keys.iter().copied().filter(|&k| k > at)));
+        assert_eq!(less.len(), less.iter().count());
+        assert_eq!(greater.len(), greater.iter().count());
+        assert_eq!(found, original.get(&at).copied());
+
+        let mut joined = less;
+        if let Some(value) = found {
+            joined.insert(at, value);
+        }
+        joined.join(greater);
+        check_tree(&joined);
+        assert_eq!(joined, original);
+
+        let mut front = build();
+        let back = front.split_off(&at);
+        check_tree(&front);
+        check_tree(&back);
+        assert!(front
+            .keys()
+            .copied()
+            .eq(keys.iter().copied().filter(|&k| k < at)));
+        assert!(back
+            .keys()
+            .copied()
+            .eq(keys.iter().copied().filter(|&k| k >= at)));
+        front.join(back);
+        assert_eq!(front, original);
+    }
+}
+
+#[test]
+fn extend_sorted() {
+    use std::collections::BTreeMap;
+
+    let mut map: RbTreeMap<u32, u32> = (0..1000).step_by(3).map(|k| (k, 0)).collect();
+    let mut expected: BTreeMap<u32, u32> = (0..1000).step_by(3).map(|k| (k, 0)).collect();
+    for (round, batch) in [(500..1500), (0..10), (2000..2100), (0..3000)]
+        .into_iter()
+        .enumerate()
+    {
+        let batch: Vec<_> = batch.step_by(2).map(|k| (k, round as u32 + 1)).collect();
+        map.extend_sorted(batch.iter().copied());
+        expected.extend(batch);
+        assert!(map.iter().eq(expected.iter()));
+        assert_eq!(map.len(), expected.len());
+        if let Some(root) = map.root.inner() {
+            root.assert_tree(&Some(root));
+        }
+    }
+
+    // the later one of equal keys wins.
+    let mut map = RbTreeMap::new();
+    map.extend_sorted([(1, "a"), (1, "b"), (2, "c")]);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&1], "b");
+}
+
+#[test]
+fn eq_with_btree() {
+    use crate::RbTreeSet;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    let map: RbTreeMap<i32, char> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    let mut expected: BTreeMap<i32, char> = [(1, 'a'), (2, 'b'), (3, 'c')].into_iter().collect();
+    assert_eq!(map, expected);
+    assert_eq!(expected, map);
+    expected.insert(2, 'x');
+    assert_ne!(map, expected);
+    assert_ne!(expected, map);
+    expected.remove(&2);
+    assert_ne!(map, expected);
+
+    let set: RbTreeSet<i32> = (0..10).collect();
+    let mut expected: BTreeSet<i32> = (0..10).collect();
+    assert_eq!(set, expected);
+    assert_eq!(expected, set);
+    expected.insert(10);
+    assert_ne!(set, expected);
+    assert_ne!(expected, set);
+}
+
+#[test]
+fn cursor_insert_in_order() {
+    use std::ops::Bound;
+
+    let mut map = RbTreeMap::new();
+    let mut cursor = map.lower_bound_mut(Bound::Unbounded);
+    for k in 0..500 {
+        cursor.insert_after(k, k * 2);
+        assert_eq!(cursor.next(), Some((&k, &mut (k * 2))));
+        assert_eq!(cursor.peek_next(), None);
+    }
+    let mut expected = RbTreeMap::new();
+    expected.extend_sorted((0..500).map(|k| (k, k * 2)));
+    assert_eq!(map, expected);
+    if let Some(root) = map.root.inner() {
+        root.assert_tree(&Some(root));
+    }
+
+    // fill the gaps between the even keys from the back.
+    let mut map: RbTreeMap<i32, ()> = (0..100).step_by(2).map(|k| (k, ())).collect();
+    let mut cursor = map.upper_bound_mut(Bound::Unbounded);
+    while let Some((&k, _)) = cursor.prev() {
+        if k > 0 {
+            cursor.insert_before(k - 1, ());
+            assert_eq!(cursor.peek_prev(), Some((&(k - 1), &mut ())));
+            cursor.prev();
+        }
+    }
+    assert!(map.keys().copied().eq(0..99));
+    if let Some(root) = map.root.inner() {
+        root.assert_tree(&Some(root));
+    }
+}
+
+#[test]
+fn rev_view() {
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    let map: RbTreeMap<i32, i32> = (0..50).map(|x| (x * 2, x)).collect();
+    let rev = map.rev();
+    assert!(rev.iter().eq(map.iter().rev()));
+    assert!(rev.keys().eq(map.keys().rev()));
+    assert!(rev.values().eq(map.values().rev()));
+    assert_eq!(rev.first(), map.last());
+    assert_eq!(rev.last(), map.first());
+    assert_eq!(rev.len(), map.len());
+
+    for (start, end) in [(60, 20), (61, 19), (20, 60), (98, 0), (200, -5)] {
+        let expected: Vec<_> = map.range(end + 1..=start).rev().collect();
+        assert!(rev.range(start..end).eq(expected));
+        let expected: Vec<_> = map.range(end..start).rev().collect();
+        assert!(rev.range((Excluded(&start), Included(&end))).eq(expected));
+    }
+    assert!(rev.range::<i32, _>(..).eq(map.iter().rev()));
+    assert_eq!(rev.range((Excluded(&10), Unbounded)).next(), Some((&8, &4)));
+}
+
+#[test]
+fn clone_values_mid_traversal() {
+    use crate::RbTreeSet;
+
+    let map: RbTreeMap<i32, i32> = (0..10).map(|x| (x, x * 10)).collect();
+    let mut values = map.values();
+    assert_eq!(values.next(), Some(&0));
+    assert_eq!(values.next_back(), Some(&90));
+
+    let mut cloned = values.clone();
+    assert_eq!(cloned.len(), 8);
+    assert!(cloned.by_ref().copied().eq((1..9).map(|x| x * 10)));
+    assert_eq!(cloned.next(), None);
+    assert_eq!(values.len(), 8);
+    assert!(values.copied().eq((1..9).map(|x| x * 10)));
+
+    let set: RbTreeSet<i32> = (0..10).collect();
+    let mut range = set.range(2..8);
+    assert_eq!(range.next(), Some(&2));
+    let cloned = range.clone();
+    assert!(cloned.copied().eq(3..8));
+    assert!(range.copied().eq(3..8));
+}
+
+#[test]
+fn set_get_disjoint() {
+    use crate::RbTreeSet;
+
+    let set: RbTreeSet<String> = ["a", "b", "c"].into_iter().map(String::from).collect();
+    let [c, a] = set.get_disjoint(["c", "a"]).unwrap();
+    assert_eq!((c.as_str(), a.as_str()), ("c", "a"));
+    assert!(std::ptr::eq(a, set.get("a").unwrap()));
+    assert_eq!(set.get_disjoint::<str, 0>([]), Some([]));
+    assert_eq!(set.get_disjoint(["a", "d"]), None);
+    assert_eq!(set.get_disjoint(["a", "b", "a"]), None);
+    assert_eq!(set.get_disjoint(["b", "b"]), None);
+}
+
+#[test]
+fn extend_ascending_batch() {
+    use std::collections::BTreeMap;
+
+    let mut map = RbTreeMap::new();
+    let mut expected = BTreeMap::new();
+    let batches: [Vec<(i32, i32)>; 6] = [
+        (0..100).map(|k| (k, k)).collect(),
+        (100..1000).map(|k| (k, k)).collect(),
+        // interleaving with the map
+        (50..150).map(|k| (k * 10, -k)).collect(),
+        // not strictly ascending
+        vec![(2000, 1), (2000, 2), (2001, 3)],
+        vec![],
+        (3000..3003).rev().map(|k| (k, k)).collect(),
+    ];
+    for batch in batches {
+        map.extend(batch.iter().copied());
+        expected.extend(batch);
+        assert_eq!(map, expected);
+        if let Some(root) = map.root.inner() {
+            root.assert_tree(&Some(root));
+        }
+    }
+
+    let mut map: RbTreeMap<i32, i32> = (0..10).map(|k| (k, k)).collect();
+    map.extend([(10, 10), (11, 11)].iter().map(|(k, v)| (k, v)));
+    assert!(map.keys().copied().eq(0..12));
+}
+
+#[test]
+fn range_values_mut() {
+    let mut map: RbTreeMap<i32, i32> = (0..10).map(|x| (x, x)).collect();
+    for value in map.range_values_mut(3..7) {
+        *value *= 100;
+    }
+    let expected = (0..10).map(|x| if (3..7).contains(&x) { x * 100 } else { x });
+    assert!(map.values().copied().eq(expected));
+
+    assert_eq!(map.range_values_mut(20..).next(), None);
+    assert_eq!(map.range_values_mut(..=1).next_back(), Some(&mut 1));
+}
+
+#[test]
+fn set_nth_by_walk() {
+    use crate::RbTreeSet;
+
+    let set: RbTreeSet<i32> = [7, -3, 12, 0, 5, 9].into_iter().collect();
+    assert_eq!(set.nth_by_walk(0), Some(&-3));
+    assert_eq!(set.nth_by_walk(set.len() - 1), Some(&12));
+    for (i, elem) in set.iter().enumerate() {
+        assert_eq!(set.nth_by_walk(i), Some(elem));
+    }
+    assert_eq!(set.nth_by_walk(set.len()), None);
+    assert_eq!(RbTreeSet::<i32>::new().nth_by_walk(0), None);
+}
+
+#[test]
+fn set_contains_all_sorted() {
+    use crate::RbTreeSet;
+
+    let set: RbTreeSet<u32> = (0..500u32).map(|i| i.wrapping_mul(7919) % 1000).collect();
+    let mut rand = rng(12345);
+    for _ in 0..100 {
+        let mut batch: Vec<u32> = (0..20).map(|_| (rand() >> 16) % 1000).collect();
+        batch.sort_unstable();
+        let expected = batch.iter().all(|q| set.contains(q));
+        assert_eq!(set.contains_all_sorted(batch.iter().copied()), expected);
+    }
+    // the batch taken from the set always hits
+    let hits: Vec<u32> = set.iter().copied().step_by(3).collect();
+    assert!(set.contains_all_sorted(hits));
+    assert!(!RbTreeSet::<u32>::new().contains_all_sorted([0]));
+}
+
+#[test]
+fn visit_in_order() {
+    let mut map: RbTreeMap<i32, i32> = [5, 3, 8, 1, 4, 7, 9]
+        .into_iter()
+        .map(|k| (k, k * 10))
+        .collect();
+    let mut visited = vec![];
+    map.visit_in_order(|&k, &v| visited.push((k, v)));
+    assert!(visited.iter().map(|(k, v)| (k, v)).eq(map.iter()));
+
+    map.visit_in_order_mut(|&k, v| *v += k);
+    assert!(map.iter().all(|(&k, &v)| v == k * 11));
+
+    RbTreeMap::<i32, i32>::new().visit_in_order(|_, _| panic!("the map is empty"));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "inconsistent Ord implementation")]
+fn reject_inconsistent_ord() {
+    // claims to be less than anything, so `a < b` and `b < a` at once.
+    #[derive(PartialEq, Eq)]
+    struct Broken(u32);
+
+    impl PartialOrd for Broken {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Broken {
+        fn cmp(&self, _: &Self) -> std::cmp::Ordering {
+            std::cmp::Ordering::Less
+        }
+    }
+
+    let mut map = RbTreeMap::new();
+    map.insert(Broken(0), ());
+    map.insert(Broken(1), ());
+}
+
+#[test]
+fn range_peek() {
+    let map: RbTreeMap<i32, i32> = (0..20).map(|k| (k, k * 10)).collect();
+
+    let mut range = map.range(5..15);
+    while let Some(peeked) = range.peek() {
+        assert_eq!(range.peek_back().map(|(k, _)| k >= peeked.0), Some(true));
+        assert_eq!(range.next(), Some(peeked));
+        if let Some(peeked) = range.peek_back() {
+            assert_eq!(range.next_back(), Some(peeked));
+        }
+    }
+    assert_eq!(range.peek(), None);
+    assert_eq!(range.peek_back(), None);
+    assert_eq!(range.next(), None);
+
+    assert_eq!(map.range(30..).peek(), None);
+    assert_eq!(map.range(7..7).peek_back(), None);
+}
+
+#[test]
+fn set_operation_lens() {
+    use crate::RbTreeSet;
+
+    let mut rand = rng(2024);
+    let mut random_set = |len: usize, range: u32| -> RbTreeSet<u32> {
+        (0..len).map(|_| (rand() >> 16) % range).collect()
+    };
+    // the sizes cover both of the stitching and the searching strategies.
+    for (a_len, b_len) in [(0, 10), (30, 30), (5, 200), (200, 5), (100, 60)] {
+        let a = random_set(a_len, 300);
+        let b = random_set(b_len, 300);
+        assert_eq!(a.intersection_len(&b), a.intersection(&b).count());
+        assert_eq!(a.union_len(&b), a.union(&b).count());
+        assert_eq!(a.difference_len(&b), a.difference(&b).count());
+        assert_eq!(
+            a.symmetric_difference_len(&b),
+            a.symmetric_difference(&b).count()
+        );
+    }
+}
+
+#[test]
+fn from_iter_duplicate_policy() {
+    let pairs = [(1, "a"), (1, "b")];
+
+    let last_wins: RbTreeMap<_, _> = pairs.into_iter().collect();
+    assert_eq!(last_wins.len(), 1);
+    assert_eq!(last_wins[&1], "b");
+
+    let first_wins = RbTreeMap::from_iter_first_wins(pairs);
+    assert_eq!(first_wins.len(), 1);
+    assert_eq!(first_wins[&1], "a");
+}
+
+#[test]
+fn set_range_with_string_bounds() {
+    use crate::RbTreeSet;
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    let set: RbTreeSet<String> = ["apple", "banana", "cherry", "date", "fig"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let expected = ["banana", "cherry"];
+
+    // owned bounds
+    assert!(set.range("b".to_string().."d".to_string()).eq(expected));
+    assert!(set
+        .range((Included("banana".to_string()), Excluded("date".to_string())))
+        .eq(expected));
+    // borrowed bounds of `String` and `str`
+    let (lo, hi) = ("b".to_string(), "d".to_string());
+    assert!(set
+        .range::<_, String>((Included(&lo), Excluded(&hi)))
+        .eq(expected));
+    assert!(set
+        .range::<_, str>((Included("b"), Excluded("d")))
+        .eq(expected));
+    assert!(set
+        .range::<_, str>((Excluded("cherry"), Unbounded))
+        .eq(["date", "fig"]));
+    assert!(set
+        .range::<_, str>((Unbounded, Included("banana")))
+        .eq(["apple", "banana"]));
+}
+
+#[test]
+fn fold_and_try_fold() {
+    use std::ops::ControlFlow;
+
+    let map: RbTreeMap<i32, i32> = (0..100).map(|k| (k, k)).collect();
+    assert_eq!(map.iter().fold(0, |sum, (_, &v)| sum + v), 4950);
+    assert_eq!(map.range(10..20).fold(0, |sum, (_, &v)| sum + v), 145);
+    assert_eq!(map.range(50..50).fold(0, |sum, (_, &v)| sum + v), 0);
+
+    // folds only the rest after advancing from the both sides
+    let mut iter = map.iter();
+    iter.next();
+    iter.next_back();
+    assert_eq!(iter.fold(0, |sum, (_, &v)| sum + v), 4950 - 99);
+    let mut iter = map.iter();
+    assert_eq!(iter.nth(200), None);
+    assert_eq!(iter.fold(0, |sum, (_, &v)| sum + v), 0);
+
+    // stops after the element which breaks
+    let mut iter = map.iter();
+    let found = iter.try_fold(0, |sum, (&k, &v)| {
+        if k == 10 {
+            ControlFlow::Break(sum)
+        } else {
+            ControlFlow::Continue(sum + v)
+        }
+    });
+    assert_eq!(found, ControlFlow::Break(45));
+    assert_eq!(iter.next(), Some((&11, &11)));
+    assert_eq!(iter.len(), 88);
+
+    let mut range = map.range(20..30);
+    let found = range.try_fold((), |(), (&k, _)| if k == 25 { Err(k) } else { Ok(()) });
+    assert_eq!(found, Err(25));
+    assert!(range.map(|(&k, _)| k).eq(26..30));
+}
+
+#[test]
+fn snapshot_is_unaffected() {
+    let mut map: RbTreeMap<u32, String> = (0..100).map(|k| (k, k.to_string())).collect();
+    let snapshot = map.snapshot();
+    if let Some(root) = snapshot.root.inner() {
+        root.assert_tree(&Some(root));
+    }
+
+    map.insert(5, "five".to_string());
+    map.retain(|&k, _| k % 2 == 0);
+    map.insert(1000, "thousand".to_string());
+
+    assert_eq!(snapshot.len(), 100);
+    assert!(snapshot
+        .into_iter()
+        .eq((0..100).map(|k| (k, k.to_string()))));
+    assert_eq!(map.len(), 51);
+}
+
+#[test]
+fn to_sorted_vec() {
+    use crate::RbTreeSet;
+
+    let keys = [42, 7, 19, 3, 88, 61, 25];
+    let map: RbTreeMap<i32, i32> = keys.iter().map(|&k| (k, -k)).collect();
+    let entries = map.to_sorted_vec();
+    assert_eq!(entries.len(), map.len());
+    assert!(entries.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert!(entries.iter().all(|&(&k, &v)| v == -k));
+
+    let set: RbTreeSet<i32> = keys.into_iter().collect();
+    let values = set.to_sorted_vec();
+    assert_eq!(values.len(), set.len());
+    assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(values.binary_search(&&19), Ok(2));
+
+    assert!(RbTreeMap::<i32, i32>::new().to_sorted_vec().is_empty());
+}
+
+#[test]
+fn set_partition() {
+    use crate::RbTreeSet;
+
+    let set: RbTreeSet<i32> = (0..8).collect();
+    let (evens, odds) = set.partition(|&x| x % 2 == 0);
+    assert_eq!(evens.len() + odds.len(), 8);
+    assert!(evens.iter().copied().eq([0, 2, 4, 6]));
+    assert!(odds.iter().copied().eq([1, 3, 5, 7]));
+    for part in [&evens, &odds] {
+        if let Some(root) = part.map.root.inner() {
+            root.assert_tree(&Some(root));
+        }
+    }
+
+    let (all, none) = evens.partition(|_| true);
+    assert_eq!(all.len(), 4);
+    assert!(none.is_empty());
+}
+
+#[test]
+fn select_random_is_uniform() {
+    let map: RbTreeMap<u32, ()> = (0..7).map(|k| (k, ())).collect();
+    let mut counts = [0usize; 7];
+    let mut rand = rng(31);
+    for _ in 0..7000 {
+        let (&k, _) = map.select_random((rand() >> 8) as usize).unwrap();
+        counts[k as usize] += 1;
+    }
+    // each of the 7 entries is expected 1000 times
+    assert!(
+        counts.iter().all(|&c| (850..1150).contains(&c)),
+        "{counts:?}"
+    );
+
+    // consecutive values visit every entry once
+    let picked: Vec<u32> = (0..7)
+        .map(|i| *map.select_random(i + 70).unwrap().0)
+        .collect();
+    assert_eq!(picked, [0, 1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+#[cfg(not(feature = "full-debug"))]
+fn debug_truncates_large_map() {
+    let small: RbTreeMap<u32, u32> = (0..100).map(|k| (k, k)).collect();
+    let small_debug = format!("{small:?}");
+    assert!(small_debug.starts_with("{0: 0, 1: 1, "));
+    assert!(small_debug.ends_with(", 99: 99}"));
+    assert!(!small_debug.contains('…'));
+
+    let mut large = RbTreeMap::new();
+    large.extend_sorted((0..1_000_000u32).map(|k| (k, k)));
+    let large_debug = format!("{large:?}");
+    assert_eq!(
+        large_debug,
+        "{0: 0, 1: 1, 2: 2, 3: 3, 4: 4, …: (1000000 total), \
+         999995: 999995, 999996: 999996, 999997: 999997, 999998: 999998, 999999: 999999}"
+    );
+    assert!(format!("{large:#?}").len() < 400);
+}
+
+#[test]
+fn entry_or_default() {
+    let mut groups: RbTreeMap<i32, Vec<i32>> = RbTreeMap::new();
+    for x in 0..20 {
+        groups.entry_or_default(x % 3).push(x);
+    }
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[&0], [0, 3, 6, 9, 12, 15, 18]);
+    assert_eq!(groups[&1], [1, 4, 7, 10, 13, 16, 19]);
+    assert_eq!(groups[&2], [2, 5, 8, 11, 14, 17]);
+
+    // the existing value is kept as is
+    groups.entry_or_default(1).clear();
+    assert!(groups[&1].is_empty());
+    assert_eq!(groups.len(), 3);
+}
+
+#[test]
+fn insert_checked_rejects_inconsistent_ord() {
+    use crate::OrderError;
+    use std::cmp::Ordering;
+
+    // claims to be less than anything, so `a < b` and `b < a` at once.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Broken(u32);
+
+    impl PartialOrd for Broken {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Broken {
+        fn cmp(&self, _: &Self) -> Ordering {
+            Ordering::Less
+        }
+    }
+
+    let mut map = RbTreeMap::new();
+    assert_eq!(map.insert_checked(Broken(0), "a"), Ok(None));
+    assert_eq!(map.insert_checked(Broken(1), "b"), Err(OrderError));
+    assert_eq!(map.len(), 1);
+
+    let mut map = RbTreeMap::new();
+    for k in [5, 3, 8, 1, 4] {
+        assert_eq!(map.insert_checked(k, k), Ok(None));
+    }
+    assert_eq!(map.insert_checked(4, 40), Ok(Some((4, 4))));
+    assert!(map.keys().copied().eq([1, 3, 4, 5, 8]));
+}
+
+#[test]
+fn zip_keys_classifies_entries() {
+    let a: RbTreeMap<i32, char> = [(1, 'a'), (3, 'c'), (5, 'e'), (6, 'f')]
+        .into_iter()
+        .collect();
+    let b: RbTreeMap<i32, u8> = [(2, 2), (3, 3), (6, 6), (9, 9)].into_iter().collect();
+
+    let (mut only_a, mut only_b, mut both) = (vec![], vec![], vec![]);
+    for entry in a.zip_keys(&b) {
+        match entry {
+            (&k, Some(&v), None) => only_a.push((k, v)),
+            (&k, None, Some(&w)) => only_b.push((k, w)),
+            (&k, Some(&v), Some(&w)) => both.push((k, v, w)),
+            (_, None, None) => unreachable!("one of the values must be present"),
+        }
+    }
+    assert_eq!(only_a, [(1, 'a'), (5, 'e')]);
+    assert_eq!(only_b, [(2, 2), (9, 9)]);
+    assert_eq!(both, [(3, 'c', 3), (6, 'f', 6)]);
+
+    assert!(a.zip_keys(&b).map(|(&k, _, _)| k).eq([1, 2, 3, 5, 6, 9]));
+    assert_eq!(a.zip_keys(&RbTreeMap::<i32, ()>::new()).count(), a.len());
+}
+
+#[test]
+fn map_difference() {
+    let a: RbTreeMap<i32, i32> = (0..10).map(|k| (k, k * 10)).collect();
+    let b: RbTreeMap<i32, ()> = (5..15).map(|k| (k, ())).collect();
+    assert!(a
+        .difference(&b)
+        .map(|(&k, &v)| (k, v))
+        .eq((0..5).map(|k| (k, k * 10))));
+    assert!(b.difference(&a).map(|(&k, _)| k).eq(10..15));
+    assert_eq!(a.difference(&a).count(), 0);
+    assert_eq!(a.difference(&RbTreeMap::<i32, ()>::new()).count(), 10);
+}
+
+#[test]
+fn map_diff() {
+    use crate::map::iter::Change;
+
+    let old: RbTreeMap<i32, &str> = [(1, "a"), (2, "b"), (4, "d"), (7, "g")]
+        .into_iter()
+        .collect();
+    let new: RbTreeMap<i32, i32> = [(0, 0), (2, 20), (5, 50), (7, 70)].into_iter().collect();
+
+    let diff: Vec<_> = old.diff(&new).collect();
+    assert_eq!(
+        diff,
+        [
+            (&0, Change::Added(&0)),
+            (&1, Change::Removed(&"a")),
+            (&2, Change::Common(&"b", &20)),
+            (&4, Change::Removed(&"d")),
+            (&5, Change::Added(&50)),
+            (&7, Change::Common(&"g", &70)),
+        ]
+    );
+    assert!(diff.windows(2).all(|pair| pair[0].0 < pair[1].0));
+}
+
+#[test]
+fn memory_footprint_grows_by_node() {
+    let mut map = RbTreeMap::<u64, u64>::new();
+    let empty = map.memory_footprint();
+    assert_eq!(empty, std::mem::size_of::<RbTreeMap<u64, u64>>());
+
+    map.extend_sorted((0..1000).map(|k| (k, k)));
+    // a node of `u64` pairs is 5 words with the packed color
+    let node_size = 5 * std::mem::size_of::<usize>();
+    assert_eq!(map.memory_footprint(), empty + 1000 * node_size);
+
+    map.retain(|&k, _| k < 10);
+    assert_eq!(map.memory_footprint(), empty + 10 * node_size);
+}
+
+#[test]
+fn entry_replace_key_updates_metadata() {
+    use crate::map::entry::Entry;
+
+    let mut map = RbTreeMap::new();
+    for id in 0..10 {
+        map.insert(Tagged(id, 0), id * 10);
+    }
+
+    if let Entry::Occupied(mut entry) = map.entry(Tagged(4, 1)) {
+        let old = entry.replace_key(Tagged(4, 1));
+        assert_eq!((old.0, old.1), (4, 0));
+        assert_eq!(entry.key().1, 1);
+        assert_eq!(entry.get(), &40);
+    } else {
+        panic!("the entry must be occupied");
+    }
+
+    assert_eq!(map.len(), 10);
+    assert!(map.keys().map(|k| k.0).eq(0..10));
+    assert!(map.keys().all(|k| k.1 == if k.0 == 4 { 1 } else { 0 }));
+    assert!(map.values().copied().eq((0..10).map(|id| id * 10)));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "the new key must be equal to the stored key")]
+fn entry_replace_key_rejects_unequal_key() {
+    use crate::map::entry::Entry;
+
+    let mut map: RbTreeMap<_, _> = (0..4).map(|k| (k, ())).collect();
+    if let Entry::Occupied(mut entry) = map.entry(2) {
+        entry.replace_key(3);
+    }
+}
+
+#[test]
+fn pop_first_with_next_matches_first_key() {
+    let mut map = RbTreeMap::new();
+    let mut rand = rng(42);
+    for _ in 0..200 {
+        let seed = rand();
+        map.insert(seed >> 16, seed);
+    }
+    let len = map.len();
+
+    let mut drained = vec![];
+    while let Some(((key, _), next)) = map.pop_first_with_next() {
+        let next = next.copied();
+        assert_eq!(next, map.first_key().copied());
+        assert!(next.is_none_or(|next| key < next));
+        drained.push(key);
+    }
+    assert_eq!(drained.len(), len);
+    assert!(drained.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn set_range_from_and_to() {
+    use crate::RbTreeSet;
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    let set: RbTreeSet<i32> = (0..20).step_by(2).collect();
+    for x in -1..21 {
+        for bound in [Included(&x), Excluded(&x), Unbounded] {
+            assert!(set.range_from(bound).eq(set.range((bound, Unbounded))));
+            assert!(set.range_to(bound).eq(set.range((Unbounded, bound))));
+        }
+    }
+
+    let words: RbTreeSet<String> = ["ant", "bee", "cat"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    assert!(words.range_from::<str>(Excluded("ant")).eq(["bee", "cat"]));
+    assert!(words.range_to::<str>(Included("bee")).eq(["ant", "bee"]));
+}
+
+#[test]
+fn smallest_and_largest_n() {
+    let mut map = RbTreeMap::new();
+    let mut rand = rng(7);
+    for _ in 0..100 {
+        let seed = rand();
+        map.insert(seed >> 20, seed);
+    }
+
+    assert_eq!(
+        map.smallest_n(3).collect::<Vec<_>>(),
+        map.iter().take(3).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        map.largest_n(3).collect::<Vec<_>>(),
+        map.iter().rev().take(3).collect::<Vec<_>>()
+    );
+    for n in [0, 1, map.len() - 1, map.len(), map.len() + 5] {
+        assert!(map.smallest_n(n).eq(map.iter().take(n)));
+        assert!(map.largest_n(n).eq(map.iter().rev().take(n)));
+    }
+    assert_eq!(RbTreeMap::<u32, u32>::new().smallest_n(3).count(), 0);
+}
+
+#[test]
+fn range_chunks_sizes() {
+    let map: RbTreeMap<i32, i32> = (0..20).map(|k| (k, k * k)).collect();
+
+    let chunks: Vec<_> = map.range_chunks(5..15, 3).collect();
+    assert_eq!(
+        chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+        [3, 3, 3, 1]
+    );
+    assert!(chunks.concat().into_iter().eq(map.range(5..15)));
+
+    assert_eq!(map.range_chunks(5..11, 3).count(), 2);
+    assert_eq!(map.range_chunks(30.., 3).count(), 0);
+    assert_eq!(map.range_chunks(.., 100).next().unwrap().len(), 20);
+
+    let mut whole = map.range_chunks(3.., usize::MAX);
+    assert!(whole.next().unwrap().into_iter().eq(map.range(3..)));
+    assert_eq!(whole.next(), None);
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be non-zero")]
+fn range_chunks_zero_size() {
+    let map: RbTreeMap<i32, i32> = (0..5).map(|k| (k, k)).collect();
+    let _ = map.range_chunks(.., 0);
+}
+
+#[test]
+fn remove_with_neighbors_on_dense_map() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..100).map(|k| (k, k * 2)));
+
+    for key in (1..99).step_by(2) {
+        assert_eq!(
+            map.remove_with_neighbors(&key),
+            Some((key * 2, Some(key - 1), Some(key + 1)))
+        );
+    }
+    assert_eq!(map.remove_with_neighbors(&0), Some((0, None, Some(2))));
+    assert_eq!(map.remove_with_neighbors(&99), Some((198, Some(98), None)));
+    assert_eq!(map.remove_with_neighbors(&1), None);
+    assert!(map.keys().copied().eq((2..99).step_by(2)));
+}
+
+#[test]
+fn hash_is_independent_of_insertion_order() {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    fn hash_of(map: &RbTreeMap<u32, u32>) -> (u64, u64) {
+        let (mut by_trait, mut stable) = (DefaultHasher::new(), DefaultHasher::new());
+        map.hash(&mut by_trait);
+        map.stable_hash(&mut stable);
+        (by_trait.finish(), stable.finish())
+    }
+
+    let mut keys = vec![];
+    let mut rand = rng(3);
+    for _ in 0..300 {
+        keys.push(rand() >> 8);
+    }
+
+    let forward: RbTreeMap<_, _> = keys.iter().map(|&k| (k, k ^ 1)).collect();
+    let backward: RbTreeMap<_, _> = keys.iter().rev().map(|&k| (k, k ^ 1)).collect();
+    let mut sorted = RbTreeMap::new();
+    sorted.extend_sorted(forward.iter().map(|(&k, &v)| (k, v)));
+
+    let expected = hash_of(&forward);
+    assert_eq!(expected.0, expected.1);
+    assert_eq!(hash_of(&backward), expected);
+    assert_eq!(hash_of(&sorted), expected);
+
+    let mut changed = forward;
+    changed.insert(keys[0], 0);
+    assert_ne!(hash_of(&changed), expected);
+}
+
+#[test]
+fn is_prefix_of_maps() {
+    let full: RbTreeMap<i32, char> = (0..10).zip('a'..).collect();
+    let prefix: RbTreeMap<i32, char> = (0..4).zip('a'..).collect();
+    let empty = RbTreeMap::new();
+
+    assert!(prefix.is_prefix_of(&full));
+    assert!(!full.is_prefix_of(&prefix));
+    assert!(full.is_prefix_of(&full));
+    assert!(empty.is_prefix_of(&full));
+    assert!(empty.is_prefix_of(&empty));
+    assert!(!full.is_prefix_of(&empty));
+
+    // diverges by a value mid-way
+    let mut diverged = prefix.snapshot();
+    diverged.insert(2, 'z');
+    assert!(!diverged.is_prefix_of(&full));
+
+    // diverges by a key mid-way
+    let mut skipped = prefix.snapshot();
+    skipped.remove(&2);
+    assert!(!skipped.is_prefix_of(&full));
+}
+
+#[test]
+fn from_sorted_slice_and_vec() {
+    let pairs: Vec<(u32, String)> = (0..1000).map(|k| (k * 3, k.to_string())).collect();
+
+    let from_slice = RbTreeMap::from_sorted_slice(&pairs);
+    assert_eq!(from_slice.len(), pairs.len());
+    assert!(from_slice.iter().eq(pairs.iter().map(|(k, v)| (k, v))));
+
+    let from_vec = RbTreeMap::from_sorted_vec(pairs.clone());
+    assert!(from_vec.iter().eq(from_slice.iter()));
+    assert_eq!(from_vec.get(&999), Some(&"333".to_string()));
+    assert_eq!(from_vec.get(&1000), None);
+
+    assert!(RbTreeMap::<u32, u32>::from_sorted_vec(vec![]).is_empty());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "the slice must be strictly ascending by key")]
+fn from_sorted_slice_rejects_unsorted() {
+    let _ = RbTreeMap::from_sorted_slice(&[(2, ()), (1, ())]);
+}
+
+#[test]
+fn key_runs_group_consecutive_keys() {
+    use crate::RbTreeSet;
+
+    let set: RbTreeSet<i32> = [-3, -2, 0, 1, 2, 3, 5, 9, 10].into_iter().collect();
+    assert!(set.runs().eq([-3..=-2, 0..=3, 5..=5, 9..=10]));
+    assert_eq!(set.runs().count(), 4);
+    assert_eq!(RbTreeSet::<i32>::new().runs().count(), 0);
+
+    // the runs are not merged across the maximum value
+    let edge: RbTreeMap<u8, ()> = [253, 254, 255].into_iter().map(|k| (k, ())).collect();
+    assert!(edge.key_runs().eq([253..=255]));
+
+    let chars: RbTreeMap<char, ()> = "abcxyz".chars().map(|c| (c, ())).collect();
+    assert!(chars.key_runs().eq(['a'..='c', 'x'..='z']));
+}
+
+#[test]
+fn find_map_range_stops_at_first_match() {
+    let map: RbTreeMap<i32, i32> = (0..100).map(|k| (k, k % 7)).collect();
+
+    let mut visited = vec![];
+    let found = map.find_map_range(10..50, |&k, &v| {
+        visited.push(k);
+        (v == 0).then_some(k * 10)
+    });
+    assert_eq!(found, Some(140));
+    assert_eq!(visited, (10..=14).collect::<Vec<_>>());
+
+    let mut calls = 0;
+    let none = map.find_map_range(15..20, |_, &v| {
+        calls += 1;
+        (v == 0).then_some(())
+    });
+    assert_eq!(none, None);
+    assert_eq!(calls, 5);
+    assert_eq!(map.find_map_range(200.., |&k, _| Some(k)), None);
+}
+
+#[test]
+fn set_operation_size_hints_bound_counts() {
+    use crate::RbTreeSet;
+
+    fn check<'a>(iter: impl Iterator<Item = &'a u32> + Clone) {
+        let mut iter = iter;
+        loop {
+            let (lower, upper) = iter.size_hint();
+            let count = iter.clone().count();
+            assert!(lower <= count, "{lower} > {count}");
+            assert!(
+                upper.is_none_or(|upper| count <= upper),
+                "{upper:?} < {count}"
+            );
+            if iter.next().is_none() {
+                break;
+            }
+        }
+    }
+
+    let mut rand = rng(11);
+    let mut random_set = |len: usize, max: u32| -> RbTreeSet<u32> {
+        let mut set = RbTreeSet::new();
+        for _ in 0..len {
+            set.insert((rand() >> 8) % max);
+        }
+        set
+    };
+    let sets = [
+        RbTreeSet::new(),
+        random_set(5, 40),
+        random_set(40, 40),
+        random_set(100, 400),
+        (100..110).collect(),
+        (39..50).collect(),
+    ];
+    for a in &sets {
+        for b in &sets {
+            check(a.difference(b));
+            check(a.intersection(b));
+            check(a.union(b));
+            check(a.symmetric_difference(b));
+
+            if let Ok(exact) = a.difference(b).into_exact() {
+                assert_eq!(exact.len(), a.difference(b).count());
+                assert!(exact.eq(a.difference(b)));
+            }
+            if let Ok(exact) = a.intersection(b).into_exact() {
+                assert_eq!(exact.len(), a.intersection(b).count());
+                assert!(exact.eq(a.intersection(b)));
+            }
+        }
+    }
+
+    // the surplus of the longer set is a lower bound of the symmetric difference
+    let (small, large): (RbTreeSet<u32>, RbTreeSet<u32>) = ((0..10).collect(), (0..30).collect());
+    assert_eq!(small.symmetric_difference(&large).size_hint().0, 20);
+    // the disjoint sets go through `self`
+    let disjoint: RbTreeSet<u32> = (50..60).collect();
+    assert_eq!(small.difference(&disjoint).size_hint(), (10, Some(10)));
+    assert_eq!(small.difference(&disjoint).into_exact().unwrap().len(), 10);
+}
+
+#[test]
+fn color_histogram_counts_all_nodes() {
+    let mut map = RbTreeMap::new();
+    assert_eq!(map.color_histogram(), (0, 0));
+
+    let mut rand = rng(5);
+    for _ in 0..500 {
+        map.insert((rand() >> 8) % 1000, ());
+        let (red, black) = map.color_histogram();
+        assert_eq!(red + black, map.len());
+    }
+    map.retain(|&k, _| k % 3 == 0);
+    let (red, black) = map.color_histogram();
+    assert_eq!(red + black, map.len());
+
+    // a perfect tree is built only of black nodes
+    let mut perfect = RbTreeMap::new();
+    perfect.extend_sorted((0..127).map(|k| (k, ())));
+    perfect.rebuild();
+    assert_eq!(perfect.color_histogram(), (0, 127));
+
+    map.clear();
+    assert_eq!(map.color_histogram(), (0, 0));
+}
+
+#[test]
+fn remove_all_matches_looped_remove() {
+    let mut keys = vec![];
+    let mut rand = rng(17);
+    for _ in 0..1200 {
+        // some of them are absent or duplicated
+        keys.push((rand() >> 8) % 6000);
+    }
+    let mut sorted = keys.clone();
+    sorted.sort_unstable();
+
+    let build = || {
+        let mut map = RbTreeMap::new();
+        map.extend_sorted((0..5000u32).map(|k| (k, k * 2)));
+        map
+    };
+    let mut expected = build();
+    let expected_count = keys
+        .iter()
+        .filter(|k| expected.remove(*k).is_some())
+        .count();
+
+    for batch in [&sorted, &keys] {
+        let mut map = build();
+        assert_eq!(map.remove_all(batch.iter().copied()), expected_count);
+        assert_eq!(map.len(), expected.len());
+        assert!(map.iter().eq(expected.iter()));
+        assert_eq!(map.get(&4999), expected.get(&4999));
+    }
+
+    // a few keys are removed one by one
+    let mut map = build();
+    assert_eq!(map.remove_all([10, 3, 10, 9999]), 2);
+    assert_eq!(map.len(), 4998);
+    assert!(!map.contains_key(&3) && !map.contains_key(&10));
+
+    assert_eq!(map.remove_all(0..5000), 4998);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn remove_all_survives_panicking_drop() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    struct Bomb(u32);
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            if self.0 == 13 {
+                panic!("boom");
+            }
+        }
+    }
+
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..100u32).map(|k| (k, Bomb(k))));
+
+    // enough keys to take the walking path
+    let result = catch_unwind(AssertUnwindSafe(|| map.remove_all(0..50)));
+    assert!(result.is_err());
+    if let Some(root) = map.root.inner() {
+        root.assert_tree(&Some(root));
+    }
+    assert_eq!(map.len(), 50);
+    assert!(map.keys().copied().eq(50..100));
+    assert!(map.values().all(|v| v.0 >= 50));
+}
+
+#[test]
+fn range_indices_map_to_positions() {
+    use std::ops::{
+        Bound::{self, Excluded, Included, Unbounded},
+        RangeBounds,
+    };
+
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..50).map(|k| (k * 2, k)));
+
+    for lo in -1..101 {
+        for hi in [lo + 1, lo + 2, lo + 7, 200] {
+            let ranges: [(Bound<i32>, Bound<i32>); 4] = [
+                (Included(lo), Excluded(hi)),
+                (Excluded(lo), Included(hi)),
+                (Unbounded, Excluded(hi)),
+                (Included(lo), Unbounded),
+            ];
+            for range in ranges {
+                let before_start = |k: &i32| match range.0 {
+                    Included(lo) => *k < lo,
+                    Excluded(lo) => *k <= lo,
+                    Unbounded => false,
+                };
+                let (start, count) = map.range_indices(range);
+                assert_eq!(start, map.keys().filter(|k| before_start(k)).count());
+                assert_eq!(count, map.range(range).count());
+
+                let mut positional = map.iter().skip(start);
+                for entry in map.range(range) {
+                    assert_eq!(positional.next(), Some(entry));
+                }
+                // just past the last entry in the range
+                assert_eq!(
+                    map.iter().nth(start + count),
+                    map.iter()
+                        .find(|(k, _)| !before_start(k) && !range.contains(*k))
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn extend_or_modify_accumulates_in_place() {
+    use std::rc::Rc;
+
+    let mut counts: RbTreeMap<u32, usize> = RbTreeMap::new();
+    let mut rand = rng(23);
+    let mut expected = [0usize; 50];
+    let mut words = vec![];
+    for _ in 0..1000 {
+        let word = (rand() >> 8) % 50;
+        expected[word as usize] += 1;
+        words.push(word);
+    }
+    counts.extend_or_modify(words.iter().map(|&w| (w, 1)), |n, m| *n += m);
+    counts.extend_or_modify([(0, 100)], |n, m| *n += m);
+    expected[0] += 100;
+    assert!(counts.iter().map(|(&k, &v)| (k as usize, v)).eq(expected
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, n)| n > 0)));
+
+    // the existing values are never moved out nor dropped
+    let first = Rc::new(());
+    let mut shared = RbTreeMap::new();
+    shared.insert(1, vec![Rc::clone(&first)]);
+    shared.extend_or_modify([(1, vec![Rc::new(())]), (2, vec![])], |v, w| v.extend(w));
+    assert!(Rc::ptr_eq(&shared[&1][0], &first));
+    assert_eq!(shared[&1].len(), 2);
+    assert!(shared[&2].is_empty());
+}
+
+#[test]
+fn adjacent_pairs_of_entries() {
+    let map: RbTreeMap<i32, char> = [(6, 'c'), (1, 'a'), (3, 'b')].into_iter().collect();
+    assert!(map
+        .adjacent_pairs()
+        .eq([((&1, &'a'), (&3, &'b')), ((&3, &'b'), (&6, &'c'))]));
+
+    let gaps: Vec<_> = map.adjacent_pairs().map(|((a, _), (b, _))| b - a).collect();
+    assert_eq!(gaps, [2, 3]);
+
+    let single: RbTreeMap<i32, ()> = [(1, ())].into_iter().collect();
+    assert_eq!(single.adjacent_pairs().count(), 0);
+    assert_eq!(RbTreeMap::<i32, ()>::new().adjacent_pairs().count(), 0);
+}
+
+#[test]
+fn first_gap_in_key_space() {
+    let map: RbTreeMap<u8, ()> = [1, 2, 3, 5, 250, 251, 252, 253, 254, 255]
+        .into_iter()
+        .map(|k| (k, ()))
+        .collect();
+    assert_eq!(map.first_gap(1), Some(4));
+    assert_eq!(map.first_gap(0), Some(0));
+    assert_eq!(map.first_gap(4), Some(4));
+    assert_eq!(map.first_gap(5), Some(6));
+    assert_eq!(map.first_gap(100), Some(100));
+    assert_eq!(map.first_gap(249), Some(249));
+    // no gap until the maximum value
+    assert_eq!(map.first_gap(250), None);
+
+    assert_eq!(RbTreeMap::<i64, ()>::new().first_gap(-3), Some(-3));
+}
+
+#[test]
+fn clone_from_varying_sources() {
+    use std::rc::Rc;
+
+    let mut target: RbTreeMap<u32, String> = RbTreeMap::new();
+    let mut rand = rng(31);
+    for round in 0..30 {
+        let len = (rand() >> 8) % 300;
+        let mut source = RbTreeMap::new();
+        source.extend_sorted((0..len).map(|k| (k * 3 + round, format!("{round}:{k}"))));
+
+        target.clone_from(&source);
+        assert_eq!(target.len(), source.len());
+        assert!(target.iter().eq(source.iter()));
+        assert_eq!(target.get(&(round + 3)), source.get(&(round + 3)));
+        assert_eq!(target.clone(), source);
+    }
+
+    // the surplus entries are dropped
+    let value = Rc::new(());
+    let mut target: RbTreeMap<u32, Rc<()>> = (0..10).map(|k| (k, Rc::clone(&value))).collect();
+    let source: RbTreeMap<u32, Rc<()>> = (5..8).map(|k| (k, Rc::clone(&value))).collect();
+    assert_eq!(Rc::strong_count(&value), 14);
+    target.clone_from(&source);
+    assert_eq!(Rc::strong_count(&value), 7);
+    assert!(target.keys().copied().eq(5..8));
+    target.insert(100, Rc::clone(&value));
+    assert_eq!(target.first_key(), Some(&5));
+}
+
+#[test]
+fn retain_while_breaks_early() {
+    use std::ops::ControlFlow;
+
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..200).map(|k| (k, k % 3)));
+
+    let mut visited = 0;
+    map.retain_while(|&k, v| {
+        visited += 1;
+        if k >= 100 {
+            return ControlFlow::Break(());
+        }
+        *v += 10;
+        ControlFlow::Continue(k % 2 == 0)
+    });
+    assert_eq!(visited, 101);
+    assert_eq!(map.len(), 150);
+    // the kept entries before the break are mutated
+    assert!(map
+        .range(..100)
+        .all(|(k, v)| k % 2 == 0 && *v == k % 3 + 10));
+    // all of the entries from the break are left untouched
+    assert!(map
+        .range(100..)
+        .map(|(&k, &v)| (k, v))
+        .eq((100..200).map(|k| (k, k % 3))));
+
+    map.retain_while(|_, _| ControlFlow::Continue(false));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn subtree_from_yields_descendants_in_order() {
+    use crate::node::Node;
+
+    fn collect(node: Option<Node<u32, u32>>, out: &mut Vec<u32>) {
+        if let Some(node) = node {
+            let (left, right) = node.children();
+            collect(left, out);
+            out.push(*node.key());
+            collect(right, out);
+        }
+    }
+
+    let mut map = RbTreeMap::new();
+    let mut rand = rng(7);
+    for _ in 0..500 {
+        let seed = rand();
+        map.insert(seed % 1000, seed);
+    }
+
+    for key in map.keys().copied().collect::<Vec<_>>() {
+        let subtree = map.subtree_from(&key).unwrap();
+        let mut expected = vec![];
+        if let Some(Ok(node)) = map.root.search(&key) {
+            collect(Some(node), &mut expected);
+        }
+        assert_eq!(subtree.root(), (&key, &map[&key]));
+        assert_eq!(subtree.len(), expected.len());
+        assert!(subtree.iter().map(|(&k, _)| k).eq(expected.iter().copied()));
+        assert!(subtree
+            .iter()
+            .rev()
+            .map(|(&k, _)| k)
+            .eq(expected.iter().rev().copied()));
+    }
+    assert!(map.subtree_from(&1000).is_none());
+}
+
+#[test]
+fn set_all_values_matches_iter_mut() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..1000u32).map(|k| (k, k)));
+    let mut expected = map.snapshot();
+
+    let mut order = vec![];
+    map.set_all_values(|&k, v| {
+        order.push(k);
+        *v = v.wrapping_mul(3) ^ k;
+    });
+    expected
+        .iter_mut()
+        .for_each(|(&k, v)| *v = v.wrapping_mul(3) ^ k);
+    assert!(order.iter().eq(map.keys()));
+    assert!(map.iter().eq(expected.iter()));
+}
+
+#[test]
+fn eq_sorted_slice_compares_entries() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..100u32).map(|k| (k, k * 2)));
+    let mut entries: Vec<_> = (0..100u32).map(|k| (k, k * 2)).collect();
+    assert!(map.eq_sorted_slice(&entries));
+
+    entries[50].1 += 1;
+    assert!(!map.eq_sorted_slice(&entries));
+    entries[50].1 -= 1;
+    entries.swap(10, 11);
+    assert!(!map.eq_sorted_slice(&entries));
+    entries.swap(10, 11);
+
+    assert!(!map.eq_sorted_slice(&entries[..99]));
+    entries.push((100, 200));
+    assert!(!map.eq_sorted_slice(&entries));
+
+    assert!(RbTreeMap::<u32, u32>::new().eq_sorted_slice(&[]));
+    assert!(!RbTreeMap::<u32, u32>::new().eq_sorted_slice(&[(0, 0)]));
+}
+
+#[test]
+fn rebalance_incremental_finishes() {
+    let mut map: RbTreeMap<_, _> = (0..300).map(|k| (k, k)).collect();
+    assert!(!map.rebalance_incremental(16));
+}
+
+#[test]
+fn vacant_entry_insert_with_derives_value_from_key() {
+    use crate::map::entry::Entry;
+
+    let mut map: RbTreeMap<String, usize> = RbTreeMap::new();
+    for word in ["pear", "fig", "banana", "fig", "kiwi"] {
+        match map.entry(word.to_owned()) {
+            Entry::Vacant(entry) => {
+                let value = entry.insert_with(|key| key.len());
+                assert_eq!(*value, word.len());
+            }
+            Entry::Occupied(mut entry) => *entry.get_mut() *= 10,
+        }
+    }
+    assert!(map.iter().map(|(k, &v)| (k.as_str(), v)).eq([
+        ("banana", 6),
+        ("fig", 30),
+        ("kiwi", 4),
+        ("pear", 4)
+    ]));
+}
+
+#[test]
+fn range_keys_both_directions() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..100u32).map(|k| (k * 2, k)));
+
+    let keys = map.range_keys(10..=20);
+    assert_eq!(keys.count_remaining(), 6);
+    assert!(keys.clone().copied().eq((10..=20).step_by(2)));
+    assert!(keys.rev().copied().eq((10..=20).rev().step_by(2)));
+
+    let mut keys = map.range_keys(11..19);
+    assert_eq!(keys.next(), Some(&12));
+    assert_eq!(keys.next_back(), Some(&18));
+    assert!(keys.copied().eq([14, 16]));
+
+    assert_eq!(map.range_keys(500..).next(), None);
+    assert_eq!(map.range_keys(..).count_remaining(), 100);
+}
+
+#[test]
+fn try_from_iter_rejects_duplicates() {
+    use crate::map::DuplicateKey;
+
+    assert_eq!(
+        RbTreeMap::try_from_iter([(1, "a"), (1, "b")]),
+        Err(DuplicateKey(1))
+    );
+    assert_eq!(
+        RbTreeMap::try_from_iter([(3, "c"), (1, "a"), (2, "b"), (1, "d"), (3, "e")]),
+        Err(DuplicateKey(1))
+    );
+    assert_eq!(DuplicateKey(7).to_string(), "duplicate key: 7");
+
+    let map = RbTreeMap::try_from_iter((0..200u32).rev().map(|k| (k, k * 2))).unwrap();
+    assert_eq!(map.len(), 200);
+    assert!(map
+        .iter()
+        .map(|(&k, &v)| (k, v))
+        .eq((0..200u32).map(|k| (k, k * 2))));
+    assert!(RbTreeMap::<u32, u32>::try_from_iter([]).unwrap().is_empty());
+}
+
+#[test]
+fn successor_and_predecessor_of_present_keys() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..100u32).map(|k| (k, k * 10)));
+
+    assert_eq!(map.successor_of(&5), Some((&6, &60)));
+    assert_eq!(map.predecessor_of(&5), Some((&4, &40)));
+    for k in 0..99 {
+        assert_eq!(map.successor_of(&k), Some((&(k + 1), &((k + 1) * 10))));
+        assert_eq!(map.predecessor_of(&(k + 1)), Some((&k, &(k * 10))));
+    }
+    assert_eq!(map.successor_of(&99), None);
+    assert_eq!(map.predecessor_of(&0), None);
+    assert_eq!(map.successor_of(&100), None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "map mutated during iteration")]
+fn iter_detects_insert_through_raw_path() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..10u32).map(|k| (k, k)));
+    let raw: *mut RbTreeMap<u32, u32> = &mut map;
+
+    // Safety: none, it emulates a bug of unsafe code which mutates the map while iterating.
+    let mut iter = unsafe { &*raw }.iter();
+    assert_eq!(iter.next(), Some((&0, &0)));
+    unsafe { (*raw).insert(100, 100) };
+    iter.next();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "map mutated during iteration")]
+fn range_detects_remove_through_raw_path() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..10u32).map(|k| (k, k)));
+    let raw: *mut RbTreeMap<u32, u32> = &mut map;
+
+    // Safety: none, it emulates a bug of unsafe code which mutates the map while iterating.
+    let mut range = unsafe { &*raw }.range(2..8);
+    assert_eq!(range.next_back(), Some((&7, &7)));
+    unsafe { (*raw).remove(&9) };
+    range.next_back();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn every_mutation_bumps_modifications() {
+    type Map = RbTreeMap<u32, u32>;
+    type Mutation = fn(&mut Map);
+    let mutations: [(&str, Mutation); 8] = [
+        ("insert replacing", |map| {
+            map.insert(3, 0);
+        }),
+        ("insert_checked replacing", |map| {
+            map.insert_checked(3, 0).unwrap();
+        }),
+        ("try_insert_alloc replacing", |map| {
+            map.try_insert_alloc(3, 0).unwrap();
+        }),
+        ("extend_sorted", |map| map.extend_sorted([(3, 0)])),
+        ("rebuild", Map::rebuild),
+        ("remove_all", |map| {
+            map.remove_all(0..10);
+        }),
+        ("drain_partition", |map| {
+            map.drain_partition(|&k, _| k % 2 == 0);
+        }),
+        ("clone_from", |map| {
+            map.clone_from(&(0..5).map(|k| (k, k)).collect())
+        }),
+    ];
+    for (name, mutate) in mutations {
+        let mut map: Map = (0..20).map(|k| (k, k)).collect();
+        let guard = map.root.modifications().guard();
+        mutate(&mut map);
+        let checked = std::panic::catch_unwind(|| guard.check());
+        assert!(checked.is_err(), "{name} must bump the modification count");
+        if let Some(root) = map.root.inner() {
+            root.assert_tree(&Some(root));
+        }
+    }
+}
+
+#[test]
+fn into_entries_sorted_is_exact() {
+    let mut map = RbTreeMap::new();
+    let mut rand = rng(11);
+    for _ in 0..300 {
+        let seed = rand();
+        map.insert(seed % 1000, seed);
+    }
+    let expected: Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+
+    let entries = map.into_entries_sorted();
+    assert_eq!(entries.capacity(), entries.len());
+    assert_eq!(entries, expected);
+    assert!(RbTreeMap::<u32, u32>::new()
+        .into_entries_sorted()
+        .is_empty());
+}
+
+#[test]
+fn clear_large_map_and_reuse() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..1_000_000u32).map(|k| (k, k)));
+    // the nodes are freed by walking, so it does not overflow the stack
+    map.clear();
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.first(), None);
+    assert_eq!(map.last(), None);
+
+    map.extend_sorted((0..1_000u32).map(|k| (k, k)));
+    map.clear_and_shrink();
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.first(), None);
+    assert_eq!(map.last(), None);
+    assert_eq!(map.memory_footprint(), std::mem::size_of_val(&map));
+
+    map.insert(3, 30);
+    map.insert(1, 10);
+    assert!(map.iter().eq([(&1, &10), (&3, &30)]));
+}
+
+#[test]
+fn search_by_first_tuple_element() {
+    let mut map = RbTreeMap::new();
+    map.extend_sorted((0..200u32).map(|n| ((n * 3, n % 7), n)));
+
+    for target in 0..600u32 {
+        let found = map.search_by(|&(n, _), _| n.cmp(&target));
+        if target % 3 == 0 {
+            let n = target / 3;
+            assert_eq!(found, Ok((&(target, n % 7), &n)));
+        } else {
+            assert_eq!(found, Err((target / 3 + 1) as usize));
+        }
+    }
+    assert_eq!(map.search_by(|&(n, _), _| n.cmp(&1000)), Err(200));
+    // the value can take part in the ordering
+    assert_eq!(map.search_by(|_, &v| v.cmp(&42)), Ok((&(126, 0), &42)));
+    assert_eq!(
+        RbTreeMap::<(u32, u32), u32>::new().search_by(|_, _| std::cmp::Ordering::Less),
+        Err(0)
+    );
+}
+
+#[test]
+fn drain_partition_splits_into_valid_maps() {
+    let mut map = RbTreeMap::new();
+    let mut rand = rng(5);
+    for _ in 0..500 {
+        let seed = rand();
+        map.insert(seed % 1000, seed);
+    }
+    let expected = map.snapshot();
+
+    let mut calls = 0;
+    let matched = map.drain_partition(|&k, v| {
+        calls += 1;
+        *v = v.wrapping_add(1);
+        k % 3 == 0
+    });
+    assert_eq!(calls, expected.len());
+    assert_eq!(matched.len() + map.len(), expected.len());
+    for (part, is_match) in [(&matched, true), (&map, false)] {
+        if let Some(root) = part.root.inner() {
+            root.assert_tree(&Some(root));
+        }
+        assert!(part.keys().all(|k| (k % 3 == 0) == is_match));
+        assert!(part.iter().all(|(k, &v)| expected[k].wrapping_add(1) == v));
+    }
+
+    let mut empty = RbTreeMap::<u32, u32>::new();
+    assert!(empty.drain_partition(|_, _| true).is_empty());
+    let all = map.drain_partition(|_, _| true);
+    assert!(map.is_empty());
+    assert!(all.keys().all(|k| k % 3 != 0));
+}
+
+#[test]
+fn try_append_rejects_overlaps() {
+    let mut evens = RbTreeMap::new();
+    evens.extend_sorted((0..100u32).step_by(2).map(|k| (k, k)));
+    let mut odds = RbTreeMap::new();
+    odds.extend_sorted((1..100u32).step_by(2).map(|k| (k, k)));
+
+    let mut overlapping = RbTreeMap::new();
+    overlapping.extend_sorted((90..110u32).map(|k| (k, 0)));
+    assert_eq!(
+        evens.try_append(&mut overlapping),
+        Err(vec![90, 92, 94, 96, 98])
+    );
+    assert_eq!(evens.len(), 50);
+    assert_eq!(overlapping.len(), 20);
+    assert!(evens.iter().all(|(k, v)| k == v));
+
+    assert_eq!(evens.try_append(&mut odds), Ok(()));
+    assert!(odds.is_empty());
+    assert!(evens
+        .iter()
+        .map(|(&k, &v)| (k, v))
+        .eq((0..100).map(|k| (k, k))));
+
+    let mut empty = RbTreeMap::new();
+    assert_eq!(empty.try_append(&mut evens), Ok(()));
+    assert_eq!(empty.len(), 100);
+}
+
+#[test]
+fn collect_large_unsorted_keeps_last_duplicates() {
+    let mut rand = rng(13);
+    let items: Vec<(u32, u32)> = (0..5000).map(|i| (rand() % 2000, i)).collect();
+
+    let mut expected = RbTreeMap::new();
+    for &(k, v) in &items {
+        expected.insert(k, v);
+    }
+    let collected: RbTreeMap<_, _> = items.iter().copied().collect();
+    if let Some(root) = collected.root.inner() {
+        root.assert_tree(&Some(root));
+    }
+    assert_eq!(collected, expected);
+
+    // an iterator without the size hint falls back to inserting one by one
+    let filtered: RbTreeMap<_, _> = items.iter().copied().filter(|_| true).collect();
+    assert_eq!(filtered, expected);
+}
+
+#[test]
+fn iter_from_starts_at_ceiling() {
+    let mut map: RbTreeMap<u32, char> = (1..=5).zip('a'..).collect();
+
+    assert!(map.iter_from(&3).eq([(&3, &'c'), (&4, &'d'), (&5, &'e')]));
+    assert!(map.iter_from(&0).map(|(&k, _)| k).eq(1..=5));
+    assert_eq!(map.iter_from(&6).next(), None);
+    assert_eq!(map.iter_from(&3).next_back(), Some((&5, &'e')));
+
+    map.remove(&3);
+    assert_eq!(map.iter_from(&3).next(), Some((&4, &'d')));
+    for (_, v) in map.iter_from_mut(&4) {
+        *v = v.to_ascii_uppercase();
+    }
+    assert!(map.values().eq(&['a', 'b', 'D', 'E']));
+
+    let words: RbTreeMap<String, ()> = ["ant", "bee", "cat"]
+        .map(|w| (w.to_owned(), ()))
+        .into_iter()
+        .collect();
+    assert!(words
+        .iter_from("b")
+        .map(|(k, _)| k.as_str())
+        .eq(["bee", "cat"]));
+}
+
+#[test]
+#[cfg(feature = "debug-asserts")]
+fn debug_tree_draws_colors_and_shape() {
+    let mut map = RbTreeMap::new();
+    for k in [4, 2, 6, 1, 3, 5, 7, 8] {
+        map.insert(k, ());
+    }
+    // 8 made its parent and uncle black, and its grandparent red
+    assert_eq!(
+        map.debug_tree(),
+        "[4]\n  [2]\n    (1)\n    (3)\n  (6)\n    [5]\n    [7]\n      -\n      (8)\n"
+    );
+    assert_eq!(RbTreeMap::<u32, ()>::new().debug_tree(), "");
+}
@@ -0,0 +1,71 @@
+use rb_tree::{AllocError, RbTreeMap};
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// The global allocator which fails after the budget of the current thread runs out.
+struct LimitedAlloc;
+
+thread_local! {
+    static BUDGET: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+unsafe impl GlobalAlloc for LimitedAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let exhausted = BUDGET
+            .try_with(|budget| match budget.get() {
+                Some(0) => true,
+                Some(n) => {
+                    budget.set(Some(n - 1));
+                    false
+                }
+                None => false,
+            })
+            .unwrap_or(false);
+        if exhausted {
+            return std::ptr::null_mut();
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: LimitedAlloc = LimitedAlloc;
+
+fn with_budget<T>(budget: usize, f: impl FnOnce() -> T) -> T {
+    BUDGET.with(|b| b.set(Some(budget)));
+    let ret = f();
+    BUDGET.with(|b| b.set(None));
+    ret
+}
+
+#[test]
+fn try_insert_alloc_fails_gracefully() {
+    let mut map = RbTreeMap::new();
+    let res = with_budget(5, || map.try_extend((0..10).map(|i| (i, i * 10))));
+    assert_eq!(res, Err(AllocError));
+    assert_eq!(map.len(), 5);
+    assert!(map
+        .iter()
+        .map(|(&k, &v)| (k, v))
+        .eq((0..5).map(|i| (i, i * 10))));
+
+    // replacing an existing entry needs no allocation
+    let res = with_budget(0, || map.try_insert_alloc(3, 300));
+    assert_eq!(res, Ok(Some((3, 30))));
+    let res = with_budget(0, || map.try_insert_alloc(20, 200));
+    assert_eq!(res, Err(AllocError));
+    assert_eq!(map.get(&20), None);
+
+    // the map is still usable after the failures
+    assert_eq!(map.try_extend((5..10).map(|i| (i, i * 10))), Ok(()));
+    map.remove(&0);
+    assert!(map.keys().copied().eq(1..10));
+    assert_eq!(map[&3], 300);
+}